@@ -1,10 +1,12 @@
 use itertools::Itertools;
+use log::warn;
 use serde_json::json;
 use sled::IVec;
 
 use crate::{
-    config::Config,
+    config::{Config, DEFAULT_INSTANCE},
     error::{Error, Result},
+    metrics::Metrics,
     name::Name,
     util::group_by_prefix,
 };
@@ -13,8 +15,11 @@ use std::{
     collections::{BTreeMap, HashSet},
     hash::Hash,
     marker::PhantomData,
+    path::Path,
     sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
 };
+use tokio::sync::Notify;
 
 fn read_i32(value: &IVec) -> Result<i32> {
     value
@@ -38,13 +43,28 @@ fn store_string(id: String) -> Result<IVec> {
     Ok(id.as_bytes().to_vec().into())
 }
 
+/// The ids of the entities existing in Uptime Kuma, see [AppDB::clean].
+#[derive(Debug, Default)]
+pub struct ExistingIds {
+    pub monitors: HashSet<i32>,
+    pub notifications: HashSet<i32>,
+    pub docker_hosts: HashSet<i32>,
+    pub tags: HashSet<i32>,
+    pub status_pages: HashSet<String>,
+    pub maintenances: HashSet<i32>,
+    pub proxies: HashSet<i32>,
+}
+
 pub struct AppDB {
     db: sled::Db,
+    prefix: String,
     monitors: DBTable<i32>,
     notifications: DBTable<i32>,
     docker_hosts: DBTable<i32>,
     tags: DBTable<i32>,
     status_pages: DBTable<String>,
+    maintenances: DBTable<i32>,
+    proxies: DBTable<i32>,
 }
 
 trait IDTable<T> {
@@ -139,15 +159,26 @@ impl TryFrom<DatabaseId> for String {
 }
 
 impl AppDB {
-    pub fn new(data_path: &str) -> Result<Self> {
-        let db = sled::open(format!("{}/autokuma.db", data_path))?;
+    /// Opens the tables of the given Uptime Kuma instance.
+    ///
+    /// The default instance uses the unprefixed tables, so existing databases keep working
+    /// when switching to multiple instances, all other instances are stored as `<instance>/<table>`.
+    pub fn new(db: &sled::Db, instance: &str) -> Result<Self> {
+        let prefix = match instance {
+            DEFAULT_INSTANCE => "".to_owned(),
+            instance => format!("{}/", instance),
+        };
+
         Ok(AppDB {
-            monitors: DBTable::new(&db, "monitors")?,
-            notifications: DBTable::new(&db, "notifications")?,
-            docker_hosts: DBTable::new(&db, "docker_hosts")?,
-            tags: DBTable::new(&db, "tags")?,
-            status_pages: DBTable::new(&db, "status_pages")?,
-            db: db,
+            monitors: DBTable::new(db, &format!("{}monitors", prefix))?,
+            notifications: DBTable::new(db, &format!("{}notifications", prefix))?,
+            docker_hosts: DBTable::new(db, &format!("{}docker_hosts", prefix))?,
+            tags: DBTable::new(db, &format!("{}tags", prefix))?,
+            status_pages: DBTable::new(db, &format!("{}status_pages", prefix))?,
+            maintenances: DBTable::new(db, &format!("{}maintenances", prefix))?,
+            proxies: DBTable::new(db, &format!("{}proxies", prefix))?,
+            db: db.clone(),
+            prefix,
         })
     }
 
@@ -175,11 +206,13 @@ impl AppDB {
 
     pub fn get_id<T: TryFrom<DatabaseId>>(&self, name: Name) -> Result<Option<T>> {
         let id = match &name {
-            Name::Monitor(name) => Self::get_value(&self.monitors, &name)?,
-            Name::Notification(name) => Self::get_value(&self.notifications, &name)?,
-            Name::DockerHost(name) => Self::get_value(&self.docker_hosts, &name)?,
-            Name::Tag(name) => Self::get_value(&self.tags, &name)?,
-            Name::StatusPage(name) => Self::get_value(&self.status_pages, &name)?,
+            Name::Monitor(name) => Self::get_value(&self.monitors, name)?,
+            Name::Notification(name) => Self::get_value(&self.notifications, name)?,
+            Name::DockerHost(name) => Self::get_value(&self.docker_hosts, name)?,
+            Name::Tag(name) => Self::get_value(&self.tags, name)?,
+            Name::StatusPage(name) => Self::get_value(&self.status_pages, name)?,
+            Name::Maintenance(name) => Self::get_value(&self.maintenances, name)?,
+            Name::Proxy(name) => Self::get_value(&self.proxies, name)?,
         };
 
         id.map(|id| T::try_from(id)).transpose().map_err(|_| {
@@ -213,6 +246,14 @@ impl AppDB {
                 .status_pages
                 .tree()
                 .insert(name, self.status_pages.store_id(id)?)?,
+            (Name::Maintenance(name), DatabaseId::I32(id)) => self
+                .maintenances
+                .tree()
+                .insert(name, self.maintenances.store_id(id)?)?,
+            (Name::Proxy(name), DatabaseId::I32(id)) => self
+                .proxies
+                .tree()
+                .insert(name, self.proxies.store_id(id)?)?,
             _ => Err(Error::InternalError(format!(
                 "Invalid key type {} for Name {}",
                 std::any::type_name::<T>(),
@@ -241,6 +282,8 @@ impl AppDB {
             Name::DockerHost(name) => (&self.docker_hosts.tree(), name),
             Name::Tag(name) => (&self.tags.tree(), name),
             Name::StatusPage(name) => (&self.status_pages.tree(), name),
+            Name::Maintenance(name) => (&self.maintenances.tree(), name),
+            Name::Proxy(name) => (&self.proxies.tree(), name),
         };
 
         tree.remove(name)?;
@@ -265,19 +308,15 @@ impl AppDB {
         Ok(())
     }
 
-    pub fn clean(
-        &self,
-        monitors: &HashSet<i32>,
-        notifications: &HashSet<i32>,
-        docker_hosts: &HashSet<i32>,
-        tags: &HashSet<i32>,
-        status_pages: &HashSet<String>,
-    ) -> Result<()> {
-        Self::clean_table(&self.monitors, monitors)?;
-        Self::clean_table(&self.notifications, notifications)?;
-        Self::clean_table(&self.docker_hosts, docker_hosts)?;
-        Self::clean_table(&self.tags, tags)?;
-        Self::clean_table(&self.status_pages, status_pages)?;
+    /// Remove the stored ids of all entities which no longer exist in Uptime Kuma.
+    pub fn clean(&self, existing: &ExistingIds) -> Result<()> {
+        Self::clean_table(&self.monitors, &existing.monitors)?;
+        Self::clean_table(&self.notifications, &existing.notifications)?;
+        Self::clean_table(&self.docker_hosts, &existing.docker_hosts)?;
+        Self::clean_table(&self.tags, &existing.tags)?;
+        Self::clean_table(&self.status_pages, &existing.status_pages)?;
+        Self::clean_table(&self.maintenances, &existing.maintenances)?;
+        Self::clean_table(&self.proxies, &existing.proxies)?;
 
         Ok(())
     }
@@ -302,25 +341,108 @@ impl AppDB {
         Self::get_entries(&self.status_pages)
     }
 
+    pub fn get_maintenances(&self) -> Result<Vec<(String, i32)>> {
+        Self::get_entries(&self.maintenances)
+    }
+
     pub fn get_version(&self) -> Result<i32> {
         Ok(self
             .db
-            .get("version")?
+            .get(format!("{}version", self.prefix))?
             .map(|value| read_i32(&value))
             .transpose()?
             .unwrap_or(0))
     }
 
     pub fn set_version(&self, version: i32) -> Result<()> {
-        self.db.insert("version", &version.to_le_bytes())?;
+        self.db
+            .insert(format!("{}version", self.prefix), &version.to_le_bytes())?;
         Ok(())
     }
 }
 
+/// Whether sled failed to acquire the exclusive lock on the database, i.e. another process is using it.
+fn is_lock_error(error: &sled::Error) -> bool {
+    matches!(error, sled::Error::Io(e) if e.to_string().contains("could not acquire lock"))
+}
+
+/// Whether the database can't be read because its content is damaged, as opposed to e.g. missing permissions.
+fn is_corruption_error(error: &sled::Error) -> bool {
+    match error {
+        sled::Error::Corruption { .. } => true,
+        sled::Error::Io(e) => matches!(
+            e.kind(),
+            std::io::ErrorKind::InvalidData | std::io::ErrorKind::UnexpectedEof
+        ),
+        _ => false,
+    }
+}
+
+/// Open the database, reporting locked or corrupt databases in a way the user can act upon.
+///
+/// sled holds an exclusive lock on the database while it is open, which prevents multiple AutoKuma instances from sharing it.
+/// If `reset_db` is set, a corrupt database is moved aside and replaced with an empty one, which the following import fills again.
+fn open_db(path: &Path, reset_db: bool) -> Result<sled::Db> {
+    let error = match sled::open(path) {
+        Ok(db) => return Ok(db),
+        Err(e) => e,
+    };
+
+    if is_lock_error(&error) {
+        return Err(Error::InternalError(format!(
+            "The database at {} is locked by another process, make sure only one AutoKuma instance uses this data path",
+            path.display()
+        )));
+    }
+
+    if !is_corruption_error(&error) {
+        return Err(Error::InternalError(format!(
+            "Unable to open the database at {}: {}",
+            path.display(),
+            error
+        )));
+    }
+
+    if !reset_db {
+        return Err(Error::InternalError(format!(
+            "The database at {} is corrupt ({}), start AutoKuma with --reset-db --import to back it up and adopt the existing entities into an empty database",
+            path.display(),
+            error
+        )));
+    }
+
+    let backup = path.with_extension(format!(
+        "db.corrupt-{}",
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs())
+    ));
+
+    std::fs::rename(path, &backup).map_err(|e| {
+        Error::IO(format!(
+            "Unable to move the corrupt database {} to {}: {}",
+            path.display(),
+            backup.display(),
+            e
+        ))
+    })?;
+
+    warn!(
+        "The database at {} was corrupt ({}), it has been moved to {} and a new database was created",
+        path.display(),
+        error,
+        backup.display()
+    );
+
+    Ok(sled::open(path)?)
+}
+
 pub struct AppState {
     pub config: Arc<Config>,
-    pub db: Arc<AppDB>,
+    dbs: BTreeMap<String, Arc<AppDB>>,
+    pub sync_trigger: Notify,
     defaults: BTreeMap<String, Vec<(String, String)>>,
+    pub metrics: Metrics,
 }
 
 impl AppState {
@@ -337,6 +459,14 @@ impl AppState {
             })
             .collect::<Result<Vec<_>>>()?;
 
+        // An empty database doesn't know any of the existing entities, without an import the next sync would create all of them again
+        if config.reset_db && !config.import {
+            return Err(Error::InvalidConfig(
+                "reset_db".to_owned(),
+                "--reset-db can only be used together with --import".to_owned(),
+            ));
+        }
+
         let data_path =
             config
                 .data_path
@@ -353,13 +483,43 @@ impl AppState {
                         .unwrap_or_else(|| "./".to_owned()),
                 });
 
+        let db = open_db(&Path::new(&data_path).join("autokuma.db"), config.reset_db)?;
+        let dbs = config
+            .kuma
+            .instances()
+            .into_iter()
+            .map(|(instance, _)| Ok((instance.clone(), Arc::new(AppDB::new(&db, &instance)?))))
+            .collect::<Result<BTreeMap<_, _>>>()?;
+
         Ok(Self {
-            db: Arc::new(AppDB::new(&data_path)?),
+            dbs,
             config: config.clone(),
+            sync_trigger: Notify::new(),
             defaults: group_by_prefix(defaults, "."),
+            metrics: Metrics::default(),
         })
     }
 
+    /// The database of the given Uptime Kuma instance, `None` if no such instance is configured.
+    pub fn db(&self, instance: &str) -> Option<Arc<AppDB>> {
+        self.dbs.get(instance).cloned()
+    }
+
+    /// Request a sync to be run as soon as possible instead of waiting for the next sync interval.
+    pub fn trigger_sync(&self) {
+        self.sync_trigger.notify_one();
+    }
+
+    /// Record an entity or instance which couldn't be synced, used to determine the exit code in one-shot mode.
+    pub fn record_failure(&self) {
+        self.metrics.record_failed();
+    }
+
+    /// The number of failures recorded since the start.
+    pub fn failures(&self) -> u64 {
+        self.metrics.failed()
+    }
+
     pub fn get_defaults(&self, monitor_type: impl AsRef<str>) -> Vec<(String, serde_json::Value)> {
         vec![
             self.defaults.get("*"),
@@ -380,3 +540,105 @@ impl AppState {
         .collect_vec()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temporary_db() -> AppDB {
+        let db = sled::Config::new()
+            .temporary(true)
+            .open()
+            .expect("Unable to open temporary database");
+
+        AppDB::new(&db, DEFAULT_INSTANCE).unwrap()
+    }
+
+    #[test]
+    fn only_corruption_allows_a_reset() {
+        assert!(is_corruption_error(&sled::Error::Corruption {
+            at: None,
+            bt: ()
+        }));
+        assert!(is_corruption_error(&sled::Error::Io(
+            std::io::ErrorKind::UnexpectedEof.into()
+        )));
+        assert!(!is_corruption_error(&sled::Error::ReportableBug(
+            "bug".to_owned()
+        )));
+        assert!(!is_corruption_error(&sled::Error::Unsupported(
+            "format".to_owned()
+        )));
+        assert!(!is_corruption_error(&sled::Error::Io(
+            std::io::ErrorKind::PermissionDenied.into()
+        )));
+    }
+
+    #[test]
+    fn reset_db_requires_an_import() {
+        let config = |values| Arc::new(crate::test_util::config(values));
+
+        assert!(matches!(
+            AppState::new(config(json!({"reset_db": true}))),
+            Err(Error::InvalidConfig(_, _))
+        ));
+        let data_path = tempfile::tempdir().unwrap();
+        assert!(AppState::new(config(json!({
+            "reset_db": true,
+            "import": true,
+            "data_path": data_path.path(),
+        })))
+        .is_ok());
+    }
+
+    #[test]
+    fn proxy_ids_are_stored_and_removed() {
+        let db = temporary_db();
+
+        db.store_id(Name::Proxy("proxy".to_owned()), 3).unwrap();
+        assert_eq!(
+            db.get_id::<i32>(Name::Proxy("proxy".to_owned())).unwrap(),
+            Some(3)
+        );
+        assert_eq!(
+            db.get_id::<i32>(Name::Maintenance("proxy".to_owned()))
+                .unwrap(),
+            None
+        );
+
+        db.remove_id(Name::Proxy("proxy".to_owned())).unwrap();
+        assert_eq!(
+            db.get_id::<i32>(Name::Proxy("proxy".to_owned())).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn clean_removes_proxy_ids_missing_from_uptime_kuma() {
+        let db = temporary_db();
+
+        db.store_id(Name::Proxy("kept".to_owned()), 1).unwrap();
+        db.store_id(Name::Proxy("deleted".to_owned()), 2).unwrap();
+
+        let existing = |proxies: &[i32]| ExistingIds {
+            proxies: HashSet::from_iter(proxies.iter().copied()),
+            ..Default::default()
+        };
+
+        db.clean(&existing(&[1])).unwrap();
+        assert_eq!(
+            db.get_id::<i32>(Name::Proxy("kept".to_owned())).unwrap(),
+            Some(1)
+        );
+        assert_eq!(
+            db.get_id::<i32>(Name::Proxy("deleted".to_owned())).unwrap(),
+            None
+        );
+
+        db.clean(&existing(&[])).unwrap();
+        assert_eq!(
+            db.get_id::<i32>(Name::Proxy("kept".to_owned())).unwrap(),
+            None
+        );
+    }
+}
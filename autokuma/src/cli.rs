@@ -0,0 +1,39 @@
+use clap::Parser;
+use kuma_client::build::{LONG_VERSION, SHORT_VERSION};
+
+#[derive(Parser, Clone, Debug)]
+#[command(author, version = SHORT_VERSION, long_version = LONG_VERSION, about, long_about = None)]
+pub(crate) struct Cli {
+    /// Load this config file instead of searching the default locations (can also be set using AUTOKUMA__CONFIG_FILE).
+    #[arg(long)]
+    pub config: Option<String>,
+
+    /// Only log the changes AutoKuma would make without applying them.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Run a single sync and exit, failing if any entity wasn't synced.
+    #[arg(long = "once")]
+    pub one_shot: bool,
+
+    /// Adopt the existing monitors, notifications and tags of Uptime Kuma and exit instead of syncing.
+    #[arg(long)]
+    pub import: bool,
+
+    /// Don't print the startup banner.
+    #[arg(long)]
+    pub quiet: bool,
+
+    /// Move a corrupt database aside and start with an empty one, requires --import.
+    #[arg(long)]
+    pub reset_db: bool,
+}
+
+impl Cli {
+    /// The config file replacing the default config file search, if any.
+    pub fn config_file(&self) -> Option<String> {
+        self.config
+            .clone()
+            .or_else(|| std::env::var("AUTOKUMA__CONFIG_FILE").ok())
+    }
+}
@@ -1,9 +1,22 @@
-use kuma_client::deserialize::DeserializeVecLenient;
-use serde::{Deserialize, Serialize};
+use crate::entity::EntityType;
+use kuma_client::{deserialize::DeserializeVecLenient, tag::TagDefinition};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_alias::serde_alias;
 use serde_inline_default::serde_inline_default;
-use serde_with::{formats::SemicolonSeparator, serde_as, PickFirst, StringWithSeparator};
-use std::collections::HashMap;
+use serde_with::{
+    formats::SemicolonSeparator, serde_as, DeserializeFromStr, PickFirst, SerializeDisplay,
+    StringWithSeparator,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::Infallible,
+    fmt::Display,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// Name of the instance used when only a single Uptime Kuma instance is configured.
+pub const DEFAULT_INSTANCE: &str = "default";
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum DockerSource {
@@ -15,6 +28,193 @@ pub enum DockerSource {
     Both,
 }
 
+#[serde_alias(ScreamingSnakeCase)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DockerTlsConfig {
+    /// Path to the CA certificate in PEM format.
+    pub ca: String,
+
+    /// Path to the client certificate in PEM format.
+    pub cert: String,
+
+    /// Path to the client key in PEM format.
+    pub key: String,
+}
+
+/// A Docker host, either given as a plain url (e.g. `unix:///var/run/docker.sock`) or as a table.
+#[serde_alias(ScreamingSnakeCase)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub struct DockerHostConfig {
+    /// The url of the Docker host, e.g. `unix:///var/run/docker.sock` or `tcp://10.0.0.2:2376`.
+    pub url: String,
+
+    /// Name of the Docker host, the ids of all entities created from this host will be prefixed with `<name>/`.
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// AutoKuma id of a docker host entity (or the id of an existing Uptime Kuma docker host) which will be used
+    /// for all docker monitors created from this host which don't specify a docker host themselves.
+    #[serde(default)]
+    pub docker_host: Option<String>,
+
+    /// TLS client certificates used to connect to the Docker host.
+    #[serde(default)]
+    pub tls: Option<DockerTlsConfig>,
+}
+
+impl<'de> Deserialize<'de> for DockerHostConfig {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match serde_json::Value::deserialize(deserializer)? {
+            serde_json::Value::String(url) => Ok(url.into()),
+            value => DockerHostConfig::deserialize(value).map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+impl Serialize for DockerHostConfig {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        DockerHostConfig::serialize(self, serializer)
+    }
+}
+
+impl DockerHostConfig {
+    /// The prefix of the entity ids created from this host, unnamed hosts keep the ids of their entities unchanged.
+    pub fn namespace(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+}
+
+impl From<String> for DockerHostConfig {
+    fn from(url: String) -> Self {
+        DockerHostConfig {
+            url,
+            ..Default::default()
+        }
+    }
+}
+
+impl FromStr for DockerHostConfig {
+    type Err = Infallible;
+
+    fn from_str(url: &str) -> Result<Self, Self::Err> {
+        Ok(url.to_owned().into())
+    }
+}
+
+impl Display for DockerHostConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
+
+/// A label selector, either `key` to match any container having the label or `key=value` to match a specific value.
+#[derive(Clone, Debug, PartialEq, DeserializeFromStr, SerializeDisplay)]
+pub struct LabelSelector {
+    pub key: String,
+    pub value: Option<String>,
+}
+
+impl LabelSelector {
+    pub fn matches(&self, labels: Option<&HashMap<String, String>>) -> bool {
+        labels
+            .and_then(|labels| labels.get(&self.key))
+            .is_some_and(|value| {
+                self.value
+                    .as_ref()
+                    .map_or(true, |expected| expected == value)
+            })
+    }
+}
+
+impl FromStr for LabelSelector {
+    type Err = Infallible;
+
+    fn from_str(selector: &str) -> Result<Self, Self::Err> {
+        Ok(match selector.split_once('=') {
+            Some((key, value)) => LabelSelector {
+                key: key.trim().to_owned(),
+                value: Some(value.trim().to_owned()),
+            },
+            None => LabelSelector {
+                key: selector.trim().to_owned(),
+                value: None,
+            },
+        })
+    }
+}
+
+impl Display for LabelSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.value {
+            Some(value) => write!(f, "{}={}", self.key, value),
+            None => write!(f, "{}", self.key),
+        }
+    }
+}
+
+/// Connection settings for Uptime Kuma, either a single instance or a map of named instances.
+#[derive(Clone, Debug, PartialEq)]
+pub enum KumaConfig {
+    Single(Box<kuma_client::Config>),
+    Multiple(BTreeMap<String, kuma_client::Config>),
+}
+
+impl KumaConfig {
+    /// All configured instances by name, a single instance is named [DEFAULT_INSTANCE].
+    pub fn instances(&self) -> Vec<(String, kuma_client::Config)> {
+        match self {
+            KumaConfig::Single(config) => vec![(DEFAULT_INSTANCE.to_owned(), (**config).clone())],
+            KumaConfig::Multiple(instances) => instances
+                .iter()
+                .map(|(name, config)| (name.clone(), config.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for KumaConfig {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer)?;
+
+        // A table containing an url (or nothing at all, to get a proper "missing field" error) is a single instance,
+        // anything else is treated as a map of named instances.
+        let is_single = value.as_object().is_some_and(|map| {
+            map.is_empty() || map.keys().any(|key| key.eq_ignore_ascii_case("url"))
+        });
+
+        match is_single {
+            true => kuma_client::Config::deserialize(value)
+                .map(|config| KumaConfig::Single(Box::new(config)))
+                .map_err(serde::de::Error::custom),
+            false => BTreeMap::<String, kuma_client::Config>::deserialize(value)
+                .map(KumaConfig::Multiple)
+                .map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+impl Serialize for KumaConfig {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            KumaConfig::Single(config) => config.serialize(serializer),
+            KumaConfig::Multiple(instances) => instances.serialize(serializer),
+        }
+    }
+}
+
 #[serde_alias(ScreamingSnakeCase)]
 #[serde_inline_default]
 #[serde_as]
@@ -24,16 +224,20 @@ pub struct DockerConfig {
     #[serde_inline_default(true)]
     pub enabled: bool,
 
+    /// The Uptime Kuma instance entities from this source are synced to (Defaults to the `default` instance).
+    #[serde_inline_default(None)]
+    pub instance: Option<String>,
+
     /// Path to the Docker socket. If not set, the DOCKER_HOST will be used.
     #[serde_inline_default(None)]
     pub socket_path: Option<String>,
 
     /// List of Docker hosts. If set this will override socker_path. Use a semicolon separated string when setting using an env variable.
     #[serde_as(
-        as = "Option<PickFirst<(DeserializeVecLenient<String>, StringWithSeparator::<SemicolonSeparator, String>)>>"
+        as = "Option<PickFirst<(DeserializeVecLenient<DockerHostConfig>, StringWithSeparator::<SemicolonSeparator, DockerHostConfig>)>>"
     )]
     #[serde(default)]
-    pub hosts: Option<Vec<String>>,
+    pub hosts: Option<Vec<DockerHostConfig>>,
 
     /// Whether monitors should be created from container or service labels (or both).
     #[serde_inline_default(DockerSource::Containers)]
@@ -42,6 +246,46 @@ pub struct DockerConfig {
     /// Prefix used when scanning for container labels.
     #[serde_inline_default("kuma".to_owned())]
     pub label_prefix: String,
+
+    /// Whether to listen for docker events and trigger a sync immediately when a container or service changes.
+    #[serde_inline_default(false)]
+    pub events: bool,
+
+    /// Only consider containers and services matching at least one of these label selectors. Use a semicolon separated string when setting using an env variable.
+    #[serde_as(
+        as = "PickFirst<(DeserializeVecLenient<LabelSelector>, StringWithSeparator::<SemicolonSeparator, LabelSelector>)>"
+    )]
+    #[serde(default)]
+    pub include: Vec<LabelSelector>,
+
+    /// Ignore containers and services matching any of these label selectors. Use a semicolon separated string when setting using an env variable.
+    #[serde_as(
+        as = "PickFirst<(DeserializeVecLenient<LabelSelector>, StringWithSeparator::<SemicolonSeparator, LabelSelector>)>"
+    )]
+    #[serde(default)]
+    pub exclude: Vec<LabelSelector>,
+}
+
+impl DockerConfig {
+    /// Whether a container or service with the given labels passes the `include` and `exclude` selectors.
+    pub fn is_selected(&self, labels: Option<&HashMap<String, String>>) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|selector| selector.matches(labels)))
+            && !self.exclude.iter().any(|selector| selector.matches(labels))
+    }
+}
+
+#[serde_alias(ScreamingSnakeCase)]
+#[serde_inline_default]
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KubernetesIngressConfig {
+    /// Whether monitors should be created from ingress rules or not.
+    #[serde_inline_default(false)]
+    pub enabled: bool,
+
+    /// Prefix used when scanning for ingress annotations.
+    #[serde_inline_default("kuma".to_owned())]
+    pub annotation_prefix: String,
 }
 
 #[serde_alias(ScreamingSnakeCase)]
@@ -52,6 +296,97 @@ pub struct KubernetesConfig {
     /// Whether kubernetes integration should be enabled or not.
     #[serde_inline_default(false)]
     pub enabled: bool,
+
+    /// The Uptime Kuma instance entities from this source are synced to (Defaults to the `default` instance).
+    #[serde_inline_default(None)]
+    pub instance: Option<String>,
+
+    pub ingress: KubernetesIngressConfig,
+}
+
+#[serde_alias(ScreamingSnakeCase)]
+#[serde_inline_default]
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SystemdConfig {
+    /// Whether systemd integration should be enabled or not.
+    #[serde_inline_default(false)]
+    pub enabled: bool,
+
+    /// The Uptime Kuma instance entities from this source are synced to (Defaults to the `default` instance).
+    #[serde_inline_default(None)]
+    pub instance: Option<String>,
+
+    /// Directory containing the unit files which should be scanned for `X-AutoKuma-*` keys.
+    #[serde_inline_default("/etc/systemd/system".to_owned())]
+    pub unit_path: String,
+
+    /// Prefix used for the labels created from `X-AutoKuma-*` keys.
+    #[serde_inline_default("kuma".to_owned())]
+    pub label_prefix: String,
+}
+
+#[serde_alias(ScreamingSnakeCase)]
+#[serde_inline_default]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NomadConfig {
+    /// Whether Nomad integration should be enabled or not (requires the `nomad` feature).
+    #[serde_inline_default(false)]
+    pub enabled: bool,
+
+    /// The Uptime Kuma instance entities from this source are synced to (Defaults to the `default` instance).
+    #[serde_inline_default(None)]
+    pub instance: Option<String>,
+
+    /// The address of the Nomad HTTP API.
+    #[serde_inline_default("http://127.0.0.1:4646".to_owned())]
+    pub address: String,
+
+    /// The ACL token used to access the Nomad API.
+    #[serde_inline_default(None)]
+    pub token: Option<String>,
+
+    /// The Nomad namespace to search for services, `*` searches all namespaces.
+    #[serde_inline_default("*".to_owned())]
+    pub namespace: String,
+
+    /// Prefix used when scanning for service tags.
+    #[serde_inline_default("kuma".to_owned())]
+    pub label_prefix: String,
+}
+
+#[serde_alias(ScreamingSnakeCase)]
+#[serde_inline_default]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AutoShrinkConfig {
+    /// Whether AutoKuma should shrink the Uptime Kuma database once it exceeds `threshold_mb`.
+    #[serde_inline_default(false)]
+    pub enabled: bool,
+
+    /// Check the database size every `interval` syncs.
+    #[serde_inline_default(720)]
+    pub interval: u64,
+
+    /// The database size in MiB above which the database is shrunk.
+    #[serde_inline_default(1024.0)]
+    pub threshold_mb: f64,
+}
+
+#[serde_alias(ScreamingSnakeCase)]
+#[serde_inline_default]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    /// Whether the Prometheus metrics endpoint should be enabled or not (requires the `metrics` feature).
+    #[serde_inline_default(false)]
+    pub enabled: bool,
+
+    /// The address the metrics endpoint should listen on.
+    #[serde_inline_default("0.0.0.0:9090".to_owned())]
+    pub listen: String,
+
+    /// The number of consecutive syncs an Uptime Kuma instance can be unreachable before `/healthz` reports the service as unhealthy.
+    #[serde_inline_default(3)]
+    pub max_down_syncs: u64,
 }
 
 #[serde_alias(ScreamingSnakeCase)]
@@ -63,9 +398,17 @@ pub struct FilesConfig {
     #[serde_inline_default(true)]
     pub enabled: bool,
 
+    /// The Uptime Kuma instance entities from this source are synced to (Defaults to the `default` instance).
+    #[serde_inline_default(None)]
+    pub instance: Option<String>,
+
     /// Whether the files source should follow symlinks or not.
     #[serde_inline_default(false)]
     pub follow_symlinks: bool,
+
+    /// Whether the files source should watch for changes and trigger a sync immediately.
+    #[serde_inline_default(false)]
+    pub watch: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -76,11 +419,43 @@ pub enum DeleteBehavior {
     Keep,
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum LogFormat {
+    #[serde(alias = "default")]
+    Default,
+    #[serde(alias = "json")]
+    Json,
+}
+
+/// Overrides of the delete behavior for specific entity types, falling back to [`Config::on_delete`] if not set.
+#[serde_alias(ScreamingSnakeCase)]
+#[serde_inline_default]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DeleteBehaviorOverrides {
+    #[serde_inline_default(None)]
+    pub monitor: Option<DeleteBehavior>,
+
+    #[serde_inline_default(None)]
+    pub docker_host: Option<DeleteBehavior>,
+
+    #[serde_inline_default(None)]
+    pub notification: Option<DeleteBehavior>,
+
+    #[serde_inline_default(None)]
+    pub tag: Option<DeleteBehavior>,
+
+    #[serde_inline_default(None)]
+    pub status_page: Option<DeleteBehavior>,
+
+    #[serde_inline_default(None)]
+    pub maintenance: Option<DeleteBehavior>,
+}
+
 #[serde_alias(ScreamingSnakeCase)]
 #[serde_inline_default]
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Config {
-    pub kuma: kuma_client::Config,
+    pub kuma: KumaConfig,
 
     pub docker: DockerConfig,
 
@@ -88,10 +463,22 @@ pub struct Config {
 
     pub files: FilesConfig,
 
+    pub systemd: SystemdConfig,
+
+    pub nomad: NomadConfig,
+
+    pub metrics: MetricsConfig,
+
+    pub auto_shrink: AutoShrinkConfig,
+
     /// The interval in between syncs.
     #[serde_inline_default(5.0)]
     pub sync_interval: f64,
 
+    /// Randomly vary the interval in between syncs by up to this percentage in either direction.
+    #[serde_inline_default(0.0)]
+    pub sync_jitter: f64,
+
     /// The path to the folder in which AutoKuma will search for static Monitor definitions.
     #[serde_inline_default(None)]
     pub static_monitors: Option<String>,
@@ -100,14 +487,17 @@ pub struct Config {
     #[serde_inline_default(DeleteBehavior::Delete)]
     pub on_delete: DeleteBehavior,
 
-    /// The name of the AutoKuma tag, used to track managed containers
+    /// Specify what to do when an entity of a specific type is not found anymore, overriding `on_delete`.
+    pub on_delete_by_type: DeleteBehaviorOverrides,
+
+    /// Delete kept tags and notifications created by AutoKuma once they aren't used by any monitor anymore.
+    #[serde_inline_default(false)]
+    pub delete_orphaned: bool,
+
+    /// The name of the legacy AutoKuma tag, monitors with this tag are taken over during the migration to the database
     #[serde_inline_default("AutoKuma".to_owned())]
     pub tag_name: String,
 
-    /// The color of the AutoKuma tag
-    #[serde_inline_default("#42C0FB".to_owned())]
-    pub tag_color: String,
-
     /// Where to store application data
     #[serde_inline_default(None)]
     pub data_path: Option<String>,
@@ -120,11 +510,148 @@ pub struct Config {
     #[serde_inline_default(HashMap::new())]
     pub snippets: HashMap<String, String>,
 
+    /// Global template variables available in all templates, values provided by a source take precedence.
+    #[serde_inline_default(HashMap::<String, serde_json::Value>::new())]
+    pub template_values: HashMap<String, serde_json::Value>,
+
     /// A directory where log files should be stored
     #[serde_inline_default(None)]
     pub log_dir: Option<String>,
 
+    /// The format of log messages, either `default` (colored if logging to a terminal) or `json` (one object per line).
+    #[serde_inline_default(LogFormat::Default)]
+    pub log_format: LogFormat,
+
+    /// Don't print the startup banner, implied by the `json` log format.
+    #[serde_inline_default(false)]
+    pub quiet: bool,
+
     /// Allow access to all env variables in templates, by default only variables starting with AUTOKUMA__ENV__ can be accessed.
     #[serde_inline_default(false)]
     pub insecure_env_access: bool,
+
+    /// Only log the changes which would be made instead of actually applying them.
+    #[serde_inline_default(false)]
+    pub dry_run: bool,
+
+    /// Run a single sync and exit, the exit code is non-zero if any entity couldn't be synced.
+    #[serde_inline_default(false)]
+    pub one_shot: bool,
+
+    /// Adopt the existing monitors, notifications and tags of all instances instead of syncing, then exit.
+    #[serde_inline_default(false)]
+    pub import: bool,
+
+    /// Write the entity definitions of imported entities to this directory.
+    #[serde_inline_default(None)]
+    pub import_dir: Option<String>,
+
+    /// Back up a corrupt database and start with an empty one instead of refusing to start, requires `import`.
+    #[serde_inline_default(false)]
+    pub reset_db: bool,
+
+    /// Write the changes of each sync as JSON to the given path (use `-` for stdout).
+    #[serde_inline_default(None)]
+    pub plan_output: Option<String>,
+
+    /// Don't update entities whose only differences are values equal to their Uptime Kuma defaults.
+    #[serde_inline_default(false)]
+    pub ignore_default_drift: bool,
+
+    /// Pause or resume existing monitors whose `active` state differs from their definition.
+    #[serde_inline_default(false)]
+    pub enforce_active: bool,
+}
+
+impl Config {
+    /// Whether the given tag is the legacy AutoKuma marker tag, which was used to track managed monitors before they were stored in the database.
+    pub fn is_marker_tag(&self, tag: &TagDefinition) -> bool {
+        tag.name.as_ref() == Some(&self.tag_name)
+    }
+
+    /// The delete behavior for entities of the given type.
+    pub fn delete_behavior(&self, entity_type: &EntityType) -> &DeleteBehavior {
+        let overrides = &self.on_delete_by_type;
+
+        match entity_type {
+            EntityType::Monitor(_) => &overrides.monitor,
+            EntityType::DockerHost => &overrides.docker_host,
+            EntityType::Notification => &overrides.notification,
+            EntityType::Tag => &overrides.tag,
+            EntityType::StatusPage => &overrides.status_page,
+            EntityType::Maintenance => &overrides.maintenance,
+        }
+        .as_ref()
+        .unwrap_or(&self.on_delete)
+    }
+
+    /// The folder the file source reads static entity definitions from.
+    pub fn static_monitors_path(&self) -> PathBuf {
+        self.static_monitors
+            .clone()
+            .unwrap_or_else(|| {
+                dirs::config_local_dir()
+                    .map(|dir| {
+                        dir.join("autokuma")
+                            .join("static-monitors")
+                            .to_string_lossy()
+                            .to_string()
+                    })
+                    .unwrap_or_default()
+            })
+            .into()
+    }
+
+    /// Whether entities adopted by an import survive the following sync, i.e. their definitions are written to the
+    /// static monitors folder or monitors, notifications and tags are kept when no source defines them.
+    pub fn imports_are_kept(&self) -> bool {
+        let overrides = &self.on_delete_by_type;
+
+        self.import_dir
+            .as_ref()
+            .is_some_and(|import_dir| Path::new(import_dir) == self.static_monitors_path())
+            || [&overrides.monitor, &overrides.notification, &overrides.tag]
+                .into_iter()
+                .all(|behavior| {
+                    behavior.as_ref().unwrap_or(&self.on_delete) == &DeleteBehavior::Keep
+                })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DockerHostConfig;
+    use crate::test_util::config;
+    use serde_json::json;
+
+    #[test]
+    fn docker_host_namespace() {
+        let named = DockerHostConfig {
+            name: Some("remote".to_owned()),
+            ..DockerHostConfig::from("tcp://10.0.0.2:2376".to_owned())
+        };
+        assert_eq!(named.namespace(), Some("remote"));
+
+        let unnamed = DockerHostConfig::from("tcp://10.0.0.2:2376".to_owned());
+        assert_eq!(unnamed.namespace(), None);
+    }
+
+    #[test]
+    fn imports_are_kept() {
+        assert!(!config(json!({})).imports_are_kept());
+        assert!(!config(json!({"import_dir": "/imports"})).imports_are_kept());
+        assert!(
+            config(json!({"import_dir": "/imports/", "static_monitors": "/imports"}))
+                .imports_are_kept()
+        );
+        assert!(config(json!({"on_delete": "keep"})).imports_are_kept());
+        assert!(
+            !config(json!({"on_delete": "keep", "on_delete_by_type": {"tag": "delete"}}))
+                .imports_are_kept()
+        );
+        assert!(config(json!({
+            "on_delete_by_type": {"monitor": "keep", "notification": "keep", "tag": "keep"},
+        }))
+        .imports_are_kept());
+    }
 }
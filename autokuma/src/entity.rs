@@ -1,19 +1,19 @@
 use crate::{
-    app_state::AppState,
+    app_state::{AppDB, AppState},
     error::{Error, Result},
     name::Name,
-    util::{fill_templates, group_by_prefix, FlattenValue},
+    util::{fill_templates, group_by_prefix, parse_snippet_args, FlattenValue},
 };
 use itertools::Itertools;
 use kuma_client::{
     docker_host::DockerHost,
+    maintenance::{Maintenance, MaintenanceMonitor},
     monitor::{Monitor, MonitorType},
-    notification::Notification,
-    status_page::StatusPage,
+    notification::{Notification, NotificationList},
+    status_page::{PublicGroupList, PublicGroupMonitor, StatusPage},
     tag::{Tag, TagDefinition},
     util::ResultLogger,
 };
-use log::warn;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::{collections::HashMap, sync::Arc};
@@ -28,6 +28,7 @@ pub enum Entity {
     Monitor(Monitor),
     Tag(TagDefinition),
     StatusPage(StatusPage),
+    Maintenance(Maintenance),
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Display)]
@@ -37,6 +38,7 @@ pub enum EntityType {
     Monitor(MonitorType),
     Tag,
     StatusPage,
+    Maintenance,
 }
 
 impl Entity {
@@ -47,6 +49,7 @@ impl Entity {
             Entity::Monitor(monitor) => EntityType::Monitor(monitor.monitor_type()),
             Entity::Tag(_) => EntityType::Tag,
             Entity::StatusPage(_) => EntityType::StatusPage,
+            Entity::Maintenance(_) => EntityType::Maintenance,
         }
     }
 }
@@ -66,14 +69,19 @@ enum EntityWrapper {
         #[serde(flatten)]
         tag: TagTagged,
     },
-    Monitor {
-        #[serde(flatten)]
-        monitor: Monitor,
-    },
     StatusPage {
         #[serde(flatten)]
         status_page: StatusPageTagged,
     },
+    Maintenance {
+        #[serde(flatten)]
+        maintenance: MaintenanceTagged,
+    },
+    // Monitors accept any unknown type, so they have to come last
+    Monitor {
+        #[serde(flatten)]
+        monitor: Monitor,
+    },
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -116,6 +124,16 @@ enum StatusPageTagged {
     },
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum MaintenanceTagged {
+    #[serde(rename = "maintenance")]
+    Maintenance {
+        #[serde(flatten)]
+        maintenance: Maintenance,
+    },
+}
+
 impl From<EntityWrapper> for Entity {
     fn from(wrapper: EntityWrapper) -> Self {
         match wrapper {
@@ -135,6 +153,10 @@ impl From<EntityWrapper> for Entity {
                 status_page: StatusPageTagged::StatusPage { status_page },
             } => Entity::StatusPage(status_page),
 
+            EntityWrapper::Maintenance {
+                maintenance: MaintenanceTagged::Maintenance { maintenance },
+            } => Entity::Maintenance(maintenance),
+
             EntityWrapper::Monitor { monitor } => Entity::Monitor(monitor),
         }
     }
@@ -159,6 +181,10 @@ impl From<Entity> for EntityWrapper {
                 status_page: StatusPageTagged::StatusPage { status_page },
             },
 
+            Entity::Maintenance(maintenance) => EntityWrapper::Maintenance {
+                maintenance: MaintenanceTagged::Maintenance { maintenance },
+            },
+
             Entity::Monitor(monitor) => EntityWrapper::Monitor { monitor },
         }
     }
@@ -181,13 +207,18 @@ pub fn get_entities_from_labels(
                         format!("Snippet '{}' not found!", key)
                     });
 
+                if snippet.is_none() {
+                    state.record_failure();
+                }
+
                 let args = if key.starts_with("__!") {
                     Some(vec![serde_json::Value::String(value.to_owned())])
                 } else {
-                    serde_json::from_str::<Vec<serde_json::Value>>(&format!("[{}]", value))
+                    parse_snippet_args(value)
                         .log_warn(std::module_path!(), |e| {
                             format!("Error while parsing snippet arguments: {}", e.to_string())
                         })
+                        .inspect_err(|_| state.record_failure())
                         .ok()
                 };
 
@@ -200,6 +231,7 @@ pub fn get_entities_from_labels(
                             .log_warn(std::module_path!(), |e| {
                                 format!("Error while parsing snippet: {}", e.to_string())
                             })
+                            .inspect_err(|_| state.record_failure())
                     {
                         snippet
                             .lines()
@@ -234,43 +266,91 @@ pub fn get_entities_from_labels(
         .map(|(key, value)| (key, group_by_prefix(value, ".")))
         .flat_map(|(id, entities)| {
             let state = state.clone();
-            entities
-                .into_iter()
-                .filter_map(move |(entity_type, settings)| {
-                    let result = get_entity_from_settings(
-                        state.clone(),
-                        &id,
-                        &entity_type,
-                        settings
-                            .into_iter()
-                            .map(|(key, value)| (key, json!(value)))
-                            .collect_vec(),
-                        template_values,
-                    )
-                    .map(|entity| (id.clone(), entity));
-
-                    match result {
-                        Err(Error::NameNotFound(name)) => {
-                            warn!(
-                                "Cannot create monitor {} because referenced {} with name {} is not found",
-                                id,
-                                name.type_name(),
-                                name.name()
-                            );
-                            None
-                        }
-                        result => Some(result),
-                    }
-                })
+            entities.into_iter().map(move |(entity_type, settings)| {
+                get_entity_from_settings(
+                    state.clone(),
+                    &id,
+                    &entity_type,
+                    settings
+                        .into_iter()
+                        .map(|(key, value)| (key, json!(value)))
+                        .collect_vec(),
+                    template_values,
+                )
+                .map(|entity| (id.clone(), entity))
+            })
         })
         .collect()
 }
 
-fn resolve_names(state: Arc<AppState>, monitor: &mut Monitor) -> Result<()> {
+/// Resolve the AutoKuma ids in `monitor_names` of a maintenance to the ids of the managed monitors.
+pub fn resolve_maintenance_names(db: &AppDB, maintenance: &mut Maintenance) -> Result<()> {
+    if let Some(monitor_names) = maintenance.common().monitor_names.clone() {
+        let mut monitors = monitor_names
+            .iter()
+            .map(|monitor_name| {
+                let name = Name::Monitor(monitor_name.clone());
+                let id = db
+                    .get_id::<i32>(name.clone())
+                    .ok()
+                    .flatten()
+                    .ok_or_else(|| Error::NameNotFound(name))?;
+
+                Ok(MaintenanceMonitor {
+                    id: Some(id),
+                    path_name: None,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        maintenance
+            .common_mut()
+            .monitors
+            .get_or_insert_with(Vec::new)
+            .append(&mut monitors);
+    }
+
+    Ok(())
+}
+
+/// Resolve the AutoKuma ids in `monitor_name` of the status page groups to the ids of the managed monitors.
+///
+/// Monitors have to be created before they can be referenced, so a status page listing a monitor
+/// which doesn't exist yet fails with [Error::NameNotFound] and is retried after the other entities have been created.
+pub fn resolve_status_page_names(db: &AppDB, status_page: &mut StatusPage) -> Result<()> {
+    for monitor in status_page
+        .public_group_list
+        .iter_mut()
+        .flatten()
+        .flat_map(|group| group.monitor_list.iter_mut())
+    {
+        if let Some(monitor_name) = &monitor.monitor_name {
+            let name = Name::Monitor(monitor_name.clone());
+            let id = db
+                .get_id(name.clone())
+                .ok()
+                .flatten()
+                .ok_or_else(|| Error::NameNotFound(name))?;
+
+            monitor.id = Some(id);
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolve all references by name (parent group, notifications, tags and docker host) using the given instance database.
+///
+/// Notifications which aren't managed by AutoKuma are looked up by their name in Uptime Kuma (`notifications`),
+/// so notifications created through the Uptime Kuma UI can be referenced as well.
+pub fn resolve_names(
+    db: &AppDB,
+    notifications: &NotificationList,
+    monitor: &mut Monitor,
+) -> Result<()> {
     if let Some(group_name) = monitor.common().parent_name().clone() {
         let name = Name::Monitor(group_name.clone());
-        let group_id = state
-            .db
+        let group_id = db
             .get_id(name.clone())
             .ok()
             .flatten()
@@ -284,11 +364,18 @@ fn resolve_names(state: Arc<AppState>, monitor: &mut Monitor) -> Result<()> {
             .iter()
             .map(|notification_name| {
                 let name = Name::Notification(notification_name.clone());
-                let id = state
-                    .db
+                let id = db
                     .get_id::<i32>(name.clone())
                     .ok()
                     .flatten()
+                    .or_else(|| {
+                        notifications
+                            .iter()
+                            .find(|notification| {
+                                notification.name.as_deref() == Some(notification_name.as_str())
+                            })
+                            .and_then(|notification| notification.id)
+                    })
                     .ok_or_else(|| Error::NameNotFound(name))?;
 
                 Ok((id.to_string(), true))
@@ -307,8 +394,7 @@ fn resolve_names(state: Arc<AppState>, monitor: &mut Monitor) -> Result<()> {
             .iter()
             .map(|tag_value| {
                 let name = Name::Tag(tag_value.name.clone());
-                let id = state
-                    .db
+                let id = db
                     .get_id(name.clone())
                     .ok()
                     .flatten()
@@ -332,8 +418,7 @@ fn resolve_names(state: Arc<AppState>, monitor: &mut Monitor) -> Result<()> {
         } => {
             if let Some(docker_host_name) = &docker_monitor.docker_host_name {
                 let name = Name::DockerHost(docker_host_name.clone());
-                let docker_host_id = state
-                    .db
+                let docker_host_id = db
                     .get_id(name.clone())
                     .ok()
                     .flatten()
@@ -399,8 +484,39 @@ pub fn get_entity_from_settings(
         .map_err(|e| Error::LabelParseError(e.to_string()))?;
 
     if let Entity::Monitor(monitor) = &mut entity {
+        if let Monitor::Unknown { value } = monitor {
+            return Err(Error::LabelParseError(format!(
+                "Unknown entity type '{}' for {}",
+                value.type_name, id
+            )));
+        }
+
         monitor.validate(id)?;
-        resolve_names(state, monitor)?;
+    }
+
+    if let Entity::Notification(notification) = &mut entity {
+        if notification.name.is_none() {
+            notification.name = notification
+                .config
+                .as_ref()
+                .and_then(|config| config.get("name"))
+                .and_then(|name| name.as_str())
+                .map(|name| name.to_owned());
+        }
+
+        notification.validate(id)?;
+    }
+
+    if let Entity::Maintenance(maintenance) = &entity {
+        maintenance.validate(id)?;
+    }
+
+    if let Entity::DockerHost(docker_host) = &entity {
+        docker_host.validate(id)?;
+    }
+
+    if let Entity::StatusPage(status_page) = &entity {
+        status_page.validate(id)?;
     }
 
     Ok(entity)
@@ -438,5 +554,110 @@ pub fn merge_entities(current: &Entity, new: &Entity, addition_tags: Option<Vec<
         *new_monitor.common_mut().tags_mut() = merged_tags;
     }
 
+    if let (Entity::StatusPage(new_status_page), Entity::StatusPage(current_status_page)) =
+        (&mut new, &current)
+    {
+        // Groups with a weight give the page a deliberate order which has to be kept
+        let specifies_order = new_status_page
+            .public_group_list
+            .iter()
+            .flatten()
+            .any(|group| group.weight.is_some());
+
+        new_status_page.normalize();
+
+        if let (false, Some(new_groups), Some(current_groups)) = (
+            specifies_order,
+            &mut new_status_page.public_group_list,
+            &current_status_page.public_group_list,
+        ) {
+            align_public_groups(new_groups, current_groups);
+        }
+    }
+
     serde_merge::omerge(current, new).unwrap()
 }
+
+/// Adopt the order of the current groups and their monitors when they only differ by order,
+/// so reordered but otherwise equal status pages don't cause an update. Only used if the definition doesn't specify group weights.
+fn align_public_groups(new_groups: &mut PublicGroupList, current_groups: &PublicGroupList) {
+    let group_names = |groups: &PublicGroupList| {
+        groups
+            .iter()
+            .map(|group| group.name.clone())
+            .sorted()
+            .collect_vec()
+    };
+
+    if group_names(new_groups) == group_names(current_groups) {
+        new_groups.sort_by_key(|group| {
+            current_groups
+                .iter()
+                .position(|current| current.name == group.name)
+        });
+
+        for (new_group, current_group) in new_groups.iter_mut().zip(current_groups) {
+            new_group.weight = current_group.weight;
+        }
+    }
+
+    for new_group in new_groups.iter_mut() {
+        let Some(current_group) = current_groups
+            .iter()
+            .find(|current| current.name == new_group.name)
+        else {
+            continue;
+        };
+
+        let monitor_ids = |monitors: &Vec<PublicGroupMonitor>| {
+            monitors
+                .iter()
+                .map(|monitor| monitor.id)
+                .sorted()
+                .collect_vec()
+        };
+
+        if monitor_ids(&new_group.monitor_list) == monitor_ids(&current_group.monitor_list) {
+            new_group.monitor_list.sort_by_key(|monitor| {
+                current_group
+                    .monitor_list
+                    .iter()
+                    .position(|current| current.id == monitor.id)
+            });
+        }
+    }
+}
+
+/// Monitor values which AutoKuma doesn't send when unset, so Uptime Kuma applies its own default.
+const MONITOR_SERVER_DEFAULTS: [(&str, i32); 1] = [("maxretries", 0)];
+
+/// Serialize an entity without any values that are unset or equal to their Uptime Kuma default.
+pub fn without_default_values(entity: &Entity) -> serde_json::Value {
+    let value = serde_json::to_value(entity).unwrap_or_default();
+
+    let defaults = match entity {
+        Entity::Monitor(monitor) => {
+            serde_json::from_value::<Monitor>(json!({ "type": monitor.monitor_type() }))
+                .ok()
+                .and_then(|monitor| serde_json::to_value(monitor).ok())
+                .map(|mut defaults| {
+                    for (key, value) in MONITOR_SERVER_DEFAULTS {
+                        defaults[key] = json!(value);
+                    }
+                    defaults
+                })
+        }
+        _ => None,
+    }
+    .unwrap_or_default();
+
+    match value {
+        serde_json::Value::Object(map) => map
+            .into_iter()
+            .filter(|(key, value)| {
+                key == "type" || (!value.is_null() && defaults.get(key) != Some(value))
+            })
+            .collect(),
+        value => value,
+    }
+}
@@ -18,6 +18,10 @@ pub enum Error {
     #[error(transparent)]
     K8S(#[from] K8SError),
 
+    #[cfg(feature = "nomad")]
+    #[error("Error while accessing the Nomad API: {0}")]
+    Nomad(String),
+
     #[error("Error while trying to parse labels: {0}")]
     LabelParseError(String),
 
@@ -33,6 +37,9 @@ pub enum Error {
     #[error("No {} named {} could be found", .0.type_name(), .0.name())]
     NameNotFound(Name),
 
+    #[error("{} is waiting for {} {} which could not be found", .0, .1.type_name(), .1.name())]
+    ReferenceNotFound(String, Name),
+
     #[error("{0}")]
     InternalError(String),
 }
@@ -42,6 +49,9 @@ pub enum Error {
 pub enum K8SError {
     #[error("Finalizer Error: {0}")]
     FinalizerError(#[source] Box<kube::runtime::finalizer::Error<Error>>),
+
+    #[error(transparent)]
+    Kube(#[from] kube::Error),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,448 @@
+use crate::{
+    app_state::{AppDB, AppState},
+    entity::{without_default_values, Entity},
+    error::{Error, Result},
+    name::Name,
+};
+use itertools::Itertools;
+use kuma_client::{tag::TagValue, Client};
+use log::{info, warn};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+/// Derive an AutoKuma id from the name of an entity, e.g. `My Website (prod)` becomes `my_website_prod`.
+fn derive_id(name: Option<&str>, fallback: String) -> String {
+    let id = name
+        .unwrap_or_default()
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .join("_");
+
+    match id.is_empty() {
+        true => fallback,
+        false => id,
+    }
+}
+
+/// Make a derived id unique by appending the Uptime Kuma id if it's already taken.
+fn unique_id(id: String, kuma_id: i32, taken: &mut HashSet<String>) -> String {
+    let id = match taken.contains(&id) {
+        true => format!("{}_{}", id, kuma_id),
+        false => id,
+    };
+
+    taken.insert(id.clone());
+    id
+}
+
+/// Fragments of the notification config keys holding credentials, e.g. `discordWebhookUrl` or `smtpPassword`.
+const SECRET_KEY_FRAGMENTS: &[&str] = &["pass", "token", "secret", "webhook", "key", "auth"];
+
+/// Replace the credentials in the config of a notification definition with a template reading them from
+/// the environment variable `AUTOKUMA__ENV__<ID>__<KEY>`, returns the names of the variables.
+fn redact_secrets(id: &str, definition: &mut serde_json::Value) -> Vec<String> {
+    let Some(config) = definition
+        .get_mut("config")
+        .and_then(|config| config.as_object_mut())
+    else {
+        return vec![];
+    };
+
+    config
+        .iter_mut()
+        .filter(|(key, value)| {
+            let key = key.to_lowercase();
+            value.as_str().is_some_and(|value| !value.is_empty())
+                && SECRET_KEY_FRAGMENTS
+                    .iter()
+                    .any(|fragment| key.contains(fragment))
+        })
+        .map(|(key, value)| {
+            let variable = format!("AUTOKUMA__ENV__{}__{}", id, key).to_uppercase();
+            *value = serde_json::Value::String(format!("{{{{ env(name='{}') }}}}", variable));
+            variable
+        })
+        .collect()
+}
+
+async fn write_definition(dir: &Path, id: &str, entity: &Entity) -> Result<()> {
+    let mut definition = without_default_values(entity);
+
+    if let Entity::Notification(_) = entity {
+        let variables = redact_secrets(id, &mut definition);
+        if !variables.is_empty() {
+            warn!(
+                "The credentials of notification {} were not written to its definition, set {} before using it",
+                id,
+                variables.join(", ")
+            );
+        }
+    }
+
+    let content = serde_json::to_string_pretty(&definition)
+        .map_err(|e| Error::InternalError(e.to_string()))?;
+
+    tokio::fs::write(dir.join(format!("{}.json", id)), content)
+        .await
+        .map_err(|e| Error::IO(e.to_string()))
+}
+
+/// Adopt the monitors, notifications and tags of an Uptime Kuma instance which aren't managed by AutoKuma yet.
+///
+/// Monitors use the value of the legacy AutoKuma tag as id if they have one, otherwise ids are derived from the entity names.
+/// If `import_dir` is set, an equivalent entity definition is written to `<import_dir>/<id>.json` for every adopted entity.
+pub async fn import_instance(
+    state: &AppState,
+    instance: &str,
+    kuma: &Client,
+    db: &AppDB,
+) -> Result<()> {
+    let mut taken = db
+        .get_monitors()?
+        .into_iter()
+        .chain(db.get_notifications()?)
+        .chain(db.get_tags()?)
+        .chain(db.get_docker_hosts()?)
+        .chain(db.get_maintenances()?)
+        .map(|(id, _)| id)
+        .chain(db.get_status_pages()?.into_iter().map(|(id, _)| id))
+        .collect::<HashSet<_>>();
+
+    let mut imported: Vec<(String, Name, i32, Entity)> = vec![];
+
+    let mut notification_ids = db
+        .get_notifications()?
+        .into_iter()
+        .map(|(id, kuma_id)| (kuma_id, id))
+        .collect::<HashMap<_, _>>();
+
+    for mut notification in kuma.get_notifications().await? {
+        let Some(kuma_id) = notification.id.take() else {
+            continue;
+        };
+
+        if notification_ids.contains_key(&kuma_id) {
+            continue;
+        }
+
+        let id = unique_id(
+            derive_id(
+                notification.name.as_deref(),
+                format!("notification_{}", kuma_id),
+            ),
+            kuma_id,
+            &mut taken,
+        );
+
+        notification_ids.insert(kuma_id, id.clone());
+        imported.push((
+            id.clone(),
+            Name::Notification(id),
+            kuma_id,
+            Entity::Notification(notification),
+        ));
+    }
+
+    let tags = kuma.get_tags().await?;
+    let marker_tag = tags
+        .iter()
+        .find(|tag| state.config.is_marker_tag(tag))
+        .and_then(|tag| tag.tag_id);
+
+    let mut tag_ids = db
+        .get_tags()?
+        .into_iter()
+        .map(|(id, kuma_id)| (kuma_id, id))
+        .collect::<HashMap<_, _>>();
+
+    for mut tag in tags {
+        let Some(kuma_id) = tag.tag_id.take() else {
+            continue;
+        };
+
+        if tag_ids.contains_key(&kuma_id) || Some(kuma_id) == marker_tag {
+            continue;
+        }
+
+        let id = unique_id(
+            derive_id(tag.name.as_deref(), format!("tag_{}", kuma_id)),
+            kuma_id,
+            &mut taken,
+        );
+
+        tag_ids.insert(kuma_id, id.clone());
+        imported.push((id.clone(), Name::Tag(id), kuma_id, Entity::Tag(tag)));
+    }
+
+    let mut monitor_ids = db
+        .get_monitors()?
+        .into_iter()
+        .map(|(id, kuma_id)| (kuma_id, id))
+        .collect::<HashMap<_, _>>();
+
+    let monitors = kuma
+        .get_monitors()
+        .await?
+        .into_values()
+        .filter_map(|monitor| monitor.common().id().map(|kuma_id| (kuma_id, monitor)))
+        .filter(|(kuma_id, _)| !monitor_ids.contains_key(kuma_id))
+        .sorted_by_key(|(kuma_id, _)| *kuma_id)
+        .collect_vec();
+
+    // Assign all ids first, so groups can be referenced regardless of the order of the monitors
+    for (kuma_id, monitor) in &monitors {
+        let legacy_id = marker_tag.and_then(|marker_tag| {
+            monitor
+                .common()
+                .tags()
+                .iter()
+                .find(|tag| tag.tag_id == Some(marker_tag))
+                .and_then(|tag| tag.value.clone())
+        });
+
+        let id = match legacy_id {
+            Some(legacy_id) if !taken.contains(&legacy_id) => {
+                taken.insert(legacy_id.clone());
+                legacy_id
+            }
+            _ => unique_id(
+                derive_id(
+                    monitor.common().name().as_deref(),
+                    format!("monitor_{}", kuma_id),
+                ),
+                *kuma_id,
+                &mut taken,
+            ),
+        };
+
+        monitor_ids.insert(*kuma_id, id);
+    }
+
+    // Replace references by Uptime Kuma id with references by AutoKuma id, so the definitions don't depend on this instance
+    for (kuma_id, mut monitor) in monitors {
+        let id = monitor_ids[&kuma_id].clone();
+        let common = monitor.common_mut();
+
+        *common.id_mut() = None;
+
+        if let Some(parent_id) = common.parent_mut().take() {
+            *common.parent_name_mut() = monitor_ids.get(&parent_id).cloned();
+        }
+
+        if let Some(notification_id_list) = common.notification_id_list_mut().take() {
+            *common.notification_names_mut() = Some(
+                notification_id_list
+                    .into_iter()
+                    .filter(|(_, enabled)| *enabled)
+                    .filter_map(|(notification_id, _)| notification_id.parse::<i32>().ok())
+                    .filter_map(|notification_id| notification_ids.get(&notification_id).cloned())
+                    .sorted()
+                    .collect(),
+            );
+        }
+
+        let tag_names = std::mem::take(common.tags_mut())
+            .into_iter()
+            .filter(|tag| tag.tag_id.is_some() && tag.tag_id != marker_tag)
+            .filter_map(|tag| {
+                tag_ids
+                    .get(&tag.tag_id.unwrap_or_default())
+                    .map(|name| TagValue {
+                        name: name.clone(),
+                        value: tag.value.filter(|value| !value.is_empty()),
+                    })
+            })
+            .collect_vec();
+
+        if !tag_names.is_empty() {
+            *common.tag_names_mut() = Some(tag_names);
+        }
+
+        imported.push((
+            id.clone(),
+            Name::Monitor(id),
+            kuma_id,
+            Entity::Monitor(monitor),
+        ));
+    }
+
+    info!(
+        "Importing {} entities from instance '{}'",
+        imported.len(),
+        instance
+    );
+
+    let import_dir = state.config.import_dir.as_ref().map(Path::new);
+
+    if let Some(import_dir) = &import_dir {
+        tokio::fs::create_dir_all(import_dir)
+            .await
+            .map_err(|e| Error::IO(e.to_string()))?;
+    }
+
+    for (id, name, kuma_id, entity) in imported {
+        // Only track entities with a definition, otherwise the next sync would delete them again
+        if let Some(import_dir) = &import_dir {
+            write_definition(import_dir, &id, &entity).await?;
+        }
+
+        match state.config.dry_run {
+            true => info!(
+                "[DRY RUN] Would import {} {} as {}",
+                entity.entity_type(),
+                kuma_id,
+                id
+            ),
+            false => {
+                info!("Importing {} {} as {}", entity.entity_type(), kuma_id, id);
+                db.store_id(name, kuma_id)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::DEFAULT_INSTANCE,
+        sources::{file_source::FileSource, source::Source},
+        test_util::TestState,
+    };
+    use kuma_client::{tag::TagDefinition, test_util::MockServer};
+    use serde_json::json;
+
+    #[test]
+    fn ids_are_derived_from_names() {
+        assert_eq!(
+            derive_id(Some("My Website (prod)"), "monitor_1".to_owned()),
+            "my_website_prod"
+        );
+        assert_eq!(
+            derive_id(Some("--- ???"), "monitor_1".to_owned()),
+            "monitor_1"
+        );
+        assert_eq!(derive_id(None, "tag_2".to_owned()), "tag_2");
+    }
+
+    #[test]
+    fn taken_ids_get_the_kuma_id_appended() {
+        let mut taken = HashSet::from(["web".to_owned()]);
+
+        assert_eq!(unique_id("api".to_owned(), 1, &mut taken), "api");
+        assert_eq!(unique_id("web".to_owned(), 2, &mut taken), "web_2");
+        assert_eq!(unique_id("api".to_owned(), 3, &mut taken), "api_3");
+    }
+
+    #[tokio::test]
+    async fn import_writes_definitions_without_credentials() {
+        let server = MockServer::start()
+            .await
+            .with_monitors(json!({
+                "1": {"id": 1, "name": "Shop", "type": "group", "parent": null, "tags": [], "notificationIDList": {}},
+                "2": {"id": 2, "name": "Website", "type": "http", "url": "https://example.com", "parent": 1, "tags": [], "notificationIDList": {"7": true}},
+                "3": {"id": 3, "name": "Website", "type": "http", "url": "https://example.org", "parent": null, "tags": [], "notificationIDList": {}},
+            }))
+            .await
+            .with_notifications(json!([{
+                "id": 7,
+                "name": "Discord",
+                "active": true,
+                "isDefault": false,
+                "userId": 1,
+                "config": json!({
+                    "name": "Discord",
+                    "type": "discord",
+                    "discordUsername": "AutoKuma",
+                    "discordWebhookUrl": "https://discord.com/api/webhooks/secret",
+                }).to_string(),
+            }]))
+            .await;
+
+        // Hidden files are skipped by the file source, including the directory itself
+        let import_dir = tempfile::Builder::new()
+            .prefix("imports")
+            .tempdir()
+            .unwrap();
+        let state = TestState::new(json!({
+            "kuma": {"url": server.url(), "username": "admin", "password": "admin"},
+            "import_dir": import_dir.path(),
+            "static_monitors": import_dir.path(),
+        }));
+
+        let client = kuma_client::Client::connect(server.config()).await.unwrap();
+        client
+            .add_tag(TagDefinition {
+                name: Some("Team".to_owned()),
+                color: Some("#ff0000".to_owned()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let db = state.state.db(DEFAULT_INSTANCE).unwrap();
+        import_instance(&state.state, DEFAULT_INSTANCE, &client, &db)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            db.get_id::<i32>(Name::Monitor("shop".to_owned())).unwrap(),
+            Some(1)
+        );
+        assert_eq!(
+            db.get_id::<i32>(Name::Monitor("website".to_owned()))
+                .unwrap(),
+            Some(2)
+        );
+        assert_eq!(
+            db.get_id::<i32>(Name::Monitor("website_3".to_owned()))
+                .unwrap(),
+            Some(3)
+        );
+        assert_eq!(
+            db.get_id::<i32>(Name::Notification("discord".to_owned()))
+                .unwrap(),
+            Some(7)
+        );
+        assert!(db
+            .get_id::<i32>(Name::Tag("team".to_owned()))
+            .unwrap()
+            .is_some());
+
+        let read = |id: &str| -> serde_json::Value {
+            let path = import_dir.path().join(format!("{}.json", id));
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+        };
+
+        let website = read("website");
+        assert_eq!(website["parent_name"], json!("shop"));
+        assert_eq!(website["notification_name_list"], json!(["discord"]));
+        assert!(website.get("id").is_none());
+        assert_eq!(read("website_3")["url"], json!("https://example.org"));
+
+        let discord = read("discord");
+        assert_eq!(discord["config"]["discordUsername"], json!("AutoKuma"));
+        assert_eq!(
+            discord["config"]["discordWebhookUrl"],
+            json!("{{ env(name='AUTOKUMA__ENV__DISCORD__DISCORDWEBHOOKURL') }}")
+        );
+        assert!(!serde_json::to_string(&discord).unwrap().contains("secret"));
+
+        // The file source reads the definitions with the ids of the adopted entities
+        std::fs::remove_file(import_dir.path().join("discord.json")).unwrap();
+        let ids = FileSource::new(state.state.clone())
+            .get_entities()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(id, _)| id)
+            .sorted()
+            .collect_vec();
+        assert_eq!(ids, vec!["shop", "team", "website", "website_3"]);
+    }
+}
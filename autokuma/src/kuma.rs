@@ -1,13 +1,19 @@
-use crate::{app_state::AppState, entity::Entity, error::Result, util::fill_templates};
+use crate::{
+    app_state::{AppDB, AppState},
+    entity::Entity,
+    error::Result,
+    util::fill_templates,
+};
 use futures_util::future::join_all;
 use kuma_client::{
-    docker_host::DockerHost, monitor::Monitor, notification::Notification, status_page::StatusPage,
-    tag::TagDefinition, Client,
+    docker_host::DockerHost, maintenance::Maintenance, monitor::Monitor,
+    notification::Notification, status_page::StatusPage, tag::TagDefinition, Client,
 };
 use std::collections::HashMap;
 
 pub fn get_kuma_labels(
     state: &AppState,
+    label_prefix: &str,
     labels: Option<&HashMap<String, String>>,
     template_values: &tera::Context,
 ) -> Result<Vec<(String, String)>> {
@@ -16,13 +22,11 @@ pub fn get_kuma_labels(
         |labels| {
             labels
                 .iter()
-                .filter(|(key, _)| {
-                    key.starts_with(&format!("{}.", state.config.docker.label_prefix))
-                })
+                .filter(|(key, _)| key.starts_with(&format!("{}.", label_prefix)))
                 .map(|(key, value)| {
                     fill_templates(
                         state.config.clone(),
-                        key.trim_start_matches(&format!("{}.", state.config.docker.label_prefix)),
+                        key.trim_start_matches(&format!("{}.", label_prefix)),
                         &template_values,
                     )
                     .map(|key| (key, value.to_owned()))
@@ -39,11 +43,10 @@ pub fn get_kuma_labels(
 }
 
 async fn get_managed_docker_hosts(
-    state: &AppState,
+    db: &AppDB,
     kuma: &Client,
 ) -> Result<HashMap<String, DockerHost>> {
-    let map = state
-        .db
+    let map = db
         .get_docker_hosts()?
         .into_iter()
         .map(|(key, value)| (value, key))
@@ -61,11 +64,10 @@ async fn get_managed_docker_hosts(
 }
 
 async fn get_managed_notification_providers(
-    state: &AppState,
+    db: &AppDB,
     kuma: &Client,
 ) -> Result<HashMap<String, Notification>> {
-    let map = state
-        .db
+    let map = db
         .get_notifications()?
         .into_iter()
         .map(|(key, value)| (value, key))
@@ -82,12 +84,8 @@ async fn get_managed_notification_providers(
         .collect::<HashMap<_, _>>())
 }
 
-async fn get_managed_tags(
-    state: &AppState,
-    kuma: &Client,
-) -> Result<HashMap<String, TagDefinition>> {
-    let map = state
-        .db
+async fn get_managed_tags(db: &AppDB, kuma: &Client) -> Result<HashMap<String, TagDefinition>> {
+    let map = db
         .get_tags()?
         .into_iter()
         .map(|(key, value)| (value, key))
@@ -105,11 +103,10 @@ async fn get_managed_tags(
 }
 
 async fn get_managed_status_pages(
-    state: &AppState,
+    db: &AppDB,
     kuma: &Client,
 ) -> Result<HashMap<String, StatusPage>> {
-    let map = state
-        .db
+    let map = db
         .get_status_pages()?
         .into_iter()
         .map(|(key, value)| (value, key))
@@ -132,9 +129,36 @@ async fn get_managed_status_pages(
     .collect::<HashMap<_, _>>())
 }
 
-async fn get_managed_monitors(state: &AppState, kuma: &Client) -> Result<HashMap<String, Monitor>> {
-    let map = state
-        .db
+async fn get_managed_maintenances(
+    db: &AppDB,
+    kuma: &Client,
+) -> Result<HashMap<String, Maintenance>> {
+    let map = db
+        .get_maintenances()?
+        .into_iter()
+        .map(|(key, value)| (value, key))
+        .collect::<HashMap<_, _>>();
+
+    Ok(join_all(
+        kuma.get_maintenances()
+            .await?
+            .into_values()
+            .filter_map(|maintenance| maintenance.common().id)
+            .filter(|id| map.contains_key(id))
+            .map(|id| kuma.get_maintenance(id)),
+    )
+    .await
+    .into_iter()
+    .flatten()
+    .filter_map(|maintenance| {
+        map.get(&maintenance.common().id.unwrap_or(-1))
+            .map(|id| (id.to_owned(), maintenance))
+    })
+    .collect::<HashMap<_, _>>())
+}
+
+async fn get_managed_monitors(db: &AppDB, kuma: &Client) -> Result<HashMap<String, Monitor>> {
+    let map = db
         .get_monitors()?
         .into_iter()
         .map(|(key, value)| (value, key))
@@ -151,37 +175,40 @@ async fn get_managed_monitors(state: &AppState, kuma: &Client) -> Result<HashMap
         .collect::<HashMap<_, _>>())
 }
 
-pub async fn get_managed_entities(
-    state: &AppState,
-    kuma: &Client,
-) -> Result<HashMap<String, Entity>> {
-    Ok(get_managed_monitors(&state, &kuma)
+pub async fn get_managed_entities(db: &AppDB, kuma: &Client) -> Result<HashMap<String, Entity>> {
+    Ok(get_managed_monitors(db, kuma)
         .await?
         .into_iter()
         .map(|(id, monitor)| (id, Entity::Monitor(monitor)))
         .chain(
-            get_managed_docker_hosts(&state, &kuma)
+            get_managed_docker_hosts(db, kuma)
                 .await?
                 .into_iter()
                 .map(|(id, host)| (id, Entity::DockerHost(host))),
         )
         .chain(
-            get_managed_notification_providers(&state, &kuma)
+            get_managed_notification_providers(db, kuma)
                 .await?
                 .into_iter()
                 .map(|(id, notification)| (id, Entity::Notification(notification))),
         )
         .chain(
-            get_managed_tags(&state, &kuma)
+            get_managed_tags(db, kuma)
                 .await?
                 .into_iter()
                 .map(|(id, tag)| (id, Entity::Tag(tag))),
         )
         .chain(
-            get_managed_status_pages(&state, &kuma)
+            get_managed_status_pages(db, kuma)
                 .await?
                 .into_iter()
                 .map(|(id, status_page)| (id, Entity::StatusPage(status_page))),
         )
+        .chain(
+            get_managed_maintenances(db, kuma)
+                .await?
+                .into_iter()
+                .map(|(id, maintenance)| (id, Entity::Maintenance(maintenance))),
+        )
         .collect::<HashMap<_, _>>())
 }
@@ -1,11 +1,16 @@
-use crate::util::ResultOrDie;
+use crate::{cli::Cli, config::LogFormat, util::ResultOrDie};
 use ::config::{Config, Environment, File, FileFormat};
-use flexi_logger::{Cleanup, Criterion, Duplicate, FileSpec, Logger, LoggerHandle, Naming};
+use clap::Parser;
+use flexi_logger::{
+    Cleanup, Criterion, DeferredNow, Duplicate, FileSpec, Logger, LoggerHandle, Naming,
+};
 use kuma_client::build::SHORT_VERSION;
 use kuma_client::util::ResultLogger;
 use serde_json::json;
 use std::sync::Arc;
 
+mod cli;
+
 include!("mod.rs");
 
 const BANNER: &str = r"                                                        
@@ -47,9 +52,30 @@ const BANNER: &str = r"
           /_/    \_\ \__,_| \__| \___/ |_|\_\ \__,_||_| |_| |_| \__,_|  
 ";
 
+fn json_log_format(
+    w: &mut dyn std::io::Write,
+    now: &mut DeferredNow,
+    record: &log::Record,
+) -> std::io::Result<()> {
+    write!(
+        w,
+        "{}",
+        json!({
+            "timestamp": now.format_rfc3339(),
+            "level": record.level().as_str(),
+            "target": record.target(),
+            "message": record.args().to_string(),
+        })
+    )
+}
+
 fn create_logger(config: &Arc<crate::config::Config>) -> LoggerHandle {
     let mut builder = Logger::try_with_env_or_str("info, kube_runtime=error").unwrap();
 
+    if config.log_format == LogFormat::Json {
+        builder = builder.format(json_log_format);
+    }
+
     if let Some(log_dir) = config.log_dir.as_ref() {
         builder = builder
             .log_to_file(FileSpec::default().directory(log_dir))
@@ -75,17 +101,21 @@ fn init_console_subscriber() {}
 
 #[tokio::main()]
 async fn main() {
+    let cli = Cli::parse();
+
     init_console_subscriber();
 
-    let config: Arc<crate::config::Config> = Arc::new(
-        Config::builder()
-            .add_source(File::from_str(
-                &serde_json::to_string(
-                    &json!({"kuma": {"tls": {}}, "docker": {}, "files": {}, "kubernetes": {}}),
-                )
-                .unwrap(),
-                FileFormat::Json,
-            ))
+    let builder = Config::builder().add_source(File::from_str(
+        &serde_json::to_string(
+            &json!({"kuma": {}, "docker": {}, "files": {}, "kubernetes": {"ingress": {}}, "systemd": {}, "nomad": {}, "metrics": {}, "auto_shrink": {}, "on_delete_by_type": {}}),
+        )
+        .unwrap(),
+        FileFormat::Json,
+    ));
+
+    let builder = match cli.config_file() {
+        Some(path) => builder.add_source(File::with_name(&path).required(true)),
+        None => builder
             .add_source(
                 File::with_name(
                     &dirs::config_local_dir()
@@ -99,12 +129,26 @@ async fn main() {
                 )
                 .required(false),
             )
-            .add_source(File::new("autokuma", FileFormat::Toml).required(false))
+            .add_source(File::new("autokuma", FileFormat::Toml).required(false)),
+    };
+
+    let config: Arc<crate::config::Config> = Arc::new(
+        builder
             .add_source(
                 Environment::with_prefix("AUTOKUMA")
                     .separator("__")
                     .prefix_separator("__"),
             )
+            .set_override_option("dry_run", cli.dry_run.then_some(true))
+            .unwrap()
+            .set_override_option("one_shot", cli.one_shot.then_some(true))
+            .unwrap()
+            .set_override_option("import", cli.import.then_some(true))
+            .unwrap()
+            .set_override_option("quiet", cli.quiet.then_some(true))
+            .unwrap()
+            .set_override_option("reset_db", cli.reset_db.then_some(true))
+            .unwrap()
             .build()
             .print_error(|e| format!("Unable to load config: {}", e))
             .and_then(|config| config.try_deserialize())
@@ -114,13 +158,22 @@ async fn main() {
 
     let logger = create_logger(&config);
 
-    println!("{}{:>70}", BANNER, SHORT_VERSION);
+    if !config.quiet && config.log_format != LogFormat::Json {
+        println!("{}{:>70}", BANNER, SHORT_VERSION);
+    }
 
-    let mut sync = sync::Sync::new(config)
+    let mut sync = sync::Sync::new(config.clone())
         .log_error(std::module_path!(), |e| format!("Invalid config: {}", e))
         .unwrap_or_die(1);
 
-    sync.run().await;
+    let success = match config.import {
+        true => sync.import().await,
+        false => sync.run().await,
+    };
 
     logger.shutdown();
+
+    if !success {
+        std::process::exit(1);
+    }
 }
@@ -0,0 +1,283 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Counters describing the syncs of this AutoKuma process, exposed in the Prometheus text format.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    syncs: AtomicU64,
+    last_sync_success: AtomicBool,
+    last_sync_timestamp: AtomicU64,
+    last_sync_duration: AtomicU64,
+    created: AtomicU64,
+    updated: AtomicU64,
+    deleted: AtomicU64,
+    failed: AtomicU64,
+    kuma_up: Mutex<BTreeMap<String, bool>>,
+    kuma_down_syncs: Mutex<BTreeMap<String, u64>>,
+}
+
+impl Metrics {
+    pub fn record_sync(&self, duration: Duration, success: bool) {
+        self.syncs.fetch_add(1, Ordering::Relaxed);
+        self.last_sync_success.store(success, Ordering::Relaxed);
+        self.last_sync_duration
+            .store(duration.as_secs_f64().to_bits(), Ordering::Relaxed);
+        self.last_sync_timestamp.store(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |time| time.as_secs()),
+            Ordering::Relaxed,
+        );
+    }
+
+    pub fn record_created(&self) {
+        self.created.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_updated(&self) {
+        self.updated.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_deleted(&self) {
+        self.deleted.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_failed(&self) {
+        self.failed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn failed(&self) -> u64 {
+        self.failed.load(Ordering::Relaxed)
+    }
+
+    pub fn set_kuma_up(&self, instance: &str, up: bool) {
+        if let Ok(mut kuma_up) = self.kuma_up.lock() {
+            kuma_up.insert(instance.to_owned(), up);
+        }
+
+        if let Ok(mut kuma_down_syncs) = self.kuma_down_syncs.lock() {
+            let down_syncs = kuma_down_syncs.entry(instance.to_owned()).or_default();
+            *down_syncs = if up { 0 } else { *down_syncs + 1 };
+        }
+    }
+
+    /// Whether at least one sync has completed and the last one didn't encounter any failures.
+    #[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+    pub fn is_ready(&self) -> bool {
+        self.syncs.load(Ordering::Relaxed) > 0
+            && self.last_sync_success.load(Ordering::Relaxed)
+            && self
+                .kuma_up
+                .lock()
+                .is_ok_and(|kuma_up| kuma_up.values().all(|up| *up))
+    }
+
+    /// Whether no Uptime Kuma instance has been unreachable for more than `max_down_syncs` consecutive syncs.
+    #[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+    pub fn is_healthy(&self, max_down_syncs: u64) -> bool {
+        self.kuma_down_syncs.lock().is_ok_and(|kuma_down_syncs| {
+            kuma_down_syncs
+                .values()
+                .all(|down_syncs| *down_syncs <= max_down_syncs)
+        })
+    }
+
+    /// Render all metrics in the Prometheus text exposition format.
+    #[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+
+        let mut metric = |name: &str, kind: &str, help: &str, values: Vec<(String, String)>| {
+            _ = writeln!(output, "# HELP {} {}", name, help);
+            _ = writeln!(output, "# TYPE {} {}", name, kind);
+            for (labels, value) in values {
+                _ = writeln!(output, "{}{} {}", name, labels, value);
+            }
+        };
+
+        let counter =
+            |value: &AtomicU64| vec![("".to_owned(), value.load(Ordering::Relaxed).to_string())];
+
+        metric(
+            "autokuma_syncs_total",
+            "counter",
+            "Number of completed syncs.",
+            counter(&self.syncs),
+        );
+        metric(
+            "autokuma_last_sync_timestamp_seconds",
+            "gauge",
+            "Unix timestamp of the last completed sync.",
+            counter(&self.last_sync_timestamp),
+        );
+        metric(
+            "autokuma_last_sync_success",
+            "gauge",
+            "Whether the last sync completed without any failures.",
+            vec![(
+                "".to_owned(),
+                (self.last_sync_success.load(Ordering::Relaxed) as u8).to_string(),
+            )],
+        );
+        metric(
+            "autokuma_last_sync_duration_seconds",
+            "gauge",
+            "Duration of the last completed sync.",
+            vec![(
+                "".to_owned(),
+                f64::from_bits(self.last_sync_duration.load(Ordering::Relaxed)).to_string(),
+            )],
+        );
+        metric(
+            "autokuma_entities_created_total",
+            "counter",
+            "Number of entities created.",
+            counter(&self.created),
+        );
+        metric(
+            "autokuma_entities_updated_total",
+            "counter",
+            "Number of entities updated.",
+            counter(&self.updated),
+        );
+        metric(
+            "autokuma_entities_deleted_total",
+            "counter",
+            "Number of entities deleted.",
+            counter(&self.deleted),
+        );
+        metric(
+            "autokuma_entities_failed_total",
+            "counter",
+            "Number of entities or instances which couldn't be synced.",
+            counter(&self.failed),
+        );
+        metric(
+            "autokuma_kuma_up",
+            "gauge",
+            "Whether the last connection attempt to the Uptime Kuma instance succeeded.",
+            self.kuma_up
+                .lock()
+                .map(|kuma_up| {
+                    kuma_up
+                        .iter()
+                        .map(|(instance, up)| {
+                            (
+                                format!("{{instance=\"{}\"}}", instance.replace('"', "\\\"")),
+                                (*up as u8).to_string(),
+                            )
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+        );
+
+        output
+    }
+}
+
+#[cfg(feature = "metrics")]
+pub mod server {
+    use crate::{
+        app_state::AppState,
+        error::{Error, Result},
+    };
+    use log::{debug, info};
+    use std::{sync::Arc, time::Duration};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    };
+
+    /// How long a client may take to send its request before the connection is dropped.
+    const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+    async fn handle_connection(state: Arc<AppState>, mut stream: TcpStream) -> Result<()> {
+        let mut buffer = [0u8; 1024];
+        let len = tokio::time::timeout(READ_TIMEOUT, stream.read(&mut buffer))
+            .await
+            .map_err(|_| Error::IO("Timed out while reading the request".to_owned()))?
+            .map_err(|e| Error::IO(e.to_string()))?;
+
+        let request = String::from_utf8_lossy(&buffer[..len]);
+        let path = request
+            .lines()
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .unwrap_or_default();
+
+        let probe = |ok: bool| match ok {
+            true => ("200 OK", "OK\n".to_owned()),
+            false => (
+                "503 Service Unavailable",
+                "Service Unavailable\n".to_owned(),
+            ),
+        };
+
+        let (status, body) = match path {
+            "/metrics" => ("200 OK", state.metrics.render()),
+            "/healthz" => probe(
+                state
+                    .metrics
+                    .is_healthy(state.config.metrics.max_down_syncs),
+            ),
+            "/readyz" => probe(state.metrics.is_ready()),
+            _ => ("404 Not Found", "Not Found\n".to_owned()),
+        };
+
+        stream
+            .write_all(
+                format!(
+                    "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .as_bytes(),
+            )
+            .await
+            .map_err(|e| Error::IO(e.to_string()))
+    }
+
+    /// Serve the metrics and health probes on the configured listen address until the process exits.
+    pub async fn serve(state: Arc<AppState>) -> Result<()> {
+        let listener = TcpListener::bind(&state.config.metrics.listen)
+            .await
+            .map_err(|e| Error::IO(e.to_string()))?;
+
+        info!(
+            "Serving metrics on http://{}/metrics",
+            state.config.metrics.listen
+        );
+
+        tokio::spawn(async move {
+            loop {
+                let stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        // Errors like running out of file descriptors persist for a while, avoid spinning on them
+                        debug!("Error while accepting metrics connection: {}", e);
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        continue;
+                    }
+                };
+
+                let state = state.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_connection(state, stream).await {
+                        debug!("Error while serving metrics: {}", e);
+                    }
+                });
+            }
+        });
+
+        Ok(())
+    }
+}
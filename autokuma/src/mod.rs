@@ -2,8 +2,13 @@ mod app_state;
 mod config;
 mod entity;
 mod error;
+mod import;
 mod kuma;
+mod metrics;
 mod name;
+mod plan;
 mod sources;
 mod sync;
+#[cfg(test)]
+mod test_util;
 mod util;
@@ -1,12 +1,14 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Eq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Eq, Hash)]
 pub enum Name {
     Monitor(String),
     Notification(String),
     DockerHost(String),
     Tag(String),
     StatusPage(String),
+    Maintenance(String),
+    Proxy(String),
 }
 
 impl Name {
@@ -17,6 +19,8 @@ impl Name {
             Name::DockerHost(name) => name,
             Name::Tag(name) => name,
             Name::StatusPage(name) => name,
+            Name::Maintenance(name) => name,
+            Name::Proxy(name) => name,
         }
     }
 
@@ -27,6 +31,8 @@ impl Name {
             Name::DockerHost(_) => "docker host",
             Name::Tag(_) => "tag",
             Name::StatusPage(_) => "status page",
+            Name::Maintenance(_) => "maintenance",
+            Name::Proxy(_) => "proxy",
         }
     }
 }
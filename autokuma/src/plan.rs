@@ -0,0 +1,129 @@
+use crate::{
+    entity::Entity,
+    error::{Error, Result},
+    util::FlattenValue,
+};
+use itertools::Itertools;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// A machine readable representation of the changes a sync is going to apply.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Plan {
+    pub create: Vec<PlanEntry>,
+    pub update: Vec<PlanEntry>,
+    pub delete: Vec<PlanEntry>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PlanEntry {
+    pub instance: String,
+
+    pub id: String,
+
+    #[serde(rename = "type")]
+    pub entity_type: String,
+
+    /// The id of the group a monitor is placed in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub changes: BTreeMap<String, Change>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Change {
+    pub old: Value,
+    pub new: Value,
+}
+
+fn entity_type_name(entity: &Entity) -> String {
+    match entity {
+        Entity::Monitor(monitor) => serde_json::to_value(monitor.monitor_type())
+            .ok()
+            .and_then(|value| value.as_str().map(|s| s.to_owned()))
+            .unwrap_or_default(),
+        Entity::DockerHost(_) => "docker_host".to_owned(),
+        Entity::Notification(_) => "notification".to_owned(),
+        Entity::Tag(_) => "tag".to_owned(),
+        Entity::StatusPage(_) => "status_page".to_owned(),
+        Entity::Maintenance(_) => "maintenance".to_owned(),
+    }
+}
+
+fn flatten_entity(entity: &Entity) -> Result<BTreeMap<String, Value>> {
+    Ok(serde_json::to_value(entity)
+        .map_err(|e| Error::DeserializeError(e.to_string()))?
+        .flatten()?
+        .into_iter()
+        .collect())
+}
+
+impl PlanEntry {
+    pub fn new(instance: &str, id: &String, entity: &Entity) -> Self {
+        Self {
+            instance: instance.to_owned(),
+            id: id.to_owned(),
+            entity_type: entity_type_name(entity),
+            parent: match entity {
+                Entity::Monitor(monitor) => monitor.common().parent_name().clone(),
+                _ => None,
+            },
+            changes: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_changes(
+        instance: &str,
+        id: &String,
+        current: &Entity,
+        new: &Entity,
+    ) -> Result<Self> {
+        let current = flatten_entity(current)?;
+        let new_values = flatten_entity(new)?;
+
+        let changes = current
+            .keys()
+            .chain(new_values.keys())
+            .unique()
+            .filter_map(|key| {
+                let old = current.get(key).cloned().unwrap_or(Value::Null);
+                let new = new_values.get(key).cloned().unwrap_or(Value::Null);
+
+                (old != new).then(|| (key.to_owned(), Change { old, new }))
+            })
+            .collect();
+
+        Ok(Self {
+            changes,
+            ..Self::new(instance, id, new)
+        })
+    }
+}
+
+impl Plan {
+    /// Sort all entries by instance and id, so the plan is stable between runs.
+    pub fn sorted(mut self) -> Self {
+        for entries in [&mut self.create, &mut self.update, &mut self.delete] {
+            entries.sort_by(|a, b| (&a.instance, &a.id).cmp(&(&b.instance, &b.id)));
+        }
+
+        self
+    }
+
+    pub async fn write(&self, path: &str) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| Error::DeserializeError(e.to_string()))?;
+
+        match path {
+            "-" => println!("{}", json),
+            path => tokio::fs::write(path, json)
+                .await
+                .map_err(|e| Error::IO(e.to_string()))?,
+        }
+
+        Ok(())
+    }
+}
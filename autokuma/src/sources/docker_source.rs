@@ -1,9 +1,10 @@
 use crate::{
     app_state::AppState,
-    config,
+    config::{self, DockerHostConfig, DEFAULT_INSTANCE},
     entity::{get_entities_from_labels, Entity},
     error::Result,
     kuma::get_kuma_labels,
+    name::Name,
     sources::source::Source,
 };
 use async_trait::async_trait;
@@ -11,11 +12,105 @@ use bollard::{
     container::ListContainersOptions,
     models::SystemInfo,
     service::{ContainerSummary, ListServicesOptions, Service},
-    Docker,
+    system::EventsOptions,
+    Docker, API_DEFAULT_VERSION,
 };
+use futures_util::StreamExt;
 use itertools::Itertools;
-use kuma_client::util::ResultLogger;
-use std::{collections::HashMap, env, sync::Arc};
+use kuma_client::{monitor::Monitor, util::ResultLogger};
+use log::{debug, warn};
+use std::{collections::HashMap, env, path::Path, sync::Arc, time::Duration};
+use tokio::task::JoinHandle;
+
+fn connect_docker(docker_host: &Option<DockerHostConfig>) -> Result<Docker> {
+    if let Some(DockerHostConfig {
+        url,
+        tls: Some(tls),
+        ..
+    }) = docker_host
+    {
+        return Ok(Docker::connect_with_ssl(
+            url,
+            Path::new(&tls.key),
+            Path::new(&tls.cert),
+            Path::new(&tls.ca),
+            120,
+            API_DEFAULT_VERSION,
+        )
+        .log_warn(std::module_path!(), |_| {
+            format!("Using DOCKER_HOST={}", url)
+        })?);
+    }
+
+    if let Some(docker_host) = docker_host {
+        env::set_var("DOCKER_HOST", &docker_host.url);
+    }
+
+    Ok(
+        Docker::connect_with_defaults().log_warn(std::module_path!(), |_| {
+            format!(
+                "Using DOCKER_HOST={}",
+                env::var("DOCKER_HOST").unwrap_or_else(|_| "None".to_owned())
+            )
+        })?,
+    )
+}
+
+async fn watch_events(state: Arc<AppState>, docker: Docker) {
+    let mut types = vec![];
+
+    if state.config.docker.source == config::DockerSource::Containers
+        || state.config.docker.source == config::DockerSource::Both
+    {
+        types.push("container");
+    }
+
+    if state.config.docker.source == config::DockerSource::Services
+        || state.config.docker.source == config::DockerSource::Both
+    {
+        types.push("service");
+    }
+
+    let filters = HashMap::from([
+        ("type", types),
+        (
+            "event",
+            vec![
+                "create", "start", "stop", "die", "destroy", "rename", "update", "remove",
+            ],
+        ),
+    ]);
+
+    loop {
+        let mut events = docker.events(Some(EventsOptions::<&str> {
+            filters: filters.clone(),
+            ..Default::default()
+        }));
+
+        while let Some(event) = events.next().await {
+            match event {
+                Ok(event) => {
+                    debug!(
+                        "Received docker event: {:?} {:?}",
+                        event.typ,
+                        event.action.unwrap_or_default()
+                    );
+                    state.trigger_sync();
+                }
+                Err(e) => {
+                    warn!(
+                        "Error while listening for docker events, falling back to polling: {}",
+                        e
+                    );
+                    break;
+                }
+            }
+        }
+
+        // Any events missed while reconnecting will be picked up by the regular sync
+        tokio::time::sleep(Duration::from_secs_f64(state.config.sync_interval)).await;
+    }
+}
 
 async fn get_kuma_containers(
     state: Arc<AppState>,
@@ -45,6 +140,7 @@ async fn get_kuma_containers(
                 },
             )
         })
+        .filter(|c| state.config.docker.is_selected(c.labels.as_ref()))
         .collect::<Vec<_>>())
 }
 
@@ -77,9 +173,55 @@ async fn get_kuma_services(state: Arc<AppState>, docker: &Docker) -> Result<Vec<
                 },
             )
         })
+        .filter(|c| {
+            state
+                .config
+                .docker
+                .is_selected(c.spec.as_ref().and_then(|spec| spec.labels.as_ref()))
+        })
         .collect::<Vec<_>>())
 }
 
+/// The values available to templates of a container, including the standard labels set by Docker Compose.
+fn get_container_template_values(
+    system_info: &SystemInfo,
+    container: &ContainerSummary,
+) -> tera::Context {
+    let mut template_values = tera::Context::new();
+    template_values.insert("container_id", &container.id);
+    template_values.insert("image_id", &container.image_id);
+    template_values.insert("image", &container.image);
+    template_values.insert(
+        "container_name",
+        &container
+            .names
+            .as_ref()
+            .and_then(|names| names.first().map(|s| s.trim_start_matches("/").to_owned())),
+    );
+
+    for (key, label) in [
+        ("compose_project", "com.docker.compose.project"),
+        ("compose_service", "com.docker.compose.service"),
+        (
+            "compose_container_number",
+            "com.docker.compose.container-number",
+        ),
+    ] {
+        template_values.insert(
+            key,
+            &container
+                .labels
+                .as_ref()
+                .and_then(|labels| labels.get(label)),
+        );
+    }
+
+    template_values.insert("container", &container);
+    template_values.insert("system_info", system_info);
+
+    template_values
+}
+
 fn get_entities_from_containers(
     state: Arc<AppState>,
     system_info: &SystemInfo,
@@ -88,22 +230,14 @@ fn get_entities_from_containers(
     containers
         .into_iter()
         .map(|container| {
-            let mut template_values = tera::Context::new();
-            template_values.insert("container_id", &container.id);
-            template_values.insert("image_id", &container.image_id);
-            template_values.insert("image", &container.image);
-            template_values.insert(
-                "container_name",
-                &container
-                    .names
-                    .as_ref()
-                    .and_then(|names| names.first().map(|s| s.trim_start_matches("/").to_owned())),
-            );
-
-            template_values.insert("container", &container);
-            template_values.insert("system_info", system_info);
+            let template_values = get_container_template_values(system_info, container);
 
-            let kuma_labels = get_kuma_labels(&state, container.labels.as_ref(), &template_values)?;
+            let kuma_labels = get_kuma_labels(
+                &state,
+                &state.config.docker.label_prefix,
+                container.labels.as_ref(),
+                &template_values,
+            )?;
 
             get_entities_from_labels(state.clone(), kuma_labels, &template_values)
         })
@@ -127,7 +261,12 @@ fn get_entities_from_services(
             let spec = service.spec.as_ref();
             let labels = spec.and_then(|spec| spec.labels.as_ref());
 
-            let kuma_labels = get_kuma_labels(&state, labels, &template_values)?;
+            let kuma_labels = get_kuma_labels(
+                &state,
+                &state.config.docker.label_prefix,
+                labels,
+                &template_values,
+            )?;
 
             get_entities_from_labels(state.clone(), kuma_labels, &template_values)
         })
@@ -137,6 +276,7 @@ fn get_entities_from_services(
 
 pub struct DockerSource {
     state: Arc<AppState>,
+    event_listeners: Vec<JoinHandle<()>>,
 }
 
 #[async_trait]
@@ -145,11 +285,32 @@ impl Source for DockerSource {
         "Docker"
     }
 
+    fn instance(&self) -> &str {
+        self.state
+            .config
+            .docker
+            .instance
+            .as_deref()
+            .unwrap_or(DEFAULT_INSTANCE)
+    }
+
     async fn init(&mut self) -> Result<()> {
+        if self.state.config.docker.enabled && self.state.config.docker.events {
+            for docker_host in self.docker_hosts() {
+                let docker = connect_docker(&docker_host)?;
+                self.event_listeners
+                    .push(tokio::spawn(watch_events(self.state.clone(), docker)));
+            }
+        }
+
         Ok(())
     }
 
     async fn shutdown(&mut self) -> Result<()> {
+        for event_listener in self.event_listeners.drain(..) {
+            event_listener.abort();
+        }
+
         Ok(())
     }
 
@@ -158,36 +319,11 @@ impl Source for DockerSource {
             return Ok(vec![]);
         }
 
-        let docker_hosts = self
-            .state
-            .config
-            .docker
-            .hosts
-            .clone()
-            .map(|f| f.into_iter().map(Some).collect::<Vec<_>>())
-            .unwrap_or_else(|| {
-                vec![self
-                    .state
-                    .config
-                    .docker
-                    .socket_path
-                    .as_ref()
-                    .and_then(|path| Some(format!("unix://{}", path)))]
-            });
-
         let mut entities = vec![];
 
-        for docker_host in docker_hosts {
-            if let Some(docker_host) = &docker_host {
-                env::set_var("DOCKER_HOST", docker_host);
-            }
-
-            let docker = Docker::connect_with_defaults().log_warn(std::module_path!(), |_| {
-                format!(
-                    "Using DOCKER_HOST={}",
-                    env::var("DOCKER_HOST").unwrap_or_else(|_| "None".to_owned())
-                )
-            })?;
+        for docker_host in self.docker_hosts() {
+            let docker = connect_docker(&docker_host)?;
+            let mut host_entities = vec![];
 
             let system_info: bollard::secret::SystemInfo = docker.info().await.unwrap_or_default();
 
@@ -195,7 +331,7 @@ impl Source for DockerSource {
                 || self.state.config.docker.source == config::DockerSource::Both
             {
                 let containers = get_kuma_containers(self.state.clone(), &docker).await?;
-                entities.extend(get_entities_from_containers(
+                host_entities.extend(get_entities_from_containers(
                     self.state.clone(),
                     &system_info,
                     &containers,
@@ -206,12 +342,21 @@ impl Source for DockerSource {
                 || self.state.config.docker.source == config::DockerSource::Both
             {
                 let services = get_kuma_services(self.state.clone(), &docker).await?;
-                entities.extend(get_entities_from_services(
+                host_entities.extend(get_entities_from_services(
                     self.state.clone(),
                     &system_info,
                     &services,
                 )?);
             }
+
+            match &docker_host {
+                Some(docker_host) => entities.extend(
+                    host_entities
+                        .into_iter()
+                        .map(|(id, entity)| self.apply_host_config(docker_host, id, entity)),
+                ),
+                None => entities.extend(host_entities),
+            }
         }
 
         Ok(entities)
@@ -220,6 +365,169 @@ impl Source for DockerSource {
 
 impl DockerSource {
     pub fn new(state: Arc<AppState>) -> Self {
-        Self { state }
+        Self {
+            state,
+            event_listeners: vec![],
+        }
+    }
+
+    fn docker_hosts(&self) -> Vec<Option<DockerHostConfig>> {
+        self.state
+            .config
+            .docker
+            .hosts
+            .clone()
+            .map(|f| f.into_iter().map(Some).collect::<Vec<_>>())
+            .unwrap_or_else(|| {
+                vec![self
+                    .state
+                    .config
+                    .docker
+                    .socket_path
+                    .as_ref()
+                    .and_then(|path| Some(format!("unix://{}", path).into()))]
+            })
+    }
+
+    fn apply_host_config(
+        &self,
+        docker_host: &DockerHostConfig,
+        id: String,
+        mut entity: Entity,
+    ) -> (String, Entity) {
+        if let (Entity::Monitor(Monitor::Docker { value: monitor }), Some(docker_host_name)) =
+            (&mut entity, &docker_host.docker_host)
+        {
+            if monitor.docker_host.is_none() && monitor.docker_host_name.is_none() {
+                // AutoKuma ids are resolved during the sync, so docker hosts created in the same sync are found
+                let is_autokuma_id = self.state.db(self.instance()).is_some_and(|db| {
+                    db.get_id::<i32>(Name::DockerHost(docker_host_name.clone()))
+                        .ok()
+                        .flatten()
+                        .is_some()
+                });
+
+                match docker_host_name.parse() {
+                    Ok(docker_host_id) if !is_autokuma_id => {
+                        monitor.docker_host = Some(docker_host_id)
+                    }
+                    _ => monitor.docker_host_name = Some(docker_host_name.clone()),
+                }
+            }
+        }
+
+        match docker_host.namespace() {
+            Some(namespace) => (format!("{}/{}", namespace, id), entity),
+            None => (id, entity),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TestState;
+    use serde_json::json;
+
+    fn compose_container() -> ContainerSummary {
+        ContainerSummary {
+            id: Some("abc123".to_owned()),
+            names: Some(vec!["/shop-web-1".to_owned()]),
+            labels: Some(HashMap::from([
+                ("com.docker.compose.project".to_owned(), "shop".to_owned()),
+                ("com.docker.compose.service".to_owned(), "web".to_owned()),
+                (
+                    "com.docker.compose.container-number".to_owned(),
+                    "1".to_owned(),
+                ),
+                (
+                    "kuma.web.http.name".to_owned(),
+                    "{{ compose_project }}/{{ compose_service }} #{{ compose_container_number }}"
+                        .to_owned(),
+                ),
+                (
+                    "kuma.web.http.url".to_owned(),
+                    "https://example.com".to_owned(),
+                ),
+            ])),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn compose_labels_are_template_values() {
+        let template_values =
+            get_container_template_values(&SystemInfo::default(), &compose_container());
+
+        assert_eq!(template_values.get("compose_project"), Some(&json!("shop")));
+        assert_eq!(template_values.get("compose_service"), Some(&json!("web")));
+        assert_eq!(
+            template_values.get("compose_container_number"),
+            Some(&json!("1"))
+        );
+        assert_eq!(
+            template_values.get("container_name"),
+            Some(&json!("shop-web-1"))
+        );
+    }
+
+    #[test]
+    fn compose_labels_can_be_used_in_templates() {
+        let test_state = TestState::new(json!({}));
+
+        let entities = get_entities_from_containers(
+            test_state.state.clone(),
+            &SystemInfo::default(),
+            &vec![compose_container()],
+        )
+        .unwrap();
+
+        let Some(Entity::Monitor(monitor)) = entities.get("web") else {
+            panic!("Expected a monitor, got {:?}", entities);
+        };
+        assert_eq!(monitor.common().name(), &Some("shop/web #1".to_owned()));
+    }
+
+    #[test]
+    fn host_docker_host_is_resolved_during_the_sync() {
+        let test_state = TestState::new(json!({}));
+        let source = DockerSource::new(test_state.state.clone());
+
+        let docker_monitor = || {
+            Entity::Monitor(
+                serde_json::from_value(json!({
+                    "type": "docker",
+                    "name": "Web",
+                    "docker_container": "web",
+                }))
+                .unwrap(),
+            )
+        };
+        let docker_host = |name: Option<&str>, docker_host: &str| DockerHostConfig {
+            name: name.map(str::to_owned),
+            docker_host: Some(docker_host.to_owned()),
+            ..DockerHostConfig::from("tcp://10.0.0.2:2376".to_owned())
+        };
+
+        let (id, entity) = source.apply_host_config(
+            &docker_host(Some("remote"), "remote_docker"),
+            "web".to_owned(),
+            docker_monitor(),
+        );
+        let Entity::Monitor(Monitor::Docker { value: monitor }) = entity else {
+            panic!("Expected a docker monitor, got {:?}", entity);
+        };
+        assert_eq!(id, "remote/web");
+        assert_eq!(monitor.docker_host, None);
+        assert_eq!(monitor.docker_host_name, Some("remote_docker".to_owned()));
+
+        let (id, entity) =
+            source.apply_host_config(&docker_host(None, "3"), "web".to_owned(), docker_monitor());
+        let Entity::Monitor(Monitor::Docker { value: monitor }) = entity else {
+            panic!("Expected a docker monitor, got {:?}", entity);
+        };
+        assert_eq!(id, "web");
+        assert_eq!(monitor.docker_host, Some(3));
+        assert_eq!(monitor.docker_host_name, None);
     }
 }
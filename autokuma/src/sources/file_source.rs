@@ -1,5 +1,6 @@
 use crate::{
     app_state::AppState,
+    config::DEFAULT_INSTANCE,
     entity::{get_entity_from_value, Entity},
     error::{Error, Result},
     sources::source::Source,
@@ -7,13 +8,35 @@ use crate::{
 use async_trait::async_trait;
 use itertools::Itertools;
 use kuma_client::util::ResultLogger;
+use log::{debug, warn};
+use notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
 use serde_json::json;
 use std::{
+    collections::{hash_map::Entry, HashMap},
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 use walkdir::WalkDir;
 
+/// A document containing multiple entities is a map of `id -> entity`, while a single entity always has a `type`.
+fn is_multi_entity_map(value: &serde_json::Map<String, serde_json::Value>) -> bool {
+    !value.contains_key("type") && !value.is_empty() && value.values().all(|v| v.is_object())
+}
+
+/// Entities in an array can specify their id using an `id` string, otherwise the id is derived from the file name and index.
+fn take_entity_id(value: &mut serde_json::Value) -> Option<String> {
+    let entity = value.as_object_mut()?;
+
+    match entity.get("id") {
+        Some(serde_json::Value::String(_)) => entity
+            .remove("id")
+            .and_then(|id| id.as_str().map(|id| id.to_owned())),
+        _ => None,
+    }
+}
+
 async fn get_entities_from_file<P1: AsRef<Path>, P2: AsRef<Path>>(
     state: Arc<AppState>,
     base_path: P1,
@@ -35,6 +58,15 @@ async fn get_entities_from_file<P1: AsRef<Path>, P2: AsRef<Path>>(
             .map_err(|e| Error::IO(e.to_string()))?;
 
         Some(toml::from_str(&content).map_err(|e| Error::DeserializeError(e.to_string()))?)
+    } else if file
+        .extension()
+        .is_some_and(|ext| ext == "yaml" || ext == "yml")
+    {
+        let content = tokio::fs::read_to_string(file_path)
+            .await
+            .map_err(|e| Error::IO(e.to_string()))?;
+
+        Some(serde_yaml::from_str(&content).map_err(|e| Error::DeserializeError(e.to_string()))?)
     } else {
         None
     };
@@ -52,7 +84,7 @@ async fn get_entities_from_file<P1: AsRef<Path>, P2: AsRef<Path>>(
 
     let value = value.ok_or_else(|| {
         Error::DeserializeError(format!(
-            "Unsupported static monitor file type: {}, supported: .json, .toml",
+            "Unsupported static monitor file type: {}, supported: .json, .toml, .yaml",
             file.display()
         ))
     })?;
@@ -61,9 +93,9 @@ async fn get_entities_from_file<P1: AsRef<Path>, P2: AsRef<Path>>(
         serde_json::Value::Array(entities) => entities
             .into_iter()
             .enumerate()
-            .map(|(i, value)| {
+            .map(|(i, mut value)| {
                 (
-                    format!("{}[{}]", file_id, i),
+                    take_entity_id(&mut value).unwrap_or_else(|| format!("{}[{}]", file_id, i)),
                     value,
                     tera::Context::from_value(json!({
                         "file_index": i,
@@ -72,6 +104,19 @@ async fn get_entities_from_file<P1: AsRef<Path>, P2: AsRef<Path>>(
                 )
             })
             .collect(),
+        serde_json::Value::Object(entities) if is_multi_entity_map(&entities) => entities
+            .into_iter()
+            .map(|(id, value)| {
+                (
+                    id.clone(),
+                    value,
+                    tera::Context::from_value(json!({
+                        "file_key": id,
+                    }))
+                    .unwrap(),
+                )
+            })
+            .collect(),
         _ => vec![(file_id, value, tera::Context::new())],
     };
 
@@ -85,6 +130,7 @@ async fn get_entities_from_file<P1: AsRef<Path>, P2: AsRef<Path>>(
             r.log_warn(std::module_path!(), |e| {
                 format!("[{}] {}", file.display(), e)
             })
+            .inspect_err(|_| state.record_failure())
             .ok()
         })
         .collect();
@@ -94,6 +140,7 @@ async fn get_entities_from_file<P1: AsRef<Path>, P2: AsRef<Path>>(
 
 pub struct FileSource {
     state: Arc<AppState>,
+    watcher: Option<Debouncer<RecommendedWatcher>>,
 }
 
 #[async_trait]
@@ -102,41 +149,40 @@ impl Source for FileSource {
         "File"
     }
 
+    fn instance(&self) -> &str {
+        self.state
+            .config
+            .files
+            .instance
+            .as_deref()
+            .unwrap_or(DEFAULT_INSTANCE)
+    }
+
     async fn init(&mut self) -> Result<()> {
+        if self.state.config.files.watch {
+            self.watch().await?;
+        }
+
         Ok(())
     }
 
     async fn shutdown(&mut self) -> Result<()> {
+        self.watcher = None;
         Ok(())
     }
 
     async fn get_entities(&mut self) -> Result<Vec<(String, Entity)>> {
         let mut entities = vec![];
+        let mut origins: HashMap<String, PathBuf> = HashMap::new();
 
-        let static_monitor_path = self
-            .state
-            .config
-            .static_monitors
-            .clone()
-            .unwrap_or_else(|| {
-                dirs::config_local_dir()
-                    .map(|dir| {
-                        dir.join("autokuma")
-                            .join("static-monitors")
-                            .to_string_lossy()
-                            .to_string()
-                    })
-                    .unwrap_or_default()
-            })
-            .to_owned();
-
-        let static_monitor_path = PathBuf::from(&static_monitor_path);
+        let static_monitor_path = self.static_monitor_path();
         if tokio::fs::metadata(&static_monitor_path)
             .await
             .is_ok_and(|md| md.is_dir())
         {
             let files = WalkDir::new(&static_monitor_path)
                 .follow_links(self.state.config.files.follow_symlinks)
+                .sort_by_file_name()
                 .into_iter()
                 .filter_entry(|e| {
                     e.file_name()
@@ -153,14 +199,32 @@ impl Source for FileSource {
             for file in files {
                 let file_path = file.path().strip_prefix(&static_monitor_path).unwrap();
 
-                if let Ok(file_entities) =
-                    get_entities_from_file(self.state.clone(), &static_monitor_path, file_path)
-                        .await
-                        .log_warn(std::module_path!(), |e| {
-                            format!("[{}] {}", file.path().display(), e)
-                        })
-                {
-                    entities.extend(file_entities);
+                match get_entities_from_file(self.state.clone(), &static_monitor_path, file_path)
+                    .await
+                    .log_warn(std::module_path!(), |e| {
+                        format!("[{}] {}", file.path().display(), e)
+                    }) {
+                    Ok(file_entities) => {
+                        // Ids are taken from map keys or `id` fields as is, files are read in a fixed order
+                        // so if multiple files define the same id the definition of the first one is used
+                        for (id, entity) in file_entities {
+                            match origins.entry(id.clone()) {
+                                Entry::Occupied(origin) => warn!(
+                                    "{} {} from {} is also defined in {}, ignoring the definition from {}",
+                                    entity.entity_type(),
+                                    id,
+                                    file_path.display(),
+                                    origin.get().display(),
+                                    file_path.display()
+                                ),
+                                Entry::Vacant(origin) => {
+                                    origin.insert(file_path.to_owned());
+                                    entities.push((id, entity));
+                                }
+                            }
+                        }
+                    }
+                    Err(_) => self.state.record_failure(),
                 }
             }
         }
@@ -171,6 +235,181 @@ impl Source for FileSource {
 
 impl FileSource {
     pub fn new(state: Arc<AppState>) -> Self {
-        Self { state }
+        Self {
+            state,
+            watcher: None,
+        }
+    }
+
+    fn static_monitor_path(&self) -> PathBuf {
+        self.state.config.static_monitors_path()
+    }
+
+    async fn watch(&mut self) -> Result<()> {
+        let static_monitor_path = self.static_monitor_path();
+
+        if !tokio::fs::metadata(&static_monitor_path)
+            .await
+            .is_ok_and(|md| md.is_dir())
+        {
+            warn!(
+                "Unable to watch {} for changes, the directory does not exist",
+                static_monitor_path.display()
+            );
+            return Ok(());
+        }
+
+        let state = self.state.clone();
+
+        // Events are debounced to coalesce the multiple writes/renames editors typically do when saving a file,
+        // the whole directory is watched so atomic-rename saves and deleted files are picked up as well.
+        let mut debouncer = new_debouncer(
+            Duration::from_millis(500),
+            move |result: DebounceEventResult| match result {
+                Ok(events) => {
+                    debug!(
+                        "Static monitors changed: {}",
+                        events.iter().map(|e| e.path.display()).join(", ")
+                    );
+                    state.trigger_sync();
+                }
+                Err(e) => warn!("Error while watching static monitors: {}", e),
+            },
+        )
+        .map_err(|e| Error::IO(e.to_string()))?;
+
+        debouncer
+            .watcher()
+            .watch(&static_monitor_path, RecursiveMode::Recursive)
+            .map_err(|e| Error::IO(e.to_string()))?;
+
+        self.watcher = Some(debouncer);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TestState;
+
+    async fn get_entities(files: &[(&str, &str)]) -> Vec<(String, Entity)> {
+        // Hidden files are skipped, including the directory itself, so avoid the default `.tmp` prefix
+        let monitors = tempfile::Builder::new()
+            .prefix("static-monitors")
+            .tempdir()
+            .unwrap();
+        for (name, content) in files {
+            let path = monitors.path().join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        let test = TestState::new(json!({
+            "static_monitors": monitors.path().to_string_lossy(),
+        }));
+
+        let mut entities = FileSource::new(test.state.clone())
+            .get_entities()
+            .await
+            .unwrap();
+        entities.sort_by(|(a, _), (b, _)| a.cmp(b));
+        entities
+    }
+
+    fn ids(entities: &[(String, Entity)]) -> Vec<&str> {
+        entities.iter().map(|(id, _)| id.as_str()).collect()
+    }
+
+    fn name(entity: &Entity) -> Option<String> {
+        match entity {
+            Entity::Monitor(monitor) => monitor.common().name().clone(),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn single_entity_files() {
+        let entities = get_entities(&[
+            (
+                "toml.toml",
+                "type = \"http\"\nname = \"Toml\"\nurl = \"https://example.com\"\n",
+            ),
+            (
+                "json.json",
+                r#"{"type": "http", "name": "Json", "url": "https://example.com"}"#,
+            ),
+            (
+                "group/yaml.yaml",
+                "type: http\nname: Yaml\nurl: https://example.com\n",
+            ),
+        ])
+        .await;
+
+        assert_eq!(ids(&entities), vec!["group/yaml", "json", "toml"]);
+        assert_eq!(name(&entities[0].1), Some("Yaml".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn multi_entity_maps() {
+        let entities = get_entities(&[
+            (
+                "monitors.yaml",
+                "yaml_a:\n  type: http\n  name: A\n  url: https://a.example.com\nyaml_b:\n  type: ping\n  name: B\n  hostname: b.example.com\n",
+            ),
+            (
+                "monitors.json",
+                r#"{"json_a": {"type": "http", "name": "A", "url": "https://a.example.com"}}"#,
+            ),
+            (
+                "monitors.toml",
+                "[toml_a]\ntype = \"http\"\nname = \"A\"\nurl = \"https://a.example.com\"\n",
+            ),
+        ])
+        .await;
+
+        assert_eq!(ids(&entities), vec!["json_a", "toml_a", "yaml_a", "yaml_b"]);
+        assert!(matches!(
+            &entities[3].1,
+            Entity::Monitor(monitor) if monitor.monitor_type() == kuma_client::monitor::MonitorType::Ping
+        ));
+    }
+
+    #[tokio::test]
+    async fn multi_entity_arrays() {
+        let entities = get_entities(&[
+            (
+                "monitors.yml",
+                "- id: first\n  type: http\n  name: First\n  url: https://a.example.com\n- type: http\n  name: Second\n  url: https://b.example.com\n",
+            ),
+            (
+                "monitors.json",
+                r#"[{"type": "http", "name": "Json", "url": "https://example.com"}]"#,
+            ),
+        ])
+        .await;
+
+        assert_eq!(ids(&entities), vec!["first", "monitors[0]", "monitors[1]"]);
+        assert_eq!(name(&entities[1].1), Some("Json".to_owned()));
+        assert_eq!(name(&entities[2].1), Some("Second".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn duplicate_ids_keep_the_first_file() {
+        let entities = get_entities(&[
+            (
+                "a.yaml",
+                "shared:\n  type: http\n  name: From A\n  url: https://a.example.com\n",
+            ),
+            (
+                "b.yaml",
+                "shared:\n  type: http\n  name: From B\n  url: https://b.example.com\n",
+            ),
+        ])
+        .await;
+
+        assert_eq!(ids(&entities), vec!["shared"]);
+        assert_eq!(name(&entities[0].1), Some("From A".to_owned()));
     }
 }
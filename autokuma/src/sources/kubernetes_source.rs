@@ -1,11 +1,14 @@
 use crate::{
     app_state::AppState,
-    entity::{get_entity_from_value, Entity},
+    config::DEFAULT_INSTANCE,
+    entity::{get_entity_from_settings, get_entity_from_value, Entity},
     error::{Error, K8SError, Result},
     sources::source::Source,
 };
 use async_trait::async_trait;
 use futures_util::StreamExt;
+use itertools::Itertools;
+use k8s_openapi::api::networking::v1::Ingress;
 use kube::{
     api::ListParams,
     runtime::{
@@ -20,7 +23,12 @@ use kuma_client::util::ResultLogger;
 use log::{error, info, trace, warn};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, sync::Arc, time::Duration};
+use serde_json::json;
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
 use tokio::sync::Mutex;
 
 pub static ENTITY_FINALIZER: &str = "entity.autokuma.bigboot.dev";
@@ -88,8 +96,101 @@ impl KumaEntity {
     }
 }
 
+fn get_entities_from_ingress(state: Arc<AppState>, ingress: &Ingress) -> Vec<(String, Entity)> {
+    let namespace = ingress.namespace().unwrap_or_default();
+    let name = ingress.name_any();
+    let prefix = format!("{}.", state.config.kubernetes.ingress.annotation_prefix);
+
+    let overrides = ingress
+        .annotations()
+        .iter()
+        .filter_map(|(key, value)| {
+            key.strip_prefix(&prefix)
+                .map(|key| (key.to_owned(), json!(value)))
+        })
+        .collect_vec();
+
+    let entity_type = overrides
+        .iter()
+        .find(|(key, _)| key == "type")
+        .and_then(|(_, value)| value.as_str())
+        .unwrap_or("http")
+        .to_owned();
+
+    let spec = ingress.spec.as_ref();
+
+    let tls_hosts = spec
+        .and_then(|spec| spec.tls.as_ref())
+        .into_iter()
+        .flatten()
+        .flat_map(|tls| tls.hosts.clone().unwrap_or_default())
+        .collect::<HashSet<_>>();
+
+    spec.and_then(|spec| spec.rules.as_ref())
+        .into_iter()
+        .flatten()
+        .filter_map(|rule| rule.host.as_ref().map(|host| (host, rule)))
+        .filter(|(host, _)| !host.starts_with("*"))
+        .flat_map(|(host, rule)| {
+            let paths = rule
+                .http
+                .as_ref()
+                .map(|http| {
+                    http.paths
+                        .iter()
+                        .map(|path| path.path.clone().unwrap_or_else(|| "/".to_owned()))
+                        .collect_vec()
+                })
+                .unwrap_or_else(|| vec!["/".to_owned()]);
+
+            paths
+                .into_iter()
+                .unique()
+                .map(|path| (host.to_owned(), path))
+                .collect_vec()
+        })
+        .filter_map(|(host, path)| {
+            let id = match path.trim_end_matches('/') {
+                "" => format!("ingress-{}-{}-{}", namespace, name, host),
+                path => format!("ingress-{}-{}-{}{}", namespace, name, host, path),
+            };
+
+            let scheme = match tls_hosts.contains(&host) {
+                true => "https",
+                false => "http",
+            };
+
+            let url = format!("{}://{}{}", scheme, host, path);
+
+            let mut context = tera::Context::new();
+            context.insert("ingress", ingress);
+            context.insert("host", &host);
+            context.insert("path", &path);
+            context.insert("url", &url);
+
+            let settings = overrides
+                .iter()
+                .filter(|(key, _)| key != "type")
+                .cloned()
+                .chain(vec![
+                    ("name".to_owned(), json!(format!("{}{}", host, path))),
+                    ("url".to_owned(), json!(url)),
+                ])
+                .collect_vec();
+
+            get_entity_from_settings(state.clone(), &id, &entity_type, settings, &context)
+                .log_warn(std::module_path!(), |e| {
+                    format!("[ingress {}/{}] {}", namespace, name, e)
+                })
+                .ok()
+                .map(|entity| (id, entity))
+        })
+        .collect()
+}
+
 pub struct KubernetesSource {
     state: Arc<AppState>,
+    client: Option<Client>,
     shutdown: Option<tokio::sync::mpsc::Sender<()>>,
     task: Option<tokio::task::JoinHandle<()>>,
     entities: Arc<Mutex<BTreeMap<String, Entity>>>,
@@ -101,6 +202,15 @@ impl Source for KubernetesSource {
         "Kubernetes"
     }
 
+    fn instance(&self) -> &str {
+        self.state
+            .config
+            .kubernetes
+            .instance
+            .as_deref()
+            .unwrap_or(DEFAULT_INSTANCE)
+    }
+
     async fn init(&mut self) -> Result<()> {
         let client = Client::try_default()
             .await
@@ -113,6 +223,8 @@ impl Source for KubernetesSource {
             std::process::exit(1);
         }
 
+        self.client = Some(client.clone());
+
         let (shutdown_tx, mut shutdown_rx) = tokio::sync::mpsc::channel(1);
         let state = self.state.clone();
         let entities = self.entities.clone();
@@ -154,12 +266,28 @@ impl Source for KubernetesSource {
     }
 
     async fn get_entities(&mut self) -> Result<Vec<(String, Entity)>> {
-        let entities = self.entities.lock().await;
-
-        Ok(entities
+        let mut entities = self
+            .entities
+            .lock()
+            .await
             .iter()
             .map(|(k, v)| (k.clone(), v.clone()))
-            .collect())
+            .collect_vec();
+
+        if let (true, Some(client)) = (self.state.config.kubernetes.ingress.enabled, &self.client) {
+            let ingresses = Api::<Ingress>::all(client.clone())
+                .list(&ListParams::default())
+                .await
+                .map_err(|e| Error::K8S(K8SError::Kube(e)))?;
+
+            entities.extend(
+                ingresses
+                    .iter()
+                    .flat_map(|ingress| get_entities_from_ingress(self.state.clone(), ingress)),
+            );
+        }
+
+        Ok(entities)
     }
 }
 
@@ -167,6 +295,7 @@ impl KubernetesSource {
     pub fn new(state: Arc<AppState>) -> Self {
         Self {
             state,
+            client: None,
             shutdown: None,
             task: None,
             entities: Arc::new(Mutex::new(BTreeMap::new())),
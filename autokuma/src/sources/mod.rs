@@ -9,6 +9,12 @@ pub mod source;
 #[cfg(feature = "kubernetes")]
 pub mod kubernetes_source;
 
+#[cfg(feature = "systemd")]
+pub mod systemd_source;
+
+#[cfg(feature = "nomad")]
+pub mod nomad_source;
+
 pub fn get_sources(state: Arc<AppState>) -> Vec<Box<dyn source::Source>> {
     let mut sources: Vec<Box<dyn source::Source>> = vec![];
 
@@ -27,5 +33,15 @@ pub fn get_sources(state: Arc<AppState>) -> Vec<Box<dyn source::Source>> {
         )));
     }
 
+    if state.config.systemd.enabled {
+        #[cfg(feature = "systemd")]
+        sources.push(Box::new(systemd_source::SystemdSource::new(state.clone())));
+    }
+
+    if state.config.nomad.enabled {
+        #[cfg(feature = "nomad")]
+        sources.push(Box::new(nomad_source::NomadSource::new(state.clone())));
+    }
+
     sources
 }
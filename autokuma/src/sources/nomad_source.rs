@@ -0,0 +1,419 @@
+use crate::{
+    app_state::AppState,
+    config::DEFAULT_INSTANCE,
+    entity::{get_entities_from_labels, Entity},
+    error::{Error, Result},
+    kuma::get_kuma_labels,
+    sources::source::Source,
+};
+use async_trait::async_trait;
+use itertools::Itertools;
+use kuma_client::monitor::Monitor;
+use reqwest::header::{HeaderMap, HeaderValue};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct NamespaceServices {
+    namespace: String,
+    #[serde(default)]
+    services: Vec<ServiceStub>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ServiceStub {
+    service_name: String,
+    tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct ServiceRegistration {
+    #[serde(rename = "ID")]
+    id: String,
+    service_name: String,
+    namespace: String,
+    #[serde(rename = "NodeID")]
+    node_id: String,
+    datacenter: String,
+    #[serde(rename = "JobID")]
+    job_id: String,
+    #[serde(rename = "AllocID")]
+    alloc_id: String,
+    tags: Option<Vec<String>>,
+    address: String,
+    port: u16,
+}
+
+/// Parse the `<label_prefix>.<key>=<value>` tags of a service into labels, other tags are ignored.
+fn parse_service_tags(tags: &[String]) -> HashMap<String, String> {
+    tags.iter()
+        .filter_map(|tag| tag.split_once('='))
+        .map(|(key, value)| (key.trim().to_owned(), value.trim().to_owned()))
+        .collect()
+}
+
+fn has_autokuma_tags(state: &AppState, tags: &[String]) -> bool {
+    parse_service_tags(tags).keys().any(|key| {
+        key.starts_with(&format!("{}.", state.config.nomad.label_prefix))
+            || state.config.snippets.contains_key(&format!("!{}", key))
+    })
+}
+
+fn get_entities_from_service(
+    state: Arc<AppState>,
+    service: &ServiceRegistration,
+) -> Result<Vec<(String, Entity)>> {
+    let labels = parse_service_tags(service.tags.as_deref().unwrap_or_default());
+
+    let mut template_values = tera::Context::new();
+    template_values.insert("nomad_namespace", &service.namespace);
+    template_values.insert("nomad_job_id", &service.job_id);
+    template_values.insert("nomad_alloc_id", &service.alloc_id);
+    template_values.insert("nomad_service_name", &service.service_name);
+    template_values.insert("nomad_address", &service.address);
+    template_values.insert("nomad_port", &service.port);
+    template_values.insert("nomad_datacenter", &service.datacenter);
+    template_values.insert("nomad_node_id", &service.node_id);
+    template_values.insert("service", service);
+
+    let kuma_labels = get_kuma_labels(
+        &state,
+        &state.config.nomad.label_prefix,
+        Some(&labels),
+        &template_values,
+    )?;
+
+    let entities = get_entities_from_labels(state, kuma_labels, &template_values)?;
+    let prefix = format!("{}/{}/", service.namespace, service.job_id);
+    let ids = entities
+        .iter()
+        .map(|(id, _)| id.clone())
+        .collect::<HashSet<_>>();
+
+    Ok(entities
+        .into_iter()
+        .map(|(id, mut entity)| {
+            prefix_references(&mut entity, &ids, &prefix);
+            (format!("{}{}", prefix, id), entity)
+        })
+        .collect())
+}
+
+/// Prefix the references of an entity to other entities of the same job, i.e. one of `ids`,
+/// references to entities defined elsewhere are left as is.
+fn prefix_references(entity: &mut Entity, ids: &HashSet<String>, prefix: &str) {
+    let prefix_reference = |name: &mut String| {
+        if ids.contains(name) {
+            *name = format!("{}{}", prefix, name);
+        }
+    };
+
+    match entity {
+        Entity::Monitor(monitor) => {
+            let common = monitor.common_mut();
+
+            common
+                .parent_name_mut()
+                .iter_mut()
+                .for_each(prefix_reference);
+            common
+                .notification_names_mut()
+                .iter_mut()
+                .flatten()
+                .for_each(prefix_reference);
+            common
+                .tag_names_mut()
+                .iter_mut()
+                .flatten()
+                .for_each(|tag| prefix_reference(&mut tag.name));
+
+            if let Monitor::Docker { value } = monitor {
+                value.docker_host_name.iter_mut().for_each(prefix_reference);
+            }
+        }
+        Entity::Maintenance(maintenance) => {
+            maintenance
+                .common_mut()
+                .monitor_names
+                .iter_mut()
+                .flatten()
+                .for_each(prefix_reference);
+        }
+        Entity::StatusPage(status_page) => {
+            status_page
+                .public_group_list
+                .iter_mut()
+                .flatten()
+                .flat_map(|group| group.monitor_list.iter_mut())
+                .flat_map(|monitor| monitor.monitor_name.iter_mut())
+                .for_each(prefix_reference);
+        }
+        Entity::DockerHost(_) | Entity::Notification(_) | Entity::Tag(_) => {}
+    }
+}
+
+/// Every allocation of a job registers the same service, only use one registration per job and namespace
+/// so the entities don't change when allocations are added, replaced or rescheduled.
+/// The registration with the lowest address and port is used, as allocation ids are random.
+fn select_registrations(
+    state: &AppState,
+    registrations: Vec<ServiceRegistration>,
+) -> Vec<ServiceRegistration> {
+    registrations
+        .into_iter()
+        .filter(|registration| {
+            has_autokuma_tags(state, registration.tags.as_deref().unwrap_or_default())
+        })
+        .sorted_by(|a, b| {
+            (&a.namespace, &a.job_id, &a.address, a.port, &a.id).cmp(&(
+                &b.namespace,
+                &b.job_id,
+                &b.address,
+                b.port,
+                &b.id,
+            ))
+        })
+        .unique_by(|registration| (registration.namespace.clone(), registration.job_id.clone()))
+        .collect()
+}
+
+pub struct NomadSource {
+    state: Arc<AppState>,
+    client: Option<reqwest::Client>,
+}
+
+impl NomadSource {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self {
+            state,
+            client: None,
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str, namespace: &str) -> Result<T> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| Error::InternalError("Nomad client not initialized".to_owned()))?;
+
+        client
+            .get(format!(
+                "{}{}",
+                self.state.config.nomad.address.trim_end_matches('/'),
+                path
+            ))
+            .query(&[("namespace", namespace)])
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| Error::Nomad(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| Error::Nomad(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl Source for NomadSource {
+    fn name(&self) -> &'static str {
+        "Nomad"
+    }
+
+    fn instance(&self) -> &str {
+        self.state
+            .config
+            .nomad
+            .instance
+            .as_deref()
+            .unwrap_or(DEFAULT_INSTANCE)
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        let mut headers = HeaderMap::new();
+
+        if let Some(token) = &self.state.config.nomad.token {
+            headers.insert(
+                "X-Nomad-Token",
+                HeaderValue::from_str(token).map_err(|_| {
+                    Error::InvalidConfig("nomad.token".to_owned(), "<redacted>".to_owned())
+                })?,
+            );
+        }
+
+        self.client = Some(
+            reqwest::Client::builder()
+                .default_headers(headers)
+                .build()
+                .map_err(|e| Error::Nomad(e.to_string()))?,
+        );
+
+        Ok(())
+    }
+
+    async fn shutdown(&mut self) -> Result<()> {
+        self.client = None;
+        Ok(())
+    }
+
+    async fn get_entities(&mut self) -> Result<Vec<(String, Entity)>> {
+        let namespaces: Vec<NamespaceServices> = self
+            .get("/v1/services", &self.state.config.nomad.namespace)
+            .await?;
+
+        let services = namespaces
+            .iter()
+            .flat_map(|namespace| {
+                namespace
+                    .services
+                    .iter()
+                    .map(move |service| (&namespace.namespace, service))
+            })
+            .filter(|(_, service)| {
+                has_autokuma_tags(&self.state, service.tags.as_deref().unwrap_or_default())
+            })
+            .collect_vec();
+
+        let mut entities = vec![];
+        for (namespace, service) in services {
+            let registrations: Vec<ServiceRegistration> = self
+                .get(&format!("/v1/service/{}", service.service_name), namespace)
+                .await?;
+
+            for registration in select_registrations(&self.state, registrations) {
+                entities.extend(get_entities_from_service(
+                    self.state.clone(),
+                    &registration,
+                )?);
+            }
+        }
+
+        Ok(entities)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TestState;
+    use serde_json::json;
+
+    fn registration(job_id: &str, alloc_id: &str, address: &str, port: u16) -> ServiceRegistration {
+        ServiceRegistration {
+            id: format!("_nomad-task-{}-web", alloc_id),
+            service_name: "web".to_owned(),
+            namespace: "default".to_owned(),
+            node_id: "node".to_owned(),
+            datacenter: "dc1".to_owned(),
+            job_id: job_id.to_owned(),
+            alloc_id: alloc_id.to_owned(),
+            tags: Some(vec![
+                "kuma.web.http.name=Web".to_owned(),
+                "kuma.web.http.url=http://{{ nomad_address }}:{{ nomad_port }}".to_owned(),
+            ]),
+            address: address.to_owned(),
+            port,
+        }
+    }
+
+    #[test]
+    fn one_registration_is_selected_per_job() {
+        let test_state = TestState::new(json!({}));
+
+        let selected = select_registrations(
+            &test_state.state,
+            vec![
+                registration("shop", "0b1e", "10.0.0.2", 8080),
+                registration("shop", "f3a9", "10.0.0.1", 8081),
+                registration("shop", "a7c2", "10.0.0.1", 8080),
+                registration("blog", "1d4f", "10.0.0.3", 80),
+            ],
+        );
+
+        assert_eq!(
+            selected
+                .iter()
+                .map(|registration| registration.alloc_id.as_str())
+                .collect_vec(),
+            vec!["1d4f", "a7c2"]
+        );
+    }
+
+    #[test]
+    fn jobs_in_different_namespaces_are_selected() {
+        let test_state = TestState::new(json!({}));
+
+        let mut other = registration("shop", "c5d8", "10.0.0.4", 8080);
+        other.namespace = "staging".to_owned();
+
+        let selected = select_registrations(
+            &test_state.state,
+            vec![registration("shop", "a7c2", "10.0.0.1", 8080), other],
+        );
+
+        assert_eq!(
+            selected
+                .iter()
+                .map(|registration| registration.alloc_id.as_str())
+                .collect_vec(),
+            vec!["a7c2", "c5d8"]
+        );
+    }
+
+    #[test]
+    fn references_to_entities_of_the_same_job_are_prefixed() {
+        let test_state = TestState::new(json!({}));
+
+        let mut registration = registration("shop", "a7c2", "10.0.0.1", 8080);
+        registration.tags = Some(vec![
+            "kuma.group.group.name=Shop".to_owned(),
+            "kuma.web.http.name=Web".to_owned(),
+            "kuma.web.http.url=http://{{ nomad_address }}:{{ nomad_port }}".to_owned(),
+            "kuma.web.http.parent_name=group".to_owned(),
+            "kuma.web.http.notification_name_list=[\"mail\"]".to_owned(),
+        ]);
+
+        let entities = get_entities_from_service(test_state.state.clone(), &registration)
+            .unwrap()
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+
+        let Some(Entity::Monitor(monitor)) = entities.get("default/shop/web") else {
+            panic!("Expected the monitor default/shop/web, got {:?}", entities);
+        };
+        assert!(entities.contains_key("default/shop/group"));
+        assert_eq!(
+            monitor.common().parent_name().as_deref(),
+            Some("default/shop/group")
+        );
+        assert_eq!(
+            monitor.common().notification_names(),
+            &Some(vec!["mail".to_owned()])
+        );
+    }
+
+    #[test]
+    fn ids_are_prefixed_with_the_namespace_and_job_id() {
+        let test_state = TestState::new(json!({}));
+
+        let entities = get_entities_from_service(
+            test_state.state.clone(),
+            &registration("shop", "a7c2", "10.0.0.1", 8080),
+        )
+        .unwrap();
+
+        let [(id, Entity::Monitor(monitor))] = entities.as_slice() else {
+            panic!("Expected a single monitor, got {:?}", entities);
+        };
+        assert_eq!(id, "default/shop/web");
+        assert_eq!(
+            serde_json::to_value(monitor).unwrap()["url"],
+            json!("http://10.0.0.1:8080")
+        );
+    }
+}
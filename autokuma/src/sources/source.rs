@@ -5,6 +5,8 @@ use crate::{entity::Entity, error::Result};
 #[async_trait]
 pub trait Source {
     fn name(&self) -> &'static str;
+    /// The Uptime Kuma instance the entities of this source are synced to.
+    fn instance(&self) -> &str;
     async fn init(&mut self) -> Result<()>;
     async fn get_entities(&mut self) -> Result<Vec<(String, Entity)>>;
     async fn shutdown(&mut self) -> Result<()>;
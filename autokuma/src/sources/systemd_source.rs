@@ -0,0 +1,212 @@
+use crate::{
+    app_state::AppState,
+    config::DEFAULT_INSTANCE,
+    entity::{get_entities_from_labels, Entity},
+    error::{Error, Result},
+    kuma::get_kuma_labels,
+    sources::source::Source,
+};
+use async_trait::async_trait;
+use itertools::Itertools;
+use kuma_client::util::ResultLogger;
+use log::debug;
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+const KEY_PREFIX: &str = "X-AutoKuma-";
+
+/// Parse the `X-AutoKuma-*` keys of a unit file into labels, i.e. `X-AutoKuma-web.http.name=Web` becomes `<label_prefix>.web.http.name: Web`.
+fn parse_unit_labels(content: &str, label_prefix: &str) -> HashMap<String, String> {
+    let mut labels = HashMap::new();
+    let mut lines = content.lines();
+
+    while let Some(line) = lines.next() {
+        let mut line = line.trim().to_owned();
+
+        while line.ends_with('\\') {
+            line.pop();
+            match lines.next() {
+                Some(next) => line.push_str(next.trim()),
+                None => break,
+            }
+        }
+
+        if line.starts_with('#') || line.starts_with(';') || line.starts_with('[') {
+            continue;
+        }
+
+        if let Some((key, value)) = line.split_once('=') {
+            if let Some(key) = key.trim().strip_prefix(KEY_PREFIX) {
+                labels.insert(format!("{}.{}", label_prefix, key), value.trim().to_owned());
+            }
+        }
+    }
+
+    labels
+}
+
+async fn get_entities_from_unit(
+    state: Arc<AppState>,
+    path: &Path,
+) -> Result<Vec<(String, Entity)>> {
+    let content = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|e| Error::IO(e.to_string()))?;
+
+    let labels = parse_unit_labels(&content, &state.config.systemd.label_prefix);
+    if labels.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut template_values = tera::Context::new();
+    template_values.insert(
+        "unit_name",
+        &path.file_name().map(|name| name.to_string_lossy()),
+    );
+    template_values.insert("unit_path", &path.to_string_lossy());
+
+    let kuma_labels = get_kuma_labels(
+        &state,
+        &state.config.systemd.label_prefix,
+        Some(&labels),
+        &template_values,
+    )?;
+
+    get_entities_from_labels(state.clone(), kuma_labels, &template_values)
+}
+
+pub struct SystemdSource {
+    state: Arc<AppState>,
+}
+
+#[async_trait]
+impl Source for SystemdSource {
+    fn name(&self) -> &'static str {
+        "Systemd"
+    }
+
+    fn instance(&self) -> &str {
+        self.state
+            .config
+            .systemd
+            .instance
+            .as_deref()
+            .unwrap_or(DEFAULT_INSTANCE)
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_entities(&mut self) -> Result<Vec<(String, Entity)>> {
+        let unit_path = Path::new(&self.state.config.systemd.unit_path);
+        if !unit_path.exists() {
+            debug!(
+                "Systemd unit directory {} does not exist",
+                unit_path.display()
+            );
+            return Ok(vec![]);
+        }
+
+        let mut dir = tokio::fs::read_dir(unit_path)
+            .await
+            .map_err(|e| Error::IO(e.to_string()))?;
+
+        let mut units = vec![];
+        while let Some(entry) = dir
+            .next_entry()
+            .await
+            .map_err(|e| Error::IO(e.to_string()))?
+        {
+            let path = entry.path();
+            if path.is_file() {
+                units.push(path);
+            }
+        }
+
+        let mut entities = vec![];
+        for unit in units.into_iter().sorted() {
+            match get_entities_from_unit(self.state.clone(), &unit)
+                .await
+                .log_warn(std::module_path!(), |e| {
+                    format!("[{}] {}", unit.display(), e)
+                }) {
+                Ok(unit_entities) => entities.extend(unit_entities),
+                Err(_) => self.state.record_failure(),
+            }
+        }
+
+        Ok(entities)
+    }
+}
+
+impl SystemdSource {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_unit_labels_reads_autokuma_keys() {
+        let labels = parse_unit_labels(
+            r#"
+[Unit]
+Description=Web Server
+X-AutoKuma-web.http.name=Web Server
+X-AutoKuma-web.http.url = http://localhost:8080
+
+[Service]
+ExecStart=/usr/bin/web
+"#,
+            "kuma",
+        );
+
+        assert_eq!(
+            labels,
+            HashMap::from([
+                ("kuma.web.http.name".to_owned(), "Web Server".to_owned()),
+                (
+                    "kuma.web.http.url".to_owned(),
+                    "http://localhost:8080".to_owned()
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_unit_labels_skips_comments() {
+        let labels = parse_unit_labels(
+            "# X-AutoKuma-web.http.name=Commented\n; X-AutoKuma-web.http.url=http://localhost\n",
+            "kuma",
+        );
+
+        assert!(labels.is_empty());
+    }
+
+    #[test]
+    fn parse_unit_labels_joins_continuation_lines() {
+        let labels = parse_unit_labels("X-AutoKuma-web.http.name=Web \\\n    Server\n", "kuma");
+
+        assert_eq!(
+            labels.get("kuma.web.http.name").map(String::as_str),
+            Some("Web Server")
+        );
+    }
+
+    #[test]
+    fn parse_unit_labels_uses_the_label_prefix() {
+        let labels = parse_unit_labels("X-AutoKuma-web.http.name=Web\n", "monitoring");
+
+        assert_eq!(
+            labels.keys().collect::<Vec<_>>(),
+            vec!["monitoring.web.http.name"]
+        );
+    }
+}
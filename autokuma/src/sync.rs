@@ -1,22 +1,92 @@
-use crate::app_state::AppState;
-use crate::entity::{merge_entities, Entity};
+use crate::app_state::{AppDB, AppState, ExistingIds};
+use crate::entity::{
+    merge_entities, resolve_maintenance_names, resolve_names, resolve_status_page_names,
+    without_default_values, Entity,
+};
 use crate::kuma::get_managed_entities;
 use crate::name::Name;
+use crate::plan::{Plan, PlanEntry};
 use crate::{
     config::{Config, DeleteBehavior},
-    error::{KumaError, Result},
+    error::{Error, KumaError, Result},
     sources::source::Source,
 };
 use futures_util::FutureExt;
 use itertools::Itertools;
-use kuma_client::{util::ResultLogger, Client};
-use log::{debug, error, info, trace, warn};
+use kuma_client::{monitor::Monitor, notification::NotificationList, util::ResultLogger, Client};
+use log::{error, info, log, trace, warn, Level};
+use rand::Rng as _;
 use std::collections::HashSet;
-use std::{collections::HashMap, env, sync::Arc, time::Duration};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    env,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// Resolve all references by name of an entity, missing references are reported together with the id of the entity waiting for them.
+fn resolve_entity_names(
+    db: &AppDB,
+    notifications: &NotificationList,
+    id: &str,
+    entity: &Entity,
+) -> Result<Entity> {
+    let mut entity = entity.clone();
+
+    match &mut entity {
+        Entity::Monitor(monitor) => resolve_names(db, notifications, monitor),
+        Entity::Maintenance(maintenance) => resolve_maintenance_names(db, maintenance),
+        Entity::StatusPage(status_page) => resolve_status_page_names(db, status_page),
+        _ => Ok(()),
+    }
+    .map_err(|e| match e {
+        Error::NameNotFound(name) => Error::ReferenceNotFound(id.to_owned(), name),
+        e => e,
+    })?;
+
+    Ok(entity)
+}
+
+/// The order entities need to be created in so referenced entities exist before the entities referencing them,
+/// i.e. docker hosts, notifications and tags first, then monitors by the depth of their group and status pages and maintenances last.
+fn creation_rank(id: &str, entity: &Entity, parents: &HashMap<String, String>) -> usize {
+    match entity {
+        Entity::DockerHost(_) | Entity::Notification(_) | Entity::Tag(_) => 0,
+        Entity::Monitor(_) => {
+            let mut depth = 1;
+            let mut current = id;
+            while let Some(parent) = parents.get(current) {
+                if depth > parents.len() {
+                    break;
+                }
+                depth += 1;
+                current = parent;
+            }
+            depth
+        }
+        Entity::StatusPage(_) | Entity::Maintenance(_) => usize::MAX,
+    }
+}
+
+/// The name an entity is referenced by from other entities.
+fn entity_name(id: &str, entity: &Entity) -> Name {
+    let id = id.to_owned();
+    match entity {
+        Entity::DockerHost(_) => Name::DockerHost(id),
+        Entity::Notification(_) => Name::Notification(id),
+        Entity::Monitor(_) => Name::Monitor(id),
+        Entity::Tag(_) => Name::Tag(id),
+        Entity::StatusPage(_) => Name::StatusPage(id),
+        Entity::Maintenance(_) => Name::Maintenance(id),
+    }
+}
 
 pub struct Sync {
     app_state: Arc<AppState>,
     sources: Vec<Box<dyn Source>>,
+    cycle: u64,
+    /// Ids defined by multiple sources during the last sync, used to only warn about new duplicates.
+    duplicates: HashSet<(String, String)>,
 }
 
 impl Sync {
@@ -25,10 +95,27 @@ impl Sync {
         Ok(Self {
             app_state: state.clone(),
             sources: crate::sources::get_sources(state),
+            cycle: 0,
+            duplicates: HashSet::new(),
         })
     }
 
-    async fn create_entity(&self, kuma: &Client, id: &String, entity: &Entity) -> Result<()> {
+    async fn create_entity(
+        &self,
+        kuma: &Client,
+        db: &AppDB,
+        id: &String,
+        entity: &Entity,
+    ) -> Result<()> {
+        if self.app_state.config.dry_run {
+            info!(
+                "[DRY RUN] Would create new {}: {}",
+                entity.entity_type(),
+                id
+            );
+            return Ok(());
+        }
+
         info!("Creating new {}: {}", entity.entity_type(), id);
         match entity.clone() {
             Entity::Monitor(monitor) => {
@@ -40,9 +127,7 @@ impl Sync {
                             )
                         })?;
 
-                        self.app_state
-                            .db
-                            .store_id(Name::Monitor(id.clone()), db_id)?;
+                        db.store_id(Name::Monitor(id.clone()), db_id)?;
 
                         Ok(())
                     }
@@ -56,9 +141,7 @@ impl Sync {
                     )
                 })?;
 
-                self.app_state
-                    .db
-                    .store_id(Name::DockerHost(id.clone()), db_id)?;
+                db.store_id(Name::DockerHost(id.clone()), db_id)?;
             }
             Entity::Notification(notification) => {
                 let db_id = kuma
@@ -71,9 +154,7 @@ impl Sync {
                         )
                     })?;
 
-                self.app_state
-                    .db
-                    .store_id(Name::Notification(id.clone()), db_id)?;
+                db.store_id(Name::Notification(id.clone()), db_id)?;
             }
             Entity::StatusPage(status_page) => {
                 let db_id = kuma
@@ -86,9 +167,7 @@ impl Sync {
                         )
                     })?;
 
-                self.app_state
-                    .db
-                    .store_id(Name::StatusPage(id.clone()), db_id)?;
+                db.store_id(Name::StatusPage(id.clone()), db_id)?;
             }
             Entity::Tag(tag) => {
                 let db_id = kuma.add_tag(tag).await?.tag_id.ok_or_else(|| {
@@ -97,64 +176,161 @@ impl Sync {
                     )
                 })?;
 
-                self.app_state.db.store_id(Name::Tag(id.clone()), db_id)?;
+                db.store_id(Name::Tag(id.clone()), db_id)?;
+            }
+            Entity::Maintenance(maintenance) => {
+                let db_id = kuma
+                    .add_maintenance(maintenance)
+                    .await?
+                    .common()
+                    .id
+                    .ok_or_else(|| {
+                        KumaError::CommunicationError(
+                            "Did not receive an id from Uptime Kuma".to_owned(),
+                        )
+                    })?;
+
+                db.store_id(Name::Maintenance(id.clone()), db_id)?;
             }
         }
 
+        self.app_state.metrics.record_created();
+
         Ok(())
     }
 
-    async fn delete_entity(&self, kuma: &Client, id: &String, entity: &Entity) -> Result<()> {
+    async fn delete_entity(
+        &self,
+        kuma: &Client,
+        db: &AppDB,
+        id: &String,
+        entity: &Entity,
+    ) -> Result<()> {
+        if self.app_state.config.dry_run {
+            info!("[DRY RUN] Would delete {}: {}", entity.entity_type(), id);
+            return Ok(());
+        }
+
         info!("Deleting {}: {}", entity.entity_type(), id);
         match entity {
             Entity::Monitor(monitor) => {
                 if let Some(db_id) = monitor.common().id() {
                     kuma.delete_monitor(*db_id).await?;
-                    self.app_state.db.remove_id(Name::Monitor(id.clone()))?;
+                    db.remove_id(Name::Monitor(id.clone()))?;
                 }
             }
             Entity::DockerHost(docker_host) => {
                 if let Some(db_id) = docker_host.id {
                     kuma.delete_docker_host(db_id).await?;
-                    self.app_state.db.remove_id(Name::DockerHost(id.clone()))?;
+                    db.remove_id(Name::DockerHost(id.clone()))?;
                 }
             }
             Entity::Notification(notification) => {
                 if let Some(db_id) = notification.id {
                     kuma.delete_notification(db_id).await?;
-                    self.app_state
-                        .db
-                        .remove_id(Name::Notification(id.clone()))?;
+                    db.remove_id(Name::Notification(id.clone()))?;
                 }
             }
             Entity::StatusPage(status_page) => {
                 if let Some(slug) = &status_page.slug {
                     kuma.delete_status_page(slug).await?;
-                    self.app_state.db.remove_id(Name::StatusPage(id.clone()))?;
+                    db.remove_id(Name::StatusPage(id.clone()))?;
                 }
             }
             Entity::Tag(tag) => {
                 if let Some(db_id) = tag.tag_id {
                     kuma.delete_tag(db_id).await?;
-                    self.app_state.db.remove_id(Name::Tag(id.clone()))?;
+                    db.remove_id(Name::Tag(id.clone()))?;
+                }
+            }
+            Entity::Maintenance(maintenance) => {
+                if let Some(db_id) = maintenance.common().id {
+                    kuma.delete_maintenance(db_id).await?;
+                    db.remove_id(Name::Maintenance(id.clone()))?;
+                }
+            }
+        }
+
+        self.app_state.metrics.record_deleted();
+
+        Ok(())
+    }
+
+    /// Delete kept tags and notifications which are no longer used by any monitor.
+    async fn delete_orphans(
+        &self,
+        instance: &str,
+        kuma: &Client,
+        db: &AppDB,
+        kept: Vec<(&String, &Entity)>,
+        plan: &mut Plan,
+    ) -> Result<()> {
+        let monitors = kuma.get_monitors().await?;
+
+        let used_tags = monitors
+            .values()
+            .flat_map(|monitor| monitor.common().tags().iter().filter_map(|tag| tag.tag_id))
+            .collect::<HashSet<_>>();
+
+        let used_notifications = monitors
+            .values()
+            .filter_map(|monitor| monitor.common().notification_id_list().as_ref())
+            .flat_map(|notifications| {
+                notifications
+                    .iter()
+                    .filter(|(_, enabled)| **enabled)
+                    .filter_map(|(id, _)| id.parse::<i32>().ok())
+            })
+            .collect::<HashSet<_>>();
+
+        for (id, entity) in kept {
+            let orphaned = match entity {
+                Entity::Tag(tag) => {
+                    !self.app_state.config.is_marker_tag(tag)
+                        && tag.tag_id.is_some_and(|id| !used_tags.contains(&id))
                 }
+                Entity::Notification(notification) => notification
+                    .id
+                    .is_some_and(|id| !used_notifications.contains(&id)),
+                _ => false,
+            };
+
+            if orphaned {
+                info!("Found orphaned {}: {}", entity.entity_type(), id);
+
+                if self.app_state.config.plan_output.is_some() {
+                    plan.delete.push(PlanEntry::new(instance, id, entity));
+                }
+
+                self.delete_entity(kuma, db, id, entity).await?;
             }
         }
 
         Ok(())
     }
 
+    fn has_changes(&self, current: &Entity, merge: &Entity) -> bool {
+        current != merge
+            && (!self.app_state.config.ignore_default_drift
+                || without_default_values(current) != without_default_values(merge))
+    }
+
     async fn update_entity(
         &self,
         kuma: &Client,
+        db: &AppDB,
         id: &String,
         current: &Entity,
         new: &Entity,
     ) -> Result<()> {
         let merge = merge_entities(&current, &new, None);
 
-        if current != &merge {
-            debug!(
+        if self.has_changes(current, &merge) {
+            log!(
+                match self.app_state.config.dry_run {
+                    true => Level::Info,
+                    false => Level::Debug,
+                },
                 "\n======= OLD =======\n{}\n===================\n\n======= NEW =======\n{}\n===================", 
                 serde_json::to_string_pretty(&current).unwrap(),
                 serde_json::to_string_pretty(&merge).unwrap()
@@ -167,8 +343,13 @@ impl Sync {
                     current.entity_type(),
                     new.entity_type()
                 );
-                self.delete_entity(kuma, id, &current).await?;
-                self.create_entity(kuma, id, &new).await?;
+                self.delete_entity(kuma, db, id, &current).await?;
+                self.create_entity(kuma, db, id, &new).await?;
+                return Ok(());
+            }
+
+            if self.app_state.config.dry_run {
+                info!("[DRY RUN] Would update {}: {}", new.entity_type(), id);
                 return Ok(());
             }
 
@@ -187,30 +368,221 @@ impl Sync {
                 (Entity::Tag(merge), Entity::Tag(_)) => {
                     kuma.edit_tag(merge).await?;
                 }
+                (Entity::StatusPage(merge), Entity::StatusPage(_)) => {
+                    kuma.edit_status_page(merge).await?;
+                }
+                (Entity::Maintenance(merge), Entity::Maintenance(_)) => {
+                    kuma.edit_maintenance(merge).await?;
+                }
                 _ => {}
             }
+
+            self.app_state.metrics.record_updated();
+        }
+
+        if self.app_state.config.enforce_active {
+            self.enforce_active(kuma, id, current, new).await?;
         }
 
         Ok(())
     }
 
+    /// Pause or resume a monitor if its `active` state differs from the one set in its definition.
+    async fn enforce_active(
+        &self,
+        kuma: &Client,
+        id: &String,
+        current: &Entity,
+        new: &Entity,
+    ) -> Result<()> {
+        let (Entity::Monitor(current), Entity::Monitor(new)) = (current, new) else {
+            return Ok(());
+        };
+
+        let (Some(monitor_id), Some(active)) = (*current.common().id(), *new.common().active())
+        else {
+            return Ok(());
+        };
+
+        if *current.common().active() == Some(active) {
+            return Ok(());
+        }
+
+        if self.app_state.config.dry_run {
+            info!(
+                "[DRY RUN] Would {} monitor: {}",
+                match active {
+                    true => "resume",
+                    false => "pause",
+                },
+                id
+            );
+            return Ok(());
+        }
+
+        match active {
+            true => {
+                info!("Resuming monitor: {}", id);
+                kuma.resume_monitor(monitor_id).await?;
+            }
+            false => {
+                info!("Pausing monitor: {}", id);
+                kuma.pause_monitor(monitor_id).await?;
+            }
+        }
+
+        self.app_state.metrics.record_updated();
+
+        Ok(())
+    }
+
     async fn do_sync(&mut self) -> Result<()> {
-        let kuma = Client::connect(self.app_state.config.kuma.clone()).await?;
+        let mut new_entities: HashMap<String, HashMap<String, Entity>> = HashMap::new();
+        let mut origins: HashMap<(String, String), &'static str> = HashMap::new();
+        let mut duplicates = HashSet::new();
+
+        // Sources are queried in a fixed order (files, docker, kubernetes, systemd, nomad),
+        // if multiple sources define the same id the definition of the first one is used
+        for source in &mut self.sources {
+            trace!("Querying source: {}", source.name());
+            let entities = source.get_entities().await?;
+            trace!("Got {} entities from source", entities.len());
+
+            let instance = source.instance().to_owned();
+            let instance_entities = new_entities.entry(instance.clone()).or_default();
+
+            for (id, entity) in entities {
+                match origins.entry((instance.clone(), id.clone())) {
+                    Entry::Occupied(origin) if *origin.get() != source.name() => {
+                        let level = match self.duplicates.contains(origin.key()) {
+                            true => Level::Debug,
+                            false => Level::Warn,
+                        };
+                        log!(
+                            level,
+                            "{} {} from source {} is also defined by source {}, ignoring the definition from {}",
+                            entity.entity_type(),
+                            id,
+                            source.name(),
+                            origin.get(),
+                            source.name()
+                        );
+                        duplicates.insert(origin.key().clone());
+                    }
+                    Entry::Occupied(_) => {
+                        instance_entities.insert(id, entity);
+                    }
+                    Entry::Vacant(origin) => {
+                        origin.insert(source.name());
+                        instance_entities.insert(id, entity);
+                    }
+                }
+            }
+        }
+        self.duplicates = duplicates;
 
-        if self.app_state.db.get_version()? == 0 {
+        for instance in new_entities.keys() {
+            if self.app_state.db(instance).is_none() {
+                warn!(
+                    "No Uptime Kuma instance named '{}' is configured, ignoring its entities",
+                    instance
+                );
+            }
+        }
+
+        let mut plan = Plan::default();
+
+        for (instance, kuma_config) in self.app_state.config.kuma.instances() {
+            let entities = new_entities.remove(&instance).unwrap_or_default();
+
+            if let Err(err) = self
+                .sync_instance(&instance, kuma_config, entities, &mut plan)
+                .await
+            {
+                warn!(
+                    "Encountered error during sync of instance '{}': {}",
+                    instance, err
+                );
+                self.app_state.record_failure();
+            }
+        }
+
+        self.cycle += 1;
+
+        if let Some(plan_output) = &self.app_state.config.plan_output {
+            _ = plan
+                .sorted()
+                .write(plan_output)
+                .await
+                .log_warn(std::module_path!(), |e| {
+                    format!("Unable to write plan to {}: {}", plan_output, e)
+                });
+        }
+
+        Ok(())
+    }
+
+    async fn sync_instance(
+        &self,
+        instance: &str,
+        kuma_config: kuma_client::Config,
+        new_entities: HashMap<String, Entity>,
+        plan: &mut Plan,
+    ) -> Result<()> {
+        let db = self.app_state.db(instance).ok_or_else(|| {
+            Error::InternalError(format!("No database for instance '{}'", instance))
+        })?;
+
+        let kuma = Client::connect(kuma_config)
+            .await
+            .inspect_err(|_| self.app_state.metrics.set_kuma_up(instance, false))?;
+
+        let result = self
+            .sync_entities(instance, &kuma, &db, new_entities, plan)
+            .await;
+
+        if self.is_shrink_due() {
+            _ = self
+                .shrink_database(instance, &kuma)
+                .await
+                .log_warn(std::module_path!(), |e| {
+                    format!(
+                        "Unable to shrink database of instance '{}': {}",
+                        instance, e
+                    )
+                });
+        }
+
+        self.app_state
+            .metrics
+            .set_kuma_up(instance, kuma.is_connected().await);
+
+        result
+    }
+
+    async fn sync_entities(
+        &self,
+        instance: &str,
+        kuma: &Client,
+        db: &AppDB,
+        new_entities: HashMap<String, Entity>,
+        plan: &mut Plan,
+    ) -> Result<()> {
+        if db.get_version()? == 0 {
             let autokuma_tag = kuma
                 .get_tags()
                 .await?
                 .iter()
-                .find(|x| {
-                    x.name
-                        .as_ref()
-                        .is_some_and(|name| name == &self.app_state.config.tag_name)
-                })
+                .find(|tag| self.app_state.config.is_marker_tag(tag))
                 .map(|tag| tag.tag_id)
                 .flatten();
 
             if let Some(autokuma_tag) = autokuma_tag {
+                if self.app_state.config.dry_run {
+                    warn!("[DRY RUN] Migration required, skipping sync");
+                    return Ok(());
+                }
+
                 if !env::var("AUTOKUMA__MIGRATE").is_ok_and(|x| x == "true") {
                     error!(
                         "Migration required, but AUTOKUMA__MIGRATE is not set to 'true', refusing to continue to avoid data loss. Please read the CHANGELOG and then set AUTOKUMA__MIGRATE=true to continue."
@@ -237,67 +609,136 @@ impl Sync {
                 info!("Migrating {} monitors", entries.len());
 
                 for (name, id) in entries {
-                    self.app_state.db.store_id(Name::Monitor(name), id)?;
+                    db.store_id(Name::Monitor(name), id)?;
                 }
 
                 kuma.delete_tag(autokuma_tag).await?;
             }
 
-            self.app_state.db.set_version(1)?
+            if !self.app_state.config.dry_run {
+                db.set_version(1)?
+            }
         }
 
-        self.app_state.db.clean(
-            &kuma
-                .get_monitors()
-                .await?
-                .into_iter()
-                .filter_map(|(_, monitor)| monitor.common().id().clone())
-                .collect::<HashSet<_>>(),
-            &kuma
-                .get_notifications()
-                .await?
-                .into_iter()
-                .filter_map(|notification| notification.id)
-                .collect::<HashSet<_>>(),
-            &kuma
-                .get_docker_hosts()
-                .await?
-                .into_iter()
-                .filter_map(|docker_host| docker_host.id)
-                .collect::<HashSet<_>>(),
-            &kuma
-                .get_tags()
-                .await?
-                .into_iter()
-                .filter_map(|tag| tag.tag_id)
-                .collect::<HashSet<_>>(),
-            &kuma
-                .get_status_pages()
-                .await?
-                .into_iter()
-                .filter_map(|(_, status_page)| status_page.slug)
-                .collect::<HashSet<_>>(),
-        )?;
+        if !self.app_state.config.dry_run {
+            db.clean(&ExistingIds {
+                monitors: kuma
+                    .get_monitors()
+                    .await?
+                    .into_iter()
+                    .filter_map(|(_, monitor)| monitor.common().id().clone())
+                    .collect::<HashSet<_>>(),
+                notifications: kuma
+                    .get_notifications()
+                    .await?
+                    .into_iter()
+                    .filter_map(|notification| notification.id)
+                    .collect::<HashSet<_>>(),
+                docker_hosts: kuma
+                    .get_docker_hosts()
+                    .await?
+                    .into_iter()
+                    .filter_map(|docker_host| docker_host.id)
+                    .collect::<HashSet<_>>(),
+                tags: kuma
+                    .get_tags()
+                    .await?
+                    .into_iter()
+                    .filter_map(|tag| tag.tag_id)
+                    .collect::<HashSet<_>>(),
+                status_pages: kuma
+                    .get_status_pages()
+                    .await?
+                    .into_iter()
+                    .filter_map(|(_, status_page)| status_page.slug)
+                    .collect::<HashSet<_>>(),
+                maintenances: kuma
+                    .get_maintenances()
+                    .await?
+                    .into_values()
+                    .filter_map(|maintenance| maintenance.common().id)
+                    .collect::<HashSet<_>>(),
+                proxies: kuma
+                    .get_proxies()
+                    .await?
+                    .into_iter()
+                    .filter_map(|proxy| proxy.id)
+                    .collect::<HashSet<_>>(),
+            })?;
+        }
 
-        let current_entities = get_managed_entities(&self.app_state, &kuma).await?;
+        let mut current_entities = get_managed_entities(db, kuma).await?;
+        let notifications = kuma.get_notifications().await?;
 
-        let mut new_entities: HashMap<String, Entity> = HashMap::new();
+        // Monitors with a type unknown to AutoKuma can't be compared or updated, so leave them alone
+        let unsupported = current_entities
+            .iter()
+            .filter_map(|(id, entity)| match entity {
+                Entity::Monitor(Monitor::Unknown { value }) => {
+                    warn!(
+                        "Skipping monitor {} because its type '{}' is not supported",
+                        id, value.type_name
+                    );
+                    Some(id.clone())
+                }
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+        current_entities.retain(|id, _| !unsupported.contains(id));
 
-        for source in &mut self.sources {
-            trace!("Querying source: {}", source.name());
-            let entities = source.get_entities().await?;
-            trace!("Got {} entities from source", entities.len());
-            new_entities.extend(entities);
-        }
+        // Entities referencing an entity which doesn't exist yet (e.g. a child declared before its parent)
+        // are deferred until the entities of the first pass have been created
+        let mut deferred = vec![];
+        let new_entities = new_entities
+            .into_iter()
+            .filter(|(id, _)| !unsupported.contains(id))
+            .filter_map(|(id, entity)| {
+                match resolve_entity_names(db, &notifications, &id, &entity) {
+                    Ok(resolved) => Some((id, resolved)),
+                    Err(Error::ReferenceNotFound(_, _)) => {
+                        deferred.push((id, entity));
+                        None
+                    }
+                    Err(err) => {
+                        warn!(
+                            "Unable to resolve names for {} {}: {}",
+                            entity.entity_type(),
+                            id,
+                            err
+                        );
+                        self.app_state.record_failure();
+                        None
+                    }
+                }
+            })
+            .collect::<HashMap<_, _>>();
 
-        let to_delete = current_entities
+        let (to_delete, kept): (Vec<_>, Vec<_>) = current_entities
             .iter()
             .filter(|(id, _)| !new_entities.contains_key(*id))
-            .collect_vec();
+            .filter(|(id, _)| !deferred.iter().any(|(deferred_id, _)| deferred_id == *id))
+            .partition(|(_, entity)| {
+                self.app_state.config.delete_behavior(&entity.entity_type())
+                    == &DeleteBehavior::Delete
+            });
+
+        let parents = new_entities
+            .iter()
+            .chain(deferred.iter().map(|(id, entity)| (id, entity)))
+            .filter_map(|(id, entity)| match entity {
+                Entity::Monitor(monitor) => monitor
+                    .common()
+                    .parent_name()
+                    .clone()
+                    .map(|parent_name| (id.clone(), parent_name)),
+                _ => None,
+            })
+            .collect::<HashMap<_, _>>();
 
         let to_create = new_entities
             .iter()
             .filter(|(id, _)| !current_entities.contains_key(*id))
+            .sorted_by_key(|(id, entity)| creation_rank(id, entity, &parents))
             .collect_vec();
 
         let to_update = current_entities
@@ -310,24 +751,205 @@ impl Sync {
             )
             .collect_vec();
 
+        if self.app_state.config.plan_output.is_some() {
+            plan.create.extend(
+                to_create
+                    .iter()
+                    .map(|(id, entity)| PlanEntry::new(instance, id, entity)),
+            );
+
+            plan.update.extend(
+                to_update
+                    .iter()
+                    .filter_map(|(id, current, new)| {
+                        let merge = merge_entities(current, new, None);
+                        self.has_changes(current, &merge)
+                            .then(|| PlanEntry::with_changes(instance, id, current, &merge))
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+            );
+
+            plan.delete.extend(
+                to_delete
+                    .iter()
+                    .map(|(id, entity)| PlanEntry::new(instance, id, entity)),
+            );
+        }
+
+        // During a dry run nothing is created, so entities referencing an entity which would be created
+        // are planned as well instead of being reported as unresolvable
+        let mut planned = to_create
+            .iter()
+            .map(|(id, entity)| entity_name(id, entity))
+            .collect::<HashSet<_>>();
+
         for (id, entity) in to_create {
-            self.create_entity(&kuma, id, entity).await?;
+            self.create_entity(kuma, db, id, entity).await?;
         }
 
         for (id, current, new) in to_update {
-            self.update_entity(&kuma, id, current, new).await?;
+            self.update_entity(kuma, db, id, current, new).await?;
         }
 
-        if self.app_state.config.on_delete == DeleteBehavior::Delete {
-            for (id, monitor) in to_delete {
-                self.delete_entity(&kuma, id, monitor).await?;
+        // Retry the deferred entities as long as each pass resolves at least one of them,
+        // as they are processed in dependency order a parent created during a pass can already be referenced by its children
+        deferred.sort_by_key(|(id, entity)| creation_rank(id, entity, &parents));
+        let mut last_errors = HashMap::new();
+        while !deferred.is_empty() {
+            let pending = deferred.len();
+            let mut unresolved = vec![];
+
+            for (id, entity) in std::mem::take(&mut deferred) {
+                let new = match resolve_entity_names(db, &notifications, &id, &entity) {
+                    Ok(new) => new,
+                    Err(Error::ReferenceNotFound(_, name))
+                        if self.app_state.config.dry_run && planned.contains(&name) =>
+                    {
+                        match current_entities.get(&id) {
+                            Some(_) => {
+                                info!("[DRY RUN] Would update {}: {}", entity.entity_type(), id);
+                            }
+                            None => {
+                                if self.app_state.config.plan_output.is_some() {
+                                    plan.create.push(PlanEntry::new(instance, &id, &entity));
+                                }
+                                self.create_entity(kuma, db, &id, &entity).await?;
+                                planned.insert(entity_name(&id, &entity));
+                            }
+                        }
+                        continue;
+                    }
+                    Err(err @ Error::ReferenceNotFound(_, _)) => {
+                        last_errors.insert(id.clone(), err);
+                        unresolved.push((id, entity));
+                        continue;
+                    }
+                    Err(err) => {
+                        warn!(
+                            "Unable to resolve names for {} {}: {}",
+                            entity.entity_type(),
+                            id,
+                            err
+                        );
+                        self.app_state.record_failure();
+                        continue;
+                    }
+                };
+
+                match current_entities.get(&id) {
+                    Some(current) => {
+                        if self.app_state.config.plan_output.is_some() {
+                            let merge = merge_entities(current, &new, None);
+                            if self.has_changes(current, &merge) {
+                                plan.update
+                                    .push(PlanEntry::with_changes(instance, &id, current, &merge)?);
+                            }
+                        }
+                        self.update_entity(kuma, db, &id, current, &new).await?;
+                    }
+                    None => {
+                        if self.app_state.config.plan_output.is_some() {
+                            plan.create.push(PlanEntry::new(instance, &id, &new));
+                        }
+                        self.create_entity(kuma, db, &id, &new).await?;
+                        planned.insert(entity_name(&id, &new));
+                    }
+                }
+            }
+
+            let resolved_any = unresolved.len() < pending;
+            deferred = unresolved;
+
+            if !resolved_any {
+                break;
             }
         }
 
+        for (id, entity) in &deferred {
+            if let Some(err) = last_errors.get(id) {
+                warn!("Cannot create {} {}: {}", entity.entity_type(), id, err);
+                self.app_state.record_failure();
+            }
+        }
+
+        for (id, entity) in to_delete {
+            self.delete_entity(kuma, db, id, entity).await?;
+        }
+
+        if self.app_state.config.delete_orphaned {
+            self.delete_orphans(instance, kuma, db, kept, plan).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether the database size should be checked during the current sync, the first check happens during the first sync.
+    fn is_shrink_due(&self) -> bool {
+        let config = &self.app_state.config.auto_shrink;
+        config.enabled && self.cycle % config.interval.max(1) == 0
+    }
+
+    /// Shrink the database of the Uptime Kuma instance if it exceeds the configured threshold.
+    async fn shrink_database(&self, instance: &str, kuma: &Client) -> Result<()> {
+        let threshold = self.app_state.config.auto_shrink.threshold_mb;
+        let size = kuma.get_database_size().await?;
+        let size_mb = size as f64 / 1024.0 / 1024.0;
+
+        if size_mb <= threshold {
+            trace!(
+                "Database of instance '{}' is {:.1} MiB, below the shrink threshold of {:.1} MiB",
+                instance,
+                size_mb,
+                threshold
+            );
+            return Ok(());
+        }
+
+        if self.app_state.config.dry_run {
+            info!(
+                "[DRY RUN] Would shrink database of instance '{}' ({:.1} MiB)",
+                instance, size_mb
+            );
+            return Ok(());
+        }
+
+        info!(
+            "Shrinking database of instance '{}' ({:.1} MiB exceeds {:.1} MiB)",
+            instance, size_mb, threshold
+        );
+        kuma.shrink_database().await?;
+
+        let new_size_mb = kuma.get_database_size().await? as f64 / 1024.0 / 1024.0;
+        info!(
+            "Shrunk database of instance '{}' from {:.1} MiB to {:.1} MiB",
+            instance, size_mb, new_size_mb
+        );
+
         Ok(())
     }
 
+    /// The delay until the next sync, randomly varied by `sync_jitter` percent to avoid many instances syncing at the same time.
+    fn next_sync_delay(&self) -> Duration {
+        let interval = self.app_state.config.sync_interval;
+        let jitter = self.app_state.config.sync_jitter.clamp(0.0, 100.0) / 100.0;
+
+        let factor = match jitter > 0.0 {
+            true => 1.0 + rand::thread_rng().gen_range(-jitter..=jitter),
+            false => 1.0,
+        };
+
+        Duration::from_secs_f64((interval * factor).max(0.0))
+    }
+
     async fn init(&mut self) -> Result<()> {
+        if self.app_state.config.metrics.enabled {
+            #[cfg(feature = "metrics")]
+            crate::metrics::server::serve(self.app_state.clone()).await?;
+
+            #[cfg(not(feature = "metrics"))]
+            warn!("Metrics are enabled, but AutoKuma was built without the `metrics` feature");
+        }
+
         for source in &mut self.sources {
             source.init().await?;
         }
@@ -335,10 +957,51 @@ impl Sync {
         Ok(())
     }
 
-    pub async fn run(&mut self) {
+    /// Adopt the existing entities of all instances, see [import_instance](crate::import::import_instance).
+    ///
+    /// Returns whether all instances have been imported successfully.
+    pub async fn import(&mut self) -> bool {
+        if !self.app_state.config.dry_run && !self.app_state.config.imports_are_kept() {
+            error!(
+                "Refusing to import, the next sync would delete all imported entities which aren't defined by a source. Set AUTOKUMA__IMPORT_DIR to the static monitors directory to write their definitions there or set AUTOKUMA__ON_DELETE=keep, or combine --import with --dry-run to preview the import."
+            );
+            return false;
+        }
+
+        let mut success = true;
+
+        for (instance, kuma_config) in self.app_state.config.kuma.instances() {
+            let Some(db) = self.app_state.db(&instance) else {
+                continue;
+            };
+
+            let result = match Client::connect(kuma_config).await {
+                Ok(kuma) => {
+                    let result =
+                        crate::import::import_instance(&self.app_state, &instance, &kuma, &db)
+                            .await;
+                    _ = kuma.disconnect().await;
+                    result
+                }
+                Err(err) => Err(err.into()),
+            };
+
+            if let Err(err) = result {
+                error!("Unable to import instance '{}': {}", instance, err);
+                success = false;
+            }
+        }
+
+        success
+    }
+
+    /// Run the sync loop until a shutdown signal is received, or a single sync in one-shot mode.
+    ///
+    /// Returns whether the run was successful, i.e. in one-shot mode all entities have been synced.
+    pub async fn run(&mut self) -> bool {
         if let Err(err) = self.init().await {
             error!("Encountered error during init: {}", err);
-            return;
+            return false;
         }
 
         async fn shutdown_signal() {
@@ -357,13 +1020,27 @@ impl Sync {
         }
 
         loop {
+            let start = Instant::now();
+            let failures = self.app_state.failures();
+
             if let Err(err) = self.do_sync().await {
                 warn!("Encountered error during sync: {}", err);
+                self.app_state.record_failure();
+            }
+
+            self.app_state
+                .metrics
+                .record_sync(start.elapsed(), self.app_state.failures() == failures);
+
+            if self.app_state.config.one_shot {
+                break;
             }
 
             match futures_util::future::select(
-                tokio::time::sleep(Duration::from_secs_f64(self.app_state.config.sync_interval))
-                    .boxed(),
+                futures_util::future::select(
+                    tokio::time::sleep(self.next_sync_delay()).boxed(),
+                    self.app_state.sync_trigger.notified().boxed(),
+                ),
                 shutdown_signal().boxed(),
             )
             .await
@@ -379,5 +1056,607 @@ impl Sync {
                 format!("Failed to gracefully shutdown source: {}", e)
             });
         }
+
+        !self.app_state.config.one_shot || self.app_state.failures() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::DEFAULT_INSTANCE,
+        test_util::{NamedSource, StaticSource, TestState},
+    };
+    use kuma_client::{tag::TagDefinition, test_util::MockServer};
+    use serde_json::json;
+
+    fn monitor(value: serde_json::Value) -> Entity {
+        Entity::Monitor(serde_json::from_value(value).unwrap())
+    }
+
+    fn tag(name: &str) -> Entity {
+        Entity::Tag(TagDefinition {
+            name: Some(name.to_owned()),
+            ..Default::default()
+        })
+    }
+
+    fn test_state(server: &MockServer, values: serde_json::Value) -> TestState {
+        let mut values = values;
+        values["kuma"] = json!({
+            "url": server.url(),
+            "username": "admin",
+            "password": "admin",
+        });
+
+        TestState::new(values)
+    }
+
+    /// Runs a single sync of `entities` and returns the methods called on the server.
+    async fn sync(
+        state: &TestState,
+        server: &MockServer,
+        entities: Vec<(&str, Entity)>,
+    ) -> Vec<String> {
+        let before = server.calls().await.len();
+
+        let mut sync = Sync {
+            app_state: state.state.clone(),
+            sources: vec![Box::new(StaticSource(
+                entities
+                    .into_iter()
+                    .map(|(id, entity)| (id.to_owned(), entity))
+                    .collect(),
+            ))],
+            cycle: 0,
+            duplicates: HashSet::new(),
+        };
+        sync.do_sync().await.unwrap();
+
+        assert_eq!(state.state.failures(), 0);
+
+        server
+            .calls()
+            .await
+            .into_iter()
+            .skip(before)
+            .map(|(method, _)| method)
+            .collect()
+    }
+
+    #[test]
+    fn creation_rank_orders_tags_groups_and_children() {
+        let entities = [
+            (
+                "child".to_owned(),
+                monitor(json!({"type": "http", "name": "Child", "url": "https://example.com"})),
+            ),
+            (
+                "group".to_owned(),
+                monitor(json!({"type": "group", "name": "Group"})),
+            ),
+            ("tag".to_owned(), tag("Tag")),
+        ];
+        let parents = HashMap::from([("child".to_owned(), "group".to_owned())]);
+
+        let order = entities
+            .iter()
+            .sorted_by_key(|(id, entity)| creation_rank(id, entity, &parents))
+            .map(|(id, _)| id.as_str())
+            .collect_vec();
+
+        assert_eq!(order, vec!["tag", "group", "child"]);
+    }
+
+    #[test]
+    fn creation_rank_stops_on_parent_cycles() {
+        let entity = monitor(json!({"type": "group", "name": "Group"}));
+        let parents = HashMap::from([
+            ("a".to_owned(), "b".to_owned()),
+            ("b".to_owned(), "a".to_owned()),
+        ]);
+
+        assert_eq!(creation_rank("a", &entity, &parents), 3);
+    }
+
+    #[tokio::test]
+    async fn children_are_created_after_their_group_and_tags() {
+        let server = MockServer::start().await;
+        let state = test_state(&server, json!({}));
+
+        sync(
+            &state,
+            &server,
+            vec![
+                (
+                    "child",
+                    monitor(json!({
+                        "type": "http",
+                        "name": "Child",
+                        "url": "https://example.com",
+                        "parent_name": "group",
+                        "tag_names": [{"name": "team", "value": "web"}],
+                    })),
+                ),
+                ("group", monitor(json!({"type": "group", "name": "Group"}))),
+                ("team", tag("Team")),
+            ],
+        )
+        .await;
+
+        let db = state.state.db(DEFAULT_INSTANCE).unwrap();
+        let group_id = db.get_id::<i32>(Name::Monitor("group".to_owned())).unwrap();
+        let child_id = db.get_id::<i32>(Name::Monitor("child".to_owned())).unwrap();
+        let tag_id = db.get_id::<i32>(Name::Tag("team".to_owned())).unwrap();
+        assert!(group_id.is_some() && child_id.is_some() && tag_id.is_some());
+
+        let client = kuma_client::Client::connect(server.config()).await.unwrap();
+        let child = client.get_monitor(child_id.unwrap()).await.unwrap();
+        assert_eq!(*child.common().parent(), group_id);
+
+        let calls = server.calls().await;
+        assert!(calls.iter().any(|(method, args)| method == "addMonitorTag"
+            && args[0] == json!(tag_id)
+            && args[1] == json!(child_id)));
+    }
+
+    #[tokio::test]
+    async fn dry_run_plans_children_of_new_groups() {
+        let server = MockServer::start().await;
+        let plan_output = tempfile::NamedTempFile::new().unwrap();
+        let state = test_state(
+            &server,
+            json!({"dry_run": true, "plan_output": plan_output.path()}),
+        );
+
+        let calls = sync(
+            &state,
+            &server,
+            vec![
+                (
+                    "child",
+                    monitor(json!({
+                        "type": "http",
+                        "name": "Child",
+                        "url": "https://example.com",
+                        "parent_name": "group",
+                    })),
+                ),
+                ("group", monitor(json!({"type": "group", "name": "Group"}))),
+            ],
+        )
+        .await;
+
+        assert!(!calls.contains(&"add".to_owned()));
+
+        let plan: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(plan_output.path()).unwrap()).unwrap();
+        assert_eq!(
+            plan["create"],
+            json!([
+                {"instance": DEFAULT_INSTANCE, "id": "child", "type": "http", "parent": "group"},
+                {"instance": DEFAULT_INSTANCE, "id": "group", "type": "group"},
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn duplicate_ids_use_the_first_source() {
+        let server = MockServer::start().await;
+        let state = test_state(&server, json!({}));
+        let web = |url: &str| {
+            vec![(
+                "web".to_owned(),
+                monitor(json!({"type": "http", "name": "Web", "url": url})),
+            )]
+        };
+
+        let mut sync = Sync {
+            app_state: state.state.clone(),
+            sources: vec![
+                Box::new(NamedSource("Files", web("https://files.example.com"))),
+                Box::new(NamedSource("Docker", web("https://docker.example.com"))),
+            ],
+            cycle: 0,
+            duplicates: HashSet::new(),
+        };
+        sync.do_sync().await.unwrap();
+
+        let added = server
+            .calls()
+            .await
+            .into_iter()
+            .filter(|(method, _)| method == "add")
+            .map(|(_, args)| args[0]["url"].clone())
+            .collect_vec();
+        assert_eq!(added, vec![json!("https://files.example.com")]);
+        assert_eq!(
+            sync.duplicates,
+            HashSet::from([(DEFAULT_INSTANCE.to_owned(), "web".to_owned())])
+        );
+    }
+
+    #[tokio::test]
+    async fn enforce_active_pauses_monitors_once() {
+        let server = MockServer::start().await;
+        let state = test_state(&server, json!({"enforce_active": true}));
+        let web = |active: bool| {
+            monitor(json!({
+                "type": "http",
+                "name": "Web",
+                "url": "https://example.com",
+                "active": active,
+            }))
+        };
+
+        let created = sync(&state, &server, vec![("web", web(true))]).await;
+        assert!(created.contains(&"add".to_owned()));
+
+        let paused = sync(&state, &server, vec![("web", web(false))]).await;
+        assert!(paused.contains(&"pauseMonitor".to_owned()));
+
+        let unchanged = sync(&state, &server, vec![("web", web(false))]).await;
+        assert!(!unchanged.contains(&"pauseMonitor".to_owned()));
+        assert!(!unchanged.contains(&"resumeMonitor".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn enforce_active_does_nothing_during_a_dry_run() {
+        let server = MockServer::start()
+            .await
+            .with_monitors(json!({
+                "1": {
+                    "id": 1,
+                    "name": "Web",
+                    "type": "http",
+                    "active": true,
+                    "interval": 60,
+                    "retryInterval": 60,
+                    "maxretries": 0,
+                    "upsideDown": false,
+                    "parent": null,
+                    "url": "https://example.com",
+                    "method": "GET",
+                    "tags": [],
+                    "notificationIDList": {},
+                    "accepted_statuscodes": ["200-299"],
+                },
+            }))
+            .await;
+        let state = test_state(&server, json!({"enforce_active": true, "dry_run": true}));
+        state
+            .state
+            .db(DEFAULT_INSTANCE)
+            .unwrap()
+            .store_id(Name::Monitor("web".to_owned()), 1)
+            .unwrap();
+
+        let calls = sync(
+            &state,
+            &server,
+            vec![(
+                "web",
+                monitor(json!({
+                    "type": "http",
+                    "name": "Web",
+                    "url": "https://example.com",
+                    "active": false,
+                })),
+            )],
+        )
+        .await;
+
+        assert!(!calls.contains(&"pauseMonitor".to_owned()));
+        assert!(!calls.contains(&"editMonitor".to_owned()));
+    }
+
+    #[test]
+    fn server_default_max_retries_is_default_drift() {
+        let state = TestState::new(json!({"ignore_default_drift": true}));
+        let sync = Sync {
+            app_state: state.state.clone(),
+            sources: vec![],
+            cycle: 0,
+            duplicates: HashSet::new(),
+        };
+        let web = |max_retries: Option<i32>| {
+            monitor(json!({
+                "type": "http",
+                "name": "Web",
+                "url": "https://example.com",
+                "max_retries": max_retries,
+            }))
+        };
+
+        assert!(!sync.has_changes(&web(Some(0)), &web(None)));
+        assert!(sync.has_changes(&web(Some(0)), &web(Some(3))));
+    }
+
+    #[tokio::test]
+    async fn server_default_max_retries_is_not_a_change() {
+        let server = MockServer::start()
+            .await
+            .with_monitors(json!({
+                "1": {
+                    "id": 1,
+                    "name": "Web",
+                    "type": "http",
+                    "active": true,
+                    "interval": 60,
+                    "retryInterval": 60,
+                    "maxretries": 0,
+                    "upsideDown": false,
+                    "parent": null,
+                    "url": "https://example.com",
+                    "method": "GET",
+                    "tags": [],
+                    "notificationIDList": {},
+                    "accepted_statuscodes": ["200-299"],
+                },
+            }))
+            .await;
+        let state = test_state(&server, json!({}));
+        state
+            .state
+            .db(DEFAULT_INSTANCE)
+            .unwrap()
+            .store_id(Name::Monitor("web".to_owned()), 1)
+            .unwrap();
+
+        let calls = sync(
+            &state,
+            &server,
+            vec![(
+                "web",
+                monitor(json!({"type": "http", "name": "Web", "url": "https://example.com"})),
+            )],
+        )
+        .await;
+
+        assert!(!calls.contains(&"editMonitor".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn deletion_follows_the_behavior_of_each_type() {
+        let server = MockServer::start().await;
+        let state = test_state(
+            &server,
+            json!({"on_delete": "delete", "on_delete_by_type": {"tag": "keep"}}),
+        );
+
+        let created = sync(
+            &state,
+            &server,
+            vec![
+                (
+                    "web",
+                    monitor(json!({"type": "http", "name": "Web", "url": "https://example.com"})),
+                ),
+                ("team", tag("Team")),
+            ],
+        )
+        .await;
+        assert!(created.contains(&"add".to_owned()));
+        assert!(created.contains(&"addTag".to_owned()));
+
+        let removed = sync(&state, &server, vec![]).await;
+        assert!(removed.contains(&"deleteMonitor".to_owned()));
+        assert!(!removed.contains(&"deleteTag".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn unchanged_status_pages_are_not_updated() {
+        let server = MockServer::start().await;
+        let state = test_state(&server, json!({}));
+
+        let status_page = |groups: serde_json::Value| {
+            Entity::StatusPage(
+                serde_json::from_value(json!({
+                    "slug": "main",
+                    "title": "Main",
+                    "publicGroupList": groups,
+                }))
+                .unwrap(),
+            )
+        };
+        let entities = |groups: serde_json::Value| {
+            vec![
+                (
+                    "web",
+                    monitor(json!({"type": "http", "name": "Web", "url": "https://example.com"})),
+                ),
+                (
+                    "api",
+                    monitor(
+                        json!({"type": "http", "name": "Api", "url": "https://example.com/api"}),
+                    ),
+                ),
+                ("main", status_page(groups)),
+            ]
+        };
+
+        let created = sync(
+            &state,
+            &server,
+            entities(json!([
+                {"name": "Backend", "monitorList": [{"monitor_name": "api"}]},
+                {"name": "Frontend", "monitorList": [{"monitor_name": "web"}, {"monitor_name": "api"}]},
+            ])),
+        )
+        .await;
+        assert!(created.contains(&"saveStatusPage".to_owned()));
+
+        let resynced = sync(
+            &state,
+            &server,
+            entities(json!([
+                {"name": "Frontend", "monitorList": [{"monitor_name": "api"}, {"monitor_name": "web"}]},
+                {"name": "Backend", "monitorList": [{"monitor_name": "api"}]},
+            ])),
+        )
+        .await;
+        assert!(!resynced.contains(&"saveStatusPage".to_owned()));
+        assert!(!resynced.contains(&"editMonitor".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn status_pages_with_weights_are_reordered() {
+        let server = MockServer::start().await;
+        let state = test_state(&server, json!({}));
+
+        let entities = |groups: serde_json::Value| {
+            vec![
+                (
+                    "web",
+                    monitor(json!({"type": "http", "name": "Web", "url": "https://example.com"})),
+                ),
+                (
+                    "api",
+                    monitor(
+                        json!({"type": "http", "name": "Api", "url": "https://example.com/api"}),
+                    ),
+                ),
+                (
+                    "main",
+                    Entity::StatusPage(
+                        serde_json::from_value(json!({
+                            "slug": "main",
+                            "title": "Main",
+                            "publicGroupList": groups,
+                        }))
+                        .unwrap(),
+                    ),
+                ),
+            ]
+        };
+
+        sync(
+            &state,
+            &server,
+            entities(json!([
+                {"name": "Frontend", "weight": 1, "monitorList": [{"monitor_name": "web"}, {"monitor_name": "api"}]},
+                {"name": "Backend", "weight": 2, "monitorList": [{"monitor_name": "api"}]},
+            ])),
+        )
+        .await;
+
+        let unchanged = sync(
+            &state,
+            &server,
+            entities(json!([
+                {"name": "Backend", "weight": 2, "monitorList": [{"monitor_name": "api"}]},
+                {"name": "Frontend", "weight": 1, "monitorList": [{"monitor_name": "web"}, {"monitor_name": "api"}]},
+            ])),
+        )
+        .await;
+        assert!(!unchanged.contains(&"saveStatusPage".to_owned()));
+
+        let reordered_monitors = sync(
+            &state,
+            &server,
+            entities(json!([
+                {"name": "Frontend", "weight": 1, "monitorList": [{"monitor_name": "api"}, {"monitor_name": "web"}]},
+                {"name": "Backend", "weight": 2, "monitorList": [{"monitor_name": "api"}]},
+            ])),
+        )
+        .await;
+        assert!(reordered_monitors.contains(&"saveStatusPage".to_owned()));
+
+        let reordered_groups = sync(
+            &state,
+            &server,
+            entities(json!([
+                {"name": "Frontend", "weight": 2, "monitorList": [{"monitor_name": "api"}, {"monitor_name": "web"}]},
+                {"name": "Backend", "weight": 1, "monitorList": [{"monitor_name": "api"}]},
+            ])),
+        )
+        .await;
+        assert!(reordered_groups.contains(&"saveStatusPage".to_owned()));
+
+        let (_, args) = server
+            .calls()
+            .await
+            .into_iter()
+            .filter(|(method, _)| method == "saveStatusPage")
+            .last()
+            .unwrap();
+        assert_eq!(
+            args[3]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|group| group["name"].clone())
+                .collect_vec(),
+            vec![json!("Backend"), json!("Frontend")]
+        );
+    }
+
+    #[tokio::test]
+    async fn themed_status_pages_round_trip() {
+        let server = MockServer::start().await;
+        let state = test_state(&server, json!({}));
+
+        let status_page = || {
+            Entity::StatusPage(
+                serde_json::from_value(json!({
+                    "slug": "main",
+                    "title": "Main",
+                    "theme": "dark",
+                    "customCSS": ".incident { color: #fff; }\nbody { background: #111; }\n",
+                    "footerText": "Operated by Example",
+                    "showPoweredBy": false,
+                    "publicGroupList": [{"name": "Services", "monitorList": []}],
+                }))
+                .unwrap(),
+            )
+        };
+
+        let created = sync(&state, &server, vec![("main", status_page())]).await;
+        assert!(created.contains(&"saveStatusPage".to_owned()));
+
+        let client = kuma_client::Client::connect(server.config()).await.unwrap();
+        let synced = client.get_status_page("main").await.unwrap();
+        assert_eq!(synced.theme, Some("dark".to_owned()));
+        assert_eq!(
+            synced.custom_css,
+            Some(".incident { color: #fff; }\nbody { background: #111; }\n".to_owned())
+        );
+        assert_eq!(synced.footer_text, Some("Operated by Example".to_owned()));
+        assert_eq!(synced.show_powered_by, Some(false));
+
+        let resynced = sync(&state, &server, vec![("main", status_page())]).await;
+        assert!(!resynced.contains(&"saveStatusPage".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn unknown_status_page_themes_are_rejected() {
+        let server = MockServer::start().await;
+        let state = test_state(&server, json!({}));
+
+        let mut sync = Sync {
+            app_state: state.state.clone(),
+            sources: vec![Box::new(StaticSource(vec![(
+                "main".to_owned(),
+                Entity::StatusPage(
+                    serde_json::from_value(json!({
+                        "slug": "main",
+                        "title": "Main",
+                        "theme": "neon",
+                    }))
+                    .unwrap(),
+                ),
+            )]))],
+            cycle: 0,
+            duplicates: HashSet::new(),
+        };
+        _ = sync.do_sync().await;
+
+        assert!(state.state.failures() > 0);
+        assert!(!server
+            .calls()
+            .await
+            .iter()
+            .any(|(method, _)| method == "addStatusPage" || method == "saveStatusPage"));
     }
 }
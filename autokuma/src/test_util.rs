@@ -0,0 +1,105 @@
+//! Helpers shared by the tests of AutoKuma.
+
+use crate::{
+    app_state::AppState,
+    config::{Config, DEFAULT_INSTANCE},
+    entity::Entity,
+    error::Result,
+    sources::source::Source,
+};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+/// A config using the defaults of all settings, with the top level settings in `values` replacing them.
+pub fn config(values: Value) -> Config {
+    let mut config = json!({
+        "kuma": {"url": "http://127.0.0.1:3001"},
+        "docker": {},
+        "files": {},
+        "kubernetes": {"ingress": {}},
+        "systemd": {},
+        "nomad": {},
+        "metrics": {},
+        "auto_shrink": {},
+        "on_delete_by_type": {},
+    });
+
+    if let (Some(config), Value::Object(values)) = (config.as_object_mut(), values) {
+        config.extend(values);
+    }
+
+    serde_json::from_value(config).expect("Invalid test config")
+}
+
+/// An [AppState] with its database stored in a temporary directory, which is removed when dropped.
+pub struct TestState {
+    pub state: Arc<AppState>,
+    _dir: TempDir,
+}
+
+impl TestState {
+    pub fn new(values: Value) -> Self {
+        let dir = TempDir::new().expect("Unable to create temporary directory");
+
+        let mut config = config(values);
+        config.data_path = Some(dir.path().to_string_lossy().to_string());
+
+        let state = Arc::new(AppState::new(Arc::new(config)).expect("Unable to create app state"));
+
+        Self { state, _dir: dir }
+    }
+}
+
+/// A source always returning the same entities for the default instance.
+pub struct StaticSource(pub Vec<(String, Entity)>);
+
+#[async_trait]
+impl Source for StaticSource {
+    fn name(&self) -> &'static str {
+        "Static"
+    }
+
+    fn instance(&self) -> &str {
+        DEFAULT_INSTANCE
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_entities(&mut self) -> Result<Vec<(String, Entity)>> {
+        Ok(self.0.clone())
+    }
+
+    async fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A source returning the same entities for the default instance under the given name, to simulate different sources.
+pub struct NamedSource(pub &'static str, pub Vec<(String, Entity)>);
+
+#[async_trait]
+impl Source for NamedSource {
+    fn name(&self) -> &'static str {
+        self.0
+    }
+
+    fn instance(&self) -> &str {
+        DEFAULT_INSTANCE
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_entities(&mut self) -> Result<Vec<(String, Entity)>> {
+        Ok(self.1.clone())
+    }
+
+    async fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
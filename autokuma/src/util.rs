@@ -37,6 +37,69 @@ where
         })
 }
 
+/// Split snippet arguments on commas, ignoring commas inside of quoted strings, arrays and objects.
+///
+/// Each argument is parsed as JSON if possible, otherwise it's used as a plain string,
+/// e.g. `"influx.example.com",443` and `influx.example.com, 443` both result in `["influx.example.com", 443]`.
+pub fn parse_snippet_args(args: &str) -> Result<Vec<serde_json::Value>> {
+    let mut tokens = vec![];
+    let mut current = String::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut depth: usize = 0;
+
+    for c in args.chars() {
+        if in_string {
+            match (escaped, c) {
+                (true, _) => escaped = false,
+                (false, '\\') => escaped = true,
+                (false, '"') => in_string = false,
+                _ => {}
+            }
+            current.push(c);
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            '[' | '{' => depth += 1,
+            ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                tokens.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+
+        current.push(c);
+    }
+
+    if in_string {
+        return Err(Error::LabelParseError(format!(
+            "Unterminated string in snippet arguments: {}",
+            args
+        )));
+    }
+
+    if !tokens.is_empty() || !current.trim().is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+        .into_iter()
+        .map(|token| {
+            let token = token.trim();
+            serde_json::from_str(token).or_else(|e| match token.starts_with('"') {
+                true => Err(Error::LabelParseError(format!(
+                    "Invalid snippet argument {}: {}",
+                    token, e
+                ))),
+                false => Ok(serde_json::Value::String(token.to_owned())),
+            })
+        })
+        .collect()
+}
+
 pub trait ResultOrDie<T> {
     fn unwrap_or_die(self, exit_code: i32) -> T;
 }
@@ -91,8 +154,10 @@ fn insert_object(
     Ok(())
 }
 
+/// Read environment variables from templates, only variables starting with `AUTOKUMA__ENV__` are accessible unless `insecure_env_access` is set.
 struct GetEnvFunction {
     config: Arc<Config>,
+    function_name: &'static str,
 }
 
 impl tera::Function for GetEnvFunction {
@@ -105,15 +170,16 @@ impl tera::Function for GetEnvFunction {
                 Ok(v) => v,
                 Err(_) => {
                     return Err(tera::Error::msg(format!(
-                        "Function `get_env` received name={} but `name` can only be a string",
-                        val
+                        "Function `{}` received name={} but `name` can only be a string",
+                        self.function_name, val
                     )));
                 }
             },
             None => {
-                return Err(tera::Error::msg(
-                    "Function `get_env` didn't receive a `name` argument",
-                ))
+                return Err(tera::Error::msg(format!(
+                    "Function `{}` didn't receive a `name` argument",
+                    self.function_name
+                )))
             }
         };
 
@@ -144,13 +210,159 @@ pub fn fill_templates(
 ) -> Result<String> {
     let template = template.into();
     let mut tera = Tera::default();
-    let get_env = GetEnvFunction {
-        config: config.clone(),
-    };
+    for function_name in ["get_env", "env"] {
+        tera.register_function(
+            function_name,
+            GetEnvFunction {
+                config: config.clone(),
+                function_name,
+            },
+        );
+    }
 
-    tera.register_function("get_env", get_env);
+    let mut context = tera::Context::new();
+    for (key, value) in &config.template_values {
+        context.insert(key, value);
+    }
+    context.extend(template_values.clone());
 
     tera.add_raw_template(&template, &template)
-        .and_then(|_| tera.render(&template, template_values))
+        .and_then(|_| tera.render(&template, &context))
         .map_err(|e| Error::LabelParseError(print_error_chain(&e)))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snippet_args_url_and_port() {
+        assert_eq!(
+            parse_snippet_args(r#""influx.dyer.house",443"#).unwrap(),
+            vec![json!("influx.dyer.house"), json!(443)]
+        );
+        assert_eq!(
+            parse_snippet_args("https://example.com/a,b, 8080").unwrap(),
+            vec![json!("https://example.com/a"), json!("b"), json!(8080)]
+        );
+    }
+
+    #[test]
+    fn snippet_args_escaped_quote() {
+        assert_eq!(
+            parse_snippet_args(r#""say \"hi\", bye",1"#).unwrap(),
+            vec![json!(r#"say "hi", bye"#), json!(1)]
+        );
+    }
+
+    #[test]
+    fn snippet_args_nested_values() {
+        assert_eq!(
+            parse_snippet_args(r#"[1, 2], {"a": "b,c"}, true"#).unwrap(),
+            vec![json!([1, 2]), json!({"a": "b,c"}), json!(true)]
+        );
+        assert_eq!(
+            parse_snippet_args("").unwrap(),
+            Vec::<serde_json::Value>::new()
+        );
+    }
+
+    #[test]
+    fn snippet_args_unterminated_string() {
+        assert!(parse_snippet_args(r#""open,1"#).is_err());
+    }
+
+    fn render(config: serde_json::Value, template: &str) -> Result<String> {
+        fill_templates(
+            Arc::new(crate::test_util::config(config)),
+            template,
+            &tera::Context::new(),
+        )
+    }
+
+    #[test]
+    fn env_set_unset_and_default() {
+        std::env::set_var("AUTOKUMA__ENV__UTIL_TEST_DOMAIN", "example.com");
+        std::env::remove_var("AUTOKUMA__ENV__UTIL_TEST_MISSING");
+
+        assert_eq!(
+            render(
+                json!({}),
+                r#"{{ env(name="AUTOKUMA__ENV__UTIL_TEST_DOMAIN") }}"#
+            )
+            .unwrap(),
+            "example.com"
+        );
+        assert_eq!(
+            render(
+                json!({}),
+                r#"{{ get_env(name="AUTOKUMA__ENV__UTIL_TEST_DOMAIN", default="unused") }}"#
+            )
+            .unwrap(),
+            "example.com"
+        );
+        assert_eq!(
+            render(
+                json!({}),
+                r#"{{ env(name="AUTOKUMA__ENV__UTIL_TEST_MISSING", default="fallback") }}"#
+            )
+            .unwrap(),
+            "fallback"
+        );
+        assert!(render(
+            json!({}),
+            r#"{{ env(name="AUTOKUMA__ENV__UTIL_TEST_MISSING") }}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn env_access_is_restricted() {
+        std::env::set_var("UTIL_TEST_SECRET", "secret");
+
+        assert!(render(json!({}), r#"{{ env(name="UTIL_TEST_SECRET") }}"#).is_err());
+        assert_eq!(
+            render(
+                json!({"insecure_env_access": true}),
+                r#"{{ env(name="UTIL_TEST_SECRET") }}"#
+            )
+            .unwrap(),
+            "secret"
+        );
+    }
+
+    #[test]
+    fn default_settings_use_global_template_values() {
+        let test_state = crate::test_util::TestState::new(json!({
+            "default_settings": "http.url: https://{{ container_name }}.{{ base_domain }}",
+            "template_values": {"base_domain": "example.com", "container_name": "global"},
+        }));
+
+        let mut context = tera::Context::new();
+        context.insert("container_name", "web");
+
+        let entity = crate::entity::get_entity_from_settings(
+            test_state.state.clone(),
+            "web",
+            "http",
+            vec![("name".to_owned(), json!("Web"))],
+            &context,
+        )
+        .unwrap();
+
+        let crate::entity::Entity::Monitor(kuma_client::monitor::Monitor::Http { value }) = entity
+        else {
+            panic!("Expected an http monitor, got {:?}", entity);
+        };
+        assert_eq!(value.url, Some("https://web.example.com".to_owned()));
+
+        assert_eq!(
+            render(
+                json!({"template_values": {"base_domain": "example.com"}}),
+                "{{ base_domain }}"
+            )
+            .unwrap(),
+            "example.com"
+        );
+    }
+}
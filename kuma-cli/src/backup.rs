@@ -0,0 +1,57 @@
+use crate::{
+    cli::Cli,
+    utils::{connect, load_files, CollectOrUnwrap, PrintResult as _},
+};
+use clap::Subcommand;
+use futures_util::future::{join_all, FutureExt};
+use kuma_client::{backup::ImportHandle, error::Result, Config};
+use serde_json::Value;
+use std::path::PathBuf;
+use tap::Pipe;
+
+#[derive(Subcommand, Clone, Debug)]
+#[command(arg_required_else_help = true)]
+pub(crate) enum Command {
+    /// Export a backup
+    Export {},
+    /// Import a backup
+    Import {
+        file: Vec<PathBuf>,
+
+        /// How to handle monitors and notifications which already exist (skip, keep, overwrite)
+        #[arg(long, default_value_t = ImportHandle::Skip)]
+        import_handle: ImportHandle,
+    },
+}
+
+pub(crate) async fn handle(command: &Option<Command>, config: &Config, cli: &Cli) {
+    match command {
+        Some(Command::Export {}) => connect(config, cli)
+            .await
+            .export_backup()
+            .await
+            .print_result(cli),
+
+        Some(Command::Import {
+            file,
+            import_handle,
+        }) => connect(config, cli)
+            .await
+            .pipe_borrow(|client| {
+                load_files::<Value>(file, cli).then(|values| {
+                    join_all(
+                        values
+                            .into_iter()
+                            .map(|value| client.import_backup(value, *import_handle)),
+                    )
+                })
+            })
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>()
+            .map(|result| result.into_iter().collect_or_unwrap())
+            .print_result(cli),
+
+        None => {}
+    }
+}
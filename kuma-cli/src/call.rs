@@ -0,0 +1,26 @@
+use crate::{
+    cli::Cli,
+    utils::{connect, PrintResult as _, ResultOrDie as _},
+};
+use kuma_client::Config;
+use serde_json::Value;
+
+pub(crate) async fn handle(
+    method: &str,
+    args: &[String],
+    result_ptr: &str,
+    config: &Config,
+    cli: &Cli,
+) {
+    let args = args
+        .iter()
+        .map(|arg| serde_json::from_str::<Value>(arg))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_or_die(cli);
+
+    connect(config, cli)
+        .await
+        .call_raw(method, args, result_ptr)
+        .await
+        .print_result(cli);
+}
@@ -14,6 +14,10 @@ pub(crate) struct Cli {
     #[arg(long, global = true)]
     pub url: Option<String>,
 
+    /// The path Uptime Kuma is served under when using a reverse proxy with a prefix (defaults to /).
+    #[arg(long, global = true)]
+    pub base_path: Option<String>,
+
     /// The username for logging into Uptime Kuma (required unless auth is disabled).
     #[arg(long, global = true)]
     pub username: Option<String>,
@@ -22,6 +26,10 @@ pub(crate) struct Cli {
     #[arg(long, global = true)]
     pub password: Option<String>,
 
+    /// Path to a file containing the password for logging into Uptime Kuma.
+    #[arg(long, global = true)]
+    pub password_file: Option<String>,
+
     /// The MFA token for logging into Uptime Kuma (required if MFA is enabled).
     #[arg(long, global = true)]
     pub mfa_token: Option<String>,
@@ -38,8 +46,20 @@ pub(crate) struct Cli {
     #[arg(long, default_value = "30.0", global = true)]
     pub call_timeout: Option<f64>,
 
+    /// How long to wait for Uptime Kuma to send its initial data after connecting.
+    #[arg(long, global = true)]
+    pub ready_timeout: Option<f64>,
+
+    /// How many times a call should be retried after a transient error.
+    #[arg(long, global = true)]
+    pub call_max_retries: Option<u32>,
+
+    /// Which Uptime Kuma API to use, `auto` uses the version reported by the server.
+    #[arg(long, value_parser = ["auto", "v1", "v2"], global = true)]
+    pub api_version: Option<String>,
+
     /// The output format
-    #[arg(value_enum, long = "format", default_value_t = OutputFormat::Json, global = true)]
+    #[arg(value_enum, long = "format", visible_alias = "output", default_value_t = OutputFormat::Json, global = true)]
     pub output_format: OutputFormat,
 
     /// Disable TLS certificate verification
@@ -50,10 +70,22 @@ pub(crate) struct Cli {
     #[arg(long = "tls-certificate", global = true)]
     pub tls_certificate: Option<String>,
 
+    /// Path to a client certificate in PEM format to use for connecting to Uptime Kuma using mutual TLS
+    #[arg(long = "tls-client-cert", global = true)]
+    pub tls_client_cert: Option<String>,
+
+    /// Path to the private key of the client certificate in PEM format
+    #[arg(long = "tls-client-key", global = true)]
+    pub tls_client_key: Option<String>,
+
     /// Whether the output should be pretty printed or condensed
     #[arg(long = "pretty", default_value_t = false, global = true)]
     pub output_pretty: bool,
 
+    /// Load the configuration from this file instead of searching the default locations.
+    #[arg(long, global = true)]
+    pub config: Option<String>,
+
     #[arg(long, hide = true)]
     pub shadow: bool,
 
@@ -63,13 +95,35 @@ pub(crate) struct Cli {
 
 impl From<Cli> for Config {
     fn from(value: Cli) -> Self {
-        config::Config::builder()
-            .add_source(File::from_str(
-                &serde_json::to_string(&json!({"tls": {}})).unwrap(),
-                FileFormat::Json,
-            ))
-            .add_source(config::File::with_name(&dirs::config_local_dir().map(|dir| dir.join("kuma").join("config").to_string_lossy().to_string()).unwrap_or_default()).required(false))
-            .add_source(config::File::with_name("kuma").required(false))
+        let builder = config::Config::builder().add_source(File::from_str(
+            &serde_json::to_string(&json!({"tls": {}})).unwrap(),
+            FileFormat::Json,
+        ));
+
+        let builder = match value
+            .config
+            .clone()
+            .or_else(|| std::env::var("KUMA__CONFIG_FILE").ok())
+        {
+            Some(path) => builder.add_source(config::File::with_name(&path).required(true)),
+            None => builder
+                .add_source(
+                    config::File::with_name(
+                        &dirs::config_local_dir()
+                            .map(|dir| {
+                                dir.join("kuma")
+                                    .join("config")
+                                    .to_string_lossy()
+                                    .to_string()
+                            })
+                            .unwrap_or_default(),
+                    )
+                    .required(false),
+                )
+                .add_source(config::File::with_name("kuma").required(false)),
+        };
+
+        builder
             .add_source(
                 config::Environment::with_prefix("KUMA")
                     .separator("__")
@@ -77,8 +131,10 @@ impl From<Cli> for Config {
             )
             .set_default("headers", Vec::<String>::new()).unwrap()
             .set_override_option("url", value.url.clone()).unwrap()
+            .set_override_option("base_path", value.base_path.clone()).unwrap()
             .set_override_option("username", value.username.clone()).unwrap()
             .set_override_option("password", value.password.clone()).unwrap()
+            .set_override_option("password_file", value.password_file.clone()).unwrap()
             .set_override_option("mfa_token", value.mfa_token.clone()).unwrap()
             .set_override_option(
                 "headers",
@@ -89,10 +145,19 @@ impl From<Cli> for Config {
             ).unwrap()
             .set_override_option("connect_timeout", value.connect_timeout).unwrap()
             .set_override_option("call_timeout", value.call_timeout).unwrap()
+            .set_override_option("ready_timeout", value.ready_timeout).unwrap()
+            .set_override_option("call_max_retries", value.call_max_retries).unwrap()
+            .set_override_option("api_version", value.api_version.clone()).unwrap()
             .set_override_option("tls.verify", value.tls_no_verify.map(|v| !v)).unwrap()
             .set_override_option("tls.cert", value.tls_certificate.clone()).unwrap()
+            .set_override_option("tls.client_cert", value.tls_client_cert.clone()).unwrap()
+            .set_override_option("tls.client_key", value.tls_client_key.clone()).unwrap()
             .build()
-            .and_then(|config| config.try_deserialize())
+            .and_then(|config| config.try_deserialize::<Config>())
+            .map(|config| match (&config.auth_token, &config.auth_token_file) {
+                (None, None) => Config { auth_token: crate::login::stored_auth_token(&config.base_url()), ..config },
+                _ => config,
+            })
             .unwrap_or_else(|e| match &e {
                 config::ConfigError::Message(msg) if msg == "missing field `url`" => Cli::command().error(clap::error::ErrorKind::MissingRequiredArgument, "the following required arguments were not provided:\n  \x1b[32m--url <URL>\x1b[0m").exit(),
                 e => Err(e).unwrap_or_die(&value),
@@ -133,4 +198,29 @@ pub(crate) enum Commands {
         #[command(subcommand)]
         command: Option<crate::docker_host::Command>,
     },
+    /// Manage Backups
+    Backup {
+        #[command(subcommand)]
+        command: Option<crate::backup::Command>,
+    },
+    /// Call an arbitrary Uptime Kuma socket method, bypassing any validation
+    Call {
+        /// The name of the socket method, e.g. `getMonitorBeats`
+        method: String,
+        /// The JSON arguments of the call
+        args: Vec<String>,
+        /// JSON pointer to the part of the response which should be returned
+        #[arg(long, default_value = "")]
+        result_ptr: String,
+    },
+    /// Log in using the username/password and store the session token for subsequent commands
+    Login {},
+    /// Remove the session token stored by login
+    Logout {},
+    /// Print a shell completion script
+    Completions {
+        /// The shell to generate the completion script for
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
 }
@@ -0,0 +1,109 @@
+use crate::{
+    cli::Cli,
+    utils::{print_value, ResultOrDie as _},
+};
+use kuma_client::{error::Error, Client, Config, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{io::Write as _, path::PathBuf};
+
+/// A token stored by `kuma login` together with the url of the server it was issued by.
+#[derive(Serialize, Deserialize)]
+struct StoredAuthToken {
+    url: Url,
+    token: String,
+}
+
+/// Location of the token stored by `kuma login`.
+fn auth_token_path() -> Option<PathBuf> {
+    dirs::config_local_dir().map(|dir| dir.join("kuma").join("auth-token"))
+}
+
+/// Returns the token stored by `kuma login`, if any was issued by the server at `url`.
+pub(crate) fn stored_auth_token(url: &Url) -> Option<String> {
+    auth_token_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<StoredAuthToken>(&content).ok())
+        .filter(|stored| &stored.url == url && !stored.token.is_empty())
+        .map(|stored| stored.token)
+}
+
+fn prompt_mfa_token() -> std::io::Result<String> {
+    eprint!("MFA Token: ");
+    std::io::stderr().flush()?;
+
+    let mut token = String::new();
+    std::io::stdin().read_line(&mut token)?;
+
+    Ok(token.trim().to_owned())
+}
+
+fn store_auth_token(path: &PathBuf, token: &StoredAuthToken) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    options
+        .open(path)
+        .and_then(|mut file| Ok(serde_json::to_writer(&mut file, token)?))
+}
+
+async fn login(config: &Config, path: &PathBuf) -> std::result::Result<(), String> {
+    let mut config = Config {
+        auth_token: None,
+        auth_token_file: None,
+        ..config.clone()
+    };
+
+    if config.username.is_none() || (config.password.is_none() && config.password_file.is_none()) {
+        return Err(Error::NotAuthenticated.to_string());
+    }
+
+    let url = config.base_url();
+    let client = match Client::connect(config.clone()).await {
+        Err(Error::TokenRequired) if config.mfa_token.is_none() => {
+            config.mfa_token = Some(prompt_mfa_token().map_err(|e| e.to_string())?);
+            Client::connect(config).await
+        }
+        result => result,
+    }
+    .map_err(|e| e.to_string())?;
+
+    let token = client
+        .get_auth_token()
+        .await
+        .ok_or_else(|| "The server didn't return a token".to_owned())?;
+
+    let token = StoredAuthToken { url, token };
+
+    store_auth_token(path, &token)
+        .map_err(|e| format!("Unable to store token in {}: {}", path.display(), e))
+}
+
+pub(crate) async fn handle_login(config: &Config, cli: &Cli) {
+    let path = auth_token_path()
+        .ok_or("Unable to determine the config directory")
+        .unwrap_or_die(cli);
+
+    login(config, &path).await.unwrap_or_die(cli);
+
+    print_value(&json!({"token_file": path}), cli);
+}
+
+pub(crate) async fn handle_logout(cli: &Cli) {
+    let path = auth_token_path()
+        .ok_or("Unable to determine the config directory")
+        .unwrap_or_die(cli);
+
+    if path.exists() {
+        std::fs::remove_file(&path).unwrap_or_die(cli);
+    }
+
+    print_value(&json!({"token_file": path}), cli);
+}
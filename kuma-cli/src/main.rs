@@ -1,10 +1,13 @@
-use clap::Parser as _;
+use clap::{CommandFactory as _, Parser as _};
 use cli::{Cli, Commands};
 use flexi_logger::Logger;
 use kuma_client::Config;
 
+mod backup;
+mod call;
 mod cli;
 mod docker_host;
+mod login;
 mod maintenance;
 mod monitor;
 mod notification;
@@ -20,6 +23,19 @@ async fn main() {
         .unwrap();
 
     let cli = Cli::parse();
+
+    if let Some(Commands::Completions { shell }) = &cli.command {
+        clap_complete::generate(*shell, &mut Cli::command(), "kuma", &mut std::io::stdout());
+        logger.shutdown();
+        return;
+    }
+
+    if let Some(Commands::Logout {}) = &cli.command {
+        login::handle_logout(&cli).await;
+        logger.shutdown();
+        return;
+    }
+
     let config = Config::from(cli.clone());
 
     match &cli.command {
@@ -33,6 +49,14 @@ async fn main() {
         }
         Some(Commands::StatusPage { command }) => status_page::handle(command, &config, &cli).await,
         Some(Commands::DockerHost { command }) => docker_host::handle(command, &config, &cli).await,
+        Some(Commands::Backup { command }) => backup::handle(command, &config, &cli).await,
+        Some(Commands::Call {
+            method,
+            args,
+            result_ptr,
+        }) => call::handle(method, args, result_ptr, &config, &cli).await,
+        Some(Commands::Login {}) => login::handle_login(&config, &cli).await,
+        Some(Commands::Completions { .. }) | Some(Commands::Logout {}) => {}
         None if cli.shadow => kuma_client::build::print_build_in(),
         None => {}
     };
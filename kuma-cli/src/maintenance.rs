@@ -1,10 +1,14 @@
 use crate::{
     cli::Cli,
-    utils::{connect, load_files, CollectOrUnwrap, PrintResult as _},
+    utils::{connect, load_file, load_files, CollectOrUnwrap, PrintResult as _},
 };
 use clap::Subcommand;
 use futures_util::{future::join_all, FutureExt};
-use kuma_client::{error::Result, Config};
+use kuma_client::{
+    error::{Error, Result},
+    maintenance::Maintenance,
+    Config,
+};
 use std::path::PathBuf;
 use tap::Pipe;
 
@@ -12,9 +16,12 @@ use tap::Pipe;
 #[command(arg_required_else_help = true)]
 pub(crate) enum Command {
     /// Add a new Maintenance
+    #[command(visible_alias = "create")]
     Add { file: Vec<PathBuf> },
     /// Edit a Maintenance
     Edit { file: Vec<PathBuf> },
+    /// Replace the Maintenance with the given id with the definition from a file
+    Update { id: i32, file: PathBuf },
     /// Get a Maintenance
     Get { id: Vec<i32> },
     /// Delete a Maintenance
@@ -63,6 +70,28 @@ pub(crate) async fn handle(command: &Option<Command>, config: &Config, cli: &Cli
             .map(|result| result.into_iter().collect_or_unwrap())
             .print_result(cli),
 
+        Some(Command::Update { id, file }) => connect(config, cli)
+            .await
+            .pipe_borrow(|client| {
+                load_file::<Maintenance>(file, cli).then(move |values| async move {
+                    // Every entry would replace the same Maintenance, so only a single one is accepted
+                    let [mut value] = <[Maintenance; 1]>::try_from(values).map_err(|values| {
+                        Error::ValidationError(
+                            file.display().to_string(),
+                            vec![format!(
+                                "Expected a single Maintenance, found {}",
+                                values.len()
+                            )],
+                        )
+                    })?;
+
+                    value.common_mut().id = Some(*id);
+                    client.edit_maintenance(value).await
+                })
+            })
+            .await
+            .print_result(cli),
+
         Some(Command::Get { id }) => connect(config, cli)
             .await
             .pipe_borrow(|client| join_all(id.iter().map(|id| client.get_maintenance(*id))))
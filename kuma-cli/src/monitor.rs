@@ -1,11 +1,26 @@
 use crate::{
     cli::Cli,
-    utils::{connect, load_files, CollectOrUnwrap, PrintResult as _},
+    utils::{
+        connect, load_files, print_value, CollectOrUnwrap, OutputFormat, PrintResult as _,
+        ResultOrDie as _,
+    },
 };
 use clap::Subcommand;
-use futures_util::{future::join_all, FutureExt};
-use kuma_client::{error::Result, monitor::Monitor, Config};
-use std::path::PathBuf;
+use futures_util::{future::join_all, stream, FutureExt, StreamExt as _};
+use itertools::Itertools;
+use kuma_client::{
+    error::{Error, Result},
+    heartbeat::{Heartbeat, HeartbeatStatus},
+    monitor::{Monitor, MonitorList, MonitorType},
+    Client, Config,
+};
+use log::warn;
+use serde::Serialize;
+use serde_json::json;
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
 use tap::Pipe;
 
 #[derive(Subcommand, Clone, Debug)]
@@ -22,9 +37,344 @@ pub(crate) enum Command {
     /// Get all Monitors
     List {},
     /// Start/Resume a Monitor
-    Resume { id: Vec<i32> },
+    Resume {
+        id: Vec<i32>,
+        /// Also resume all monitors below a group
+        #[arg(long)]
+        recursive: bool,
+    },
     /// Stop/Pause a Monitor
-    Pause { id: Vec<i32> },
+    Pause {
+        id: Vec<i32>,
+        /// Also pause all monitors below a group
+        #[arg(long)]
+        recursive: bool,
+    },
+    /// Create a copy of a Monitor including its tags and notifications
+    Clone { id: i32, new_name: String },
+    /// Show all Monitors as a tree based on their parent groups
+    Tree {},
+    /// Show the current state, last ping and 24h uptime of a Monitor, or of all Monitors if no id is given
+    Status { id: Option<i32> },
+    /// Get the URL used to send heartbeats to a push Monitor
+    PushUrl { id: Vec<i32> },
+    /// Create or update Monitors from files in the format of AutoKuma's static monitors, existing Monitors are matched by name
+    Import {
+        file: Vec<PathBuf>,
+
+        /// Delete all Monitors which are not present in the given files
+        #[arg(long)]
+        delete_missing: bool,
+    },
+}
+
+#[derive(Serialize, Default)]
+struct ImportSummary {
+    created: Vec<String>,
+    updated: Vec<String>,
+    deleted: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    skipped: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct MonitorTreeNode {
+    id: i32,
+    name: Option<String>,
+    #[serde(rename = "type")]
+    monitor_type: MonitorType,
+    active: Option<bool>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    children: Vec<MonitorTreeNode>,
+}
+
+impl MonitorTreeNode {
+    fn flatten(&self, depth: usize, rows: &mut Vec<serde_json::Value>) {
+        rows.push(json!({
+            "id": self.id,
+            "name": format!("{}{}", "  ".repeat(depth), self.name.clone().unwrap_or_default()),
+            "type": self.monitor_type,
+            "active": self.active,
+        }));
+
+        for child in &self.children {
+            child.flatten(depth + 1, rows);
+        }
+    }
+}
+
+fn build_monitor_tree(monitors: &MonitorList) -> Vec<MonitorTreeNode> {
+    let monitors = monitors
+        .values()
+        .filter_map(|monitor| monitor.common().id().map(|id| (id, monitor)))
+        .collect::<HashMap<_, _>>();
+
+    // Parents which don't exist are ignored, so their children are still shown
+    let mut parents = monitors
+        .iter()
+        .map(|(id, monitor)| {
+            (
+                *id,
+                monitor
+                    .common()
+                    .parent()
+                    .filter(|parent| monitors.contains_key(parent)),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    for id in monitors.keys().sorted() {
+        let mut seen = HashSet::from([*id]);
+        let mut current = parents[id];
+
+        while let Some(parent) = current {
+            if !seen.insert(parent) {
+                if parent == *id {
+                    warn!(
+                        "Monitor {} is part of a parent cycle, showing it at the top level",
+                        id
+                    );
+                    parents.insert(*id, None);
+                }
+                break;
+            }
+
+            current = parents[&parent];
+        }
+    }
+
+    let children = parents
+        .iter()
+        .map(|(id, parent)| (*parent, *id))
+        .into_group_map();
+
+    fn build(
+        parent: Option<i32>,
+        monitors: &HashMap<i32, &Monitor>,
+        children: &HashMap<Option<i32>, Vec<i32>>,
+    ) -> Vec<MonitorTreeNode> {
+        children
+            .get(&parent)
+            .into_iter()
+            .flatten()
+            .map(|id| (id, monitors[id]))
+            .sorted_by_key(|(id, monitor)| (monitor.common().name().clone(), **id))
+            .map(|(id, monitor)| MonitorTreeNode {
+                id: *id,
+                name: monitor.common().name().clone(),
+                monitor_type: monitor.monitor_type(),
+                active: *monitor.common().active(),
+                children: build(Some(*id), monitors, children),
+            })
+            .collect()
+    }
+
+    build(None, &monitors, &children)
+}
+
+#[derive(Serialize)]
+struct MonitorStatus {
+    id: i32,
+    name: Option<String>,
+    status: &'static str,
+    last_ping: Option<f64>,
+    last_heartbeat: Option<String>,
+    uptime_24h: Option<f64>,
+}
+
+/// Uptime ratio of the given heartbeats, ignoring pending and maintenance heartbeats like Uptime Kuma does.
+fn uptime_from_heartbeats(heartbeats: &[Heartbeat]) -> Option<f64> {
+    let (up, down) =
+        heartbeats
+            .iter()
+            .fold((0, 0), |(up, down), heartbeat| match heartbeat.status {
+                Some(HeartbeatStatus::Up) => (up + 1, down),
+                Some(HeartbeatStatus::Down) => (up, down + 1),
+                _ => (up, down),
+            });
+
+    (up + down > 0).then(|| up as f64 / (up + down) as f64)
+}
+
+/// The maximum number of `getMonitorBeats` calls running at the same time.
+const MAX_CONCURRENT_BEAT_CALLS: usize = 8;
+
+async fn get_monitor_status(client: &Client, id: Option<i32>) -> Result<Vec<MonitorStatus>> {
+    let monitors = client.get_monitors().await?;
+    let heartbeats = client.get_heartbeats().await?;
+    let uptimes = client.get_uptimes().await?;
+
+    let monitors = monitors
+        .values()
+        .filter_map(|monitor| {
+            monitor
+                .common()
+                .id()
+                .map(|monitor_id| (monitor_id, monitor))
+        })
+        .filter(|(monitor_id, _)| id.map_or(true, |id| id == *monitor_id))
+        .sorted_by_key(|(monitor_id, _)| *monitor_id)
+        .collect_vec();
+
+    if let (Some(id), true) = (id, monitors.is_empty()) {
+        return Err(Error::IdNotFound("Monitor".to_owned(), id));
+    }
+
+    let statuses = stream::iter(monitors.into_iter().map(|(monitor_id, monitor)| {
+        let uptime = uptimes.get(&monitor_id).cloned().unwrap_or_default();
+        let mut heartbeats = heartbeats.get(&monitor_id).cloned().unwrap_or_default();
+
+        async move {
+            // The caches are filled by events Uptime Kuma sends after login, which might not have arrived yet
+            if heartbeats.is_empty() || uptime.uptime_24h.is_none() {
+                heartbeats = client.get_monitor_beats(monitor_id, 24).await?;
+            }
+
+            let last = heartbeats.last();
+
+            Ok(MonitorStatus {
+                id: monitor_id,
+                name: monitor.common().name().clone(),
+                status: match (monitor.common().active(), last.and_then(|last| last.status)) {
+                    (Some(false), _) => "paused",
+                    (_, Some(HeartbeatStatus::Up)) => "up",
+                    (_, Some(HeartbeatStatus::Down)) => "down",
+                    (_, Some(HeartbeatStatus::Pending)) => "pending",
+                    (_, Some(HeartbeatStatus::Maintenance)) => "maintenance",
+                    (_, None) => "unknown",
+                },
+                last_ping: last.and_then(|last| last.ping),
+                last_heartbeat: last.and_then(|last| last.time.clone()),
+                uptime_24h: uptime
+                    .uptime_24h
+                    .or_else(|| uptime_from_heartbeats(&heartbeats))
+                    .map(|uptime| (uptime * 10000.0).round() / 100.0),
+            })
+        }
+    }))
+    .buffer_unordered(MAX_CONCURRENT_BEAT_CALLS)
+    .collect::<Vec<Result<MonitorStatus>>>()
+    .await;
+
+    Ok(statuses
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .sorted_by_key(|status| status.id)
+        .collect())
+}
+
+/// A document containing multiple monitors is a map of `id -> monitor`, while a single monitor always has a `type`.
+fn is_multi_entity_map(value: &serde_json::Map<String, serde_json::Value>) -> bool {
+    !value.contains_key("type") && !value.is_empty() && value.values().all(|v| v.is_object())
+}
+
+/// Reads monitor definitions laid out like the files of AutoKuma's file source: a single monitor, an array of monitors
+/// (optionally with an `id` string) or a map of `id -> monitor`. Entries which aren't a named monitor are returned as errors.
+fn monitors_from_definitions(definitions: Vec<serde_json::Value>) -> (Vec<Monitor>, Vec<String>) {
+    let entries = definitions
+        .into_iter()
+        .flat_map(|definition| match definition {
+            serde_json::Value::Array(entries) => entries
+                .into_iter()
+                .map(|mut entry| {
+                    let id = entry
+                        .as_object_mut()
+                        .and_then(|entry| match entry.get("id") {
+                            Some(serde_json::Value::String(_)) => entry.remove("id"),
+                            _ => None,
+                        })
+                        .and_then(|id| id.as_str().map(|id| id.to_owned()));
+                    (id, entry)
+                })
+                .collect_vec(),
+            serde_json::Value::Object(entries) if is_multi_entity_map(&entries) => entries
+                .into_iter()
+                .map(|(id, entry)| (Some(id), entry))
+                .collect_vec(),
+            entry => vec![(None, entry)],
+        })
+        .enumerate()
+        .map(|(index, (id, entry))| (id.unwrap_or_else(|| format!("#{}", index)), entry));
+
+    let mut monitors = vec![];
+    let mut errors = vec![];
+
+    for (id, entry) in entries {
+        match serde_json::from_value::<Monitor>(entry) {
+            Ok(Monitor::Unknown { value }) => errors.push(format!(
+                "Monitor {} has an unknown type '{}'",
+                id, value.type_name
+            )),
+            Ok(monitor)
+                if monitor
+                    .common()
+                    .name()
+                    .as_deref()
+                    .unwrap_or_default()
+                    .is_empty() =>
+            {
+                errors.push(format!("Monitor {} is missing a name", id))
+            }
+            Ok(monitor) => monitors.push(monitor),
+            Err(e) => errors.push(format!("Monitor {} is invalid: {}", id, e)),
+        }
+    }
+
+    (monitors, errors)
+}
+
+async fn import_monitors(
+    client: &Client,
+    definitions: Vec<serde_json::Value>,
+    delete_missing: bool,
+) -> Result<ImportSummary> {
+    let (monitors, errors) = monitors_from_definitions(definitions);
+    let existing = client.get_monitors().await?;
+    let mut summary = ImportSummary::default();
+
+    for error in errors {
+        warn!("Skipping invalid definition: {}", error);
+        summary.skipped.push(error);
+    }
+
+    let names = monitors
+        .iter()
+        .filter_map(|monitor| monitor.common().name().clone())
+        .collect::<HashSet<_>>();
+
+    for mut monitor in monitors {
+        let name = monitor.common().name().clone().unwrap_or_default();
+        let current = existing
+            .values()
+            .find(|current| current.common().name().as_ref() == Some(&name));
+
+        match current.and_then(|current| *current.common().id()) {
+            Some(id) => {
+                *monitor.common_mut().id_mut() = Some(id);
+                client.edit_monitor(monitor).await?;
+                summary.updated.push(name);
+            }
+            None => {
+                *monitor.common_mut().id_mut() = None;
+                client.add_monitor(monitor).await?;
+                summary.created.push(name);
+            }
+        }
+    }
+
+    if delete_missing {
+        for monitor in existing.values() {
+            if let (Some(id), Some(name)) = (monitor.common().id(), monitor.common().name()) {
+                if !names.contains(name) {
+                    client.delete_monitor(*id).await?;
+                    summary.deleted.push(name.clone());
+                }
+            }
+        }
+    }
+
+    Ok(summary)
 }
 
 pub(crate) async fn handle(command: &Option<Command>, config: &Config, cli: &Cli) {
@@ -57,7 +407,7 @@ pub(crate) async fn handle(command: &Option<Command>, config: &Config, cli: &Cli
 
         Some(Command::Get { id }) => connect(config, cli)
             .await
-            .pipe_borrow(|client| join_all(id.into_iter().map(|id| client.get_monitor(*id))))
+            .pipe_borrow(|client| join_all(id.iter().map(|id| client.get_monitor(*id))))
             .await
             .into_iter()
             .collect::<Result<Vec<_>>>()
@@ -66,7 +416,7 @@ pub(crate) async fn handle(command: &Option<Command>, config: &Config, cli: &Cli
 
         Some(Command::Delete { id }) => connect(config, cli)
             .await
-            .pipe_borrow(|client| join_all(id.into_iter().map(|id| client.delete_monitor(*id))))
+            .pipe_borrow(|client| join_all(id.iter().map(|id| client.delete_monitor(*id))))
             .await
             .into_iter()
             .collect::<Result<Vec<_>>>()
@@ -79,24 +429,209 @@ pub(crate) async fn handle(command: &Option<Command>, config: &Config, cli: &Cli
             .await
             .print_result(cli),
 
-        Some(Command::Resume { id }) => connect(config, cli)
+        Some(Command::Resume { id, recursive }) => connect(config, cli)
+            .await
+            .pipe_borrow(|client| {
+                join_all(id.iter().map(|id| match recursive {
+                    true => client.resume_monitor_recursive(*id).boxed(),
+                    false => client.resume_monitor(*id).boxed(),
+                }))
+            })
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>()
+            .map(|result| result.into_iter().collect_or_unwrap())
+            .print_result(cli),
+
+        Some(Command::Clone { id, new_name }) => connect(config, cli)
+            .await
+            .clone_monitor(*id, new_name)
+            .await
+            .print_result(cli),
+
+        Some(Command::Pause { id, recursive }) => connect(config, cli)
             .await
-            .pipe_borrow(|client| join_all(id.into_iter().map(|id| client.resume_monitor(*id))))
+            .pipe_borrow(|client| {
+                join_all(id.iter().map(|id| match recursive {
+                    true => client.pause_monitor_recursive(*id).boxed(),
+                    false => client.pause_monitor(*id).boxed(),
+                }))
+            })
             .await
             .into_iter()
             .collect::<Result<Vec<_>>>()
             .map(|result| result.into_iter().collect_or_unwrap())
             .print_result(cli),
 
-        Some(Command::Pause { id }) => connect(config, cli)
+        Some(Command::Tree {}) => {
+            let tree = connect(config, cli)
+                .await
+                .get_monitors()
+                .await
+                .map(|monitors| build_monitor_tree(&monitors))
+                .unwrap_or_die(cli);
+
+            match cli.output_format {
+                OutputFormat::Table => {
+                    let mut rows = vec![];
+                    for node in &tree {
+                        node.flatten(0, &mut rows);
+                    }
+                    print_value(&rows, cli);
+                }
+                _ => print_value(&tree, cli),
+            }
+        }
+
+        Some(Command::Status { id }) => connect(config, cli)
+            .await
+            .pipe_borrow(|client| get_monitor_status(client, *id))
+            .await
+            .print_result(cli),
+
+        Some(Command::PushUrl { id }) => connect(config, cli)
             .await
-            .pipe_borrow(|client| join_all(id.into_iter().map(|id| client.pause_monitor(*id))))
+            .pipe_borrow(|client| join_all(id.iter().map(|id| client.get_monitor(*id))))
             .await
             .into_iter()
+            .map(|monitor| match monitor? {
+                Monitor::Push { value } => Ok(value.push_url(&config.base_url())?.to_string()),
+                monitor => Err(Error::ValidationError(
+                    monitor.common().id().unwrap_or_default().to_string(),
+                    vec!["Not a push monitor".to_owned()],
+                )),
+            })
             .collect::<Result<Vec<_>>>()
             .map(|result| result.into_iter().collect_or_unwrap())
             .print_result(cli),
 
+        Some(Command::Import {
+            file,
+            delete_missing,
+        }) => connect(config, cli)
+            .await
+            .pipe_borrow(|client| {
+                load_files::<serde_json::Value>(file, cli)
+                    .then(|values| import_monitors(client, values, *delete_missing))
+            })
+            .await
+            .print_result(cli),
+
         None => {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kuma_client::test_util::{fixtures, MockServer};
+
+    async fn import(
+        server: &MockServer,
+        definitions: serde_json::Value,
+        delete_missing: bool,
+    ) -> ImportSummary {
+        let client = Client::connect(server.config()).await.unwrap();
+        import_monitors(&client, vec![definitions], delete_missing)
+            .await
+            .unwrap()
+    }
+
+    async fn calls(server: &MockServer, method: &str) -> Vec<serde_json::Value> {
+        server
+            .calls()
+            .await
+            .into_iter()
+            .filter(|(name, _)| name == method)
+            .map(|(_, args)| args[0].clone())
+            .collect()
+    }
+
+    #[test]
+    fn definitions_use_the_file_source_layout() {
+        let (monitors, errors) = monitors_from_definitions(vec![
+            json!({"type": "http", "name": "Single", "url": "https://example.com"}),
+            json!([
+                {"id": "website", "type": "http", "name": "Website", "url": "https://example.com", "max_retries": 3},
+                {"type": "group", "name": "Group"},
+            ]),
+            json!({
+                "shop": {"type": "http", "name": "Shop", "url": "https://shop.example.com"},
+                "team": {"type": "http", "url": "https://team.example.com"},
+                "discord": {"type": "notification", "name": "Discord"},
+            }),
+        ]);
+
+        assert_eq!(
+            monitors
+                .iter()
+                .map(|monitor| monitor.common().name().clone().unwrap())
+                .collect_vec(),
+            vec!["Single", "Website", "Group", "Shop"]
+        );
+        assert_eq!(*monitors[1].common().id(), None);
+        assert_eq!(*monitors[1].common().max_retries(), Some(3));
+        assert_eq!(
+            errors,
+            vec![
+                "Monitor discord has an unknown type 'notification'",
+                "Monitor team is missing a name",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn import_updates_monitors_matched_by_name() {
+        let server = MockServer::start()
+            .await
+            .with_monitors(fixtures::monitor_list())
+            .await;
+
+        let summary = import(
+            &server,
+            json!([
+                {"id": "website", "type": "http", "name": "Website", "url": "https://example.org"},
+                {"type": "http", "name": "Shop", "url": "https://shop.example.com"},
+                {"type": "http", "url": "https://unnamed.example.com"},
+            ]),
+            false,
+        )
+        .await;
+
+        assert_eq!(summary.updated, vec!["Website"]);
+        assert_eq!(summary.created, vec!["Shop"]);
+        assert_eq!(summary.deleted, Vec::<String>::new());
+        assert_eq!(summary.skipped, vec!["Monitor #2 is missing a name"]);
+
+        let edits = calls(&server, "editMonitor").await;
+        assert!(edits
+            .iter()
+            .any(|monitor| monitor["id"] == 2 && monitor["url"] == "https://example.org"));
+        assert!(edits
+            .iter()
+            .all(|monitor| monitor["url"] != "https://unnamed.example.com"));
+
+        let adds = calls(&server, "add").await;
+        assert_eq!(adds.len(), 1);
+        assert_eq!(adds[0]["name"], "Shop");
+    }
+
+    #[tokio::test]
+    async fn import_deletes_missing_monitors() {
+        let server = MockServer::start()
+            .await
+            .with_monitors(fixtures::monitor_list())
+            .await;
+
+        let summary = import(
+            &server,
+            json!({"type": "http", "name": "Website", "url": "https://example.com"}),
+            true,
+        )
+        .await;
+
+        assert_eq!(summary.updated, vec!["Website"]);
+        assert_eq!(summary.deleted, vec!["Group"]);
+        assert_eq!(calls(&server, "deleteMonitor").await, vec![json!(1)]);
+    }
+}
@@ -4,7 +4,8 @@ use crate::{
 };
 use clap::Subcommand;
 use futures_util::{future::join_all, FutureExt};
-use kuma_client::{error::Result, Config};
+use kuma_client::{error::Result, notification::Notification, Config};
+use serde_json::json;
 use std::path::PathBuf;
 use tap::Pipe;
 
@@ -21,6 +22,10 @@ pub(crate) enum Command {
     Delete { id: Vec<i32> },
     /// Get all Notifications
     List {},
+    /// Import Notifications from provider specific configs
+    Import { file: Vec<PathBuf> },
+    /// Send a test message using a Notification
+    Test { id: Vec<i32> },
 }
 
 pub(crate) async fn handle(command: &Option<Command>, config: &Config, cli: &Cli) {
@@ -83,6 +88,42 @@ pub(crate) async fn handle(command: &Option<Command>, config: &Config, cli: &Cli
             .await
             .print_result(cli),
 
+        Some(Command::Import { file }) => connect(config, cli)
+            .await
+            .pipe_borrow(|client| {
+                load_files::<serde_json::Value>(file, cli).then(move |values| {
+                    join_all(values.into_iter().enumerate().map(|(i, value)| async move {
+                        let notification = Notification::from_provider_config(value);
+                        notification.validate(format!("Notification #{}", i + 1))?;
+                        client.add_notification(notification).await
+                    }))
+                })
+            })
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>()
+            .map(|result| result.into_iter().collect_or_unwrap())
+            .print_result(cli),
+
+        Some(Command::Test { id }) => connect(config, cli)
+            .await
+            .pipe_borrow(|client| {
+                join_all(id.iter().map(|id| async move {
+                    let notification = client.get_notification(*id).await?;
+                    client.test_notification(notification).await
+                }))
+            })
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>()
+            .map(|responses| {
+                responses
+                    .into_iter()
+                    .map(|response| json!({"msg": response}))
+                    .collect_or_unwrap()
+            })
+            .print_result(cli),
+
         None => {}
     }
 }
@@ -4,7 +4,7 @@ use crate::{
 };
 use clap::Subcommand;
 use futures_util::{future::join_all, FutureExt};
-use kuma_client::{error::Result, Config};
+use kuma_client::{error::Result, status_page::logo_data_uri, Config};
 use std::path::PathBuf;
 use tap::Pipe;
 
@@ -21,6 +21,8 @@ pub(crate) enum Command {
     Delete { slug: Vec<String> },
     /// Get all StatusPages
     List {},
+    /// Upload a logo for a StatusPage from a local image file
+    SetLogo { slug: String, file: PathBuf },
 }
 
 pub(crate) async fn handle(command: &Option<Command>, config: &Config, cli: &Cli) {
@@ -83,6 +85,20 @@ pub(crate) async fn handle(command: &Option<Command>, config: &Config, cli: &Cli
             .await
             .print_result(cli),
 
+        Some(Command::SetLogo { slug, file }) => {
+            let client = connect(config, cli).await;
+
+            async {
+                let icon = logo_data_uri(file)?;
+                let mut status_page = client.get_status_page(slug).await?;
+                status_page.icon = Some(icon);
+                client.edit_status_page(status_page).await?;
+                client.get_status_page(slug).await
+            }
+            .await
+            .print_result(cli)
+        }
+
         None => {}
     }
 }
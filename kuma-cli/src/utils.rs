@@ -8,7 +8,7 @@ use inkjet::{
 use kuma_client::Config;
 use owo_colors::Style;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
+use serde_json::{json, Map, Value};
 use std::{collections::HashMap, path::PathBuf};
 use tap::Pipe;
 use tokio::task;
@@ -25,8 +25,14 @@ pub(crate) async fn connect(config: &Config, cli: &Cli) -> kuma_client::Client {
 pub(crate) enum OutputFormat {
     Json,
     Yaml,
+    Table,
 }
 
+/// Columns shown when printing a list of entities as a table, falls back to all scalar fields if none of them exist.
+const TABLE_COLUMNS: &[&str] = &[
+    "id", "tag_id", "slug", "name", "title", "type", "active", "url", "hostname",
+];
+
 pub(crate) trait PrintResult {
     fn print_result(self, cli: &Cli);
 }
@@ -81,11 +87,89 @@ where
             )
             .unwrap(),
         (OutputFormat::Yaml, false) => serde_yaml::to_string(value).unwrap(),
+        (OutputFormat::Table, _) => format_table(&serde_json::to_value(value).unwrap()),
     };
 
     print!("{}", str);
 }
 
+fn format_cell(value: &Value) -> String {
+    match value {
+        Value::Null => "".to_owned(),
+        Value::String(s) => s.to_owned(),
+        value => value.to_string(),
+    }
+}
+
+fn format_rows(rows: Vec<Vec<String>>) -> String {
+    let widths = rows.iter().fold(vec![], |mut widths: Vec<usize>, row| {
+        widths.resize(widths.len().max(row.len()), 0);
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+        widths
+    });
+
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_owned()
+                + "\n"
+        })
+        .collect()
+}
+
+fn format_table(value: &Value) -> String {
+    let entries: Vec<&Map<String, Value>> = match value {
+        Value::Array(items) => items.iter().filter_map(|item| item.as_object()).collect(),
+        Value::Object(map) if !map.is_empty() && map.values().all(|v| v.is_object()) => {
+            map.values().filter_map(|item| item.as_object()).collect()
+        }
+        Value::Object(map) => {
+            return format_rows(
+                map.iter()
+                    .map(|(key, value)| vec![key.to_owned(), format_cell(value)])
+                    .collect(),
+            )
+        }
+        value => return format!("{}\n", format_cell(value)),
+    };
+
+    let mut columns = TABLE_COLUMNS
+        .iter()
+        .map(|column| column.to_string())
+        .filter(|column| entries.iter().any(|entry| entry.contains_key(column)))
+        .collect::<Vec<_>>();
+
+    if columns.is_empty() {
+        for entry in &entries {
+            for (key, value) in entry.iter() {
+                if !value.is_object() && !value.is_array() && !columns.contains(key) {
+                    columns.push(key.to_owned());
+                }
+            }
+        }
+    }
+
+    let header = columns.iter().map(|column| column.to_uppercase()).collect();
+
+    format_rows(
+        std::iter::once(header)
+            .chain(entries.iter().map(|entry| {
+                columns
+                    .iter()
+                    .map(|column| entry.get(column).map(format_cell).unwrap_or_default())
+                    .collect()
+            }))
+            .collect(),
+    )
+}
+
 #[derive(Deserialize, Serialize)]
 #[serde(untagged)]
 pub(crate) enum OneOrMany<T> {
@@ -115,10 +199,22 @@ where
 
     let result = task::spawn_blocking(move || {
         if file_clone.to_string_lossy() == "-" {
-            serde_json::from_reader(std::io::stdin()).unwrap_or_die(&cli_clone)
-        } else {
-            serde_json::from_reader(std::fs::File::open(&file_clone).unwrap_or_die(&cli_clone))
-                .unwrap_or_die(&cli_clone)
+            return serde_json::from_reader(std::io::stdin()).unwrap_or_die(&cli_clone);
+        }
+
+        match file_clone.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => {
+                toml::from_str(&std::fs::read_to_string(&file_clone).unwrap_or_die(&cli_clone))
+                    .unwrap_or_die(&cli_clone)
+            }
+            Some("yaml") | Some("yml") => {
+                serde_yaml::from_reader(std::fs::File::open(&file_clone).unwrap_or_die(&cli_clone))
+                    .unwrap_or_die(&cli_clone)
+            }
+            _ => {
+                serde_json::from_reader(std::fs::File::open(&file_clone).unwrap_or_die(&cli_clone))
+                    .unwrap_or_die(&cli_clone)
+            }
         }
     })
     .await
@@ -2,18 +2,19 @@ use kuma_client::{
     monitor::{MonitorGroup, MonitorHttp},
     notification::Notification,
     tag::{Tag, TagDefinition},
-    Client, Config, Url,
+    Client, Config,
 };
 
 #[tokio::main()]
 async fn main() {
     // Connect to the server
-    let client = Client::connect(Config {
-        url: Url::parse("http://localhost:3001").expect("Invalid URL"),
-        username: Some("Username".to_owned()),
-        password: Some("Password".to_owned()),
-        ..Default::default()
-    })
+    let client = Client::connect(
+        Config::builder()
+            .url("http://localhost:3001")
+            .basic_auth("Username", "Password")
+            .build()
+            .expect("Invalid config"),
+    )
     .await
     .expect("Failed to connect to server");
 
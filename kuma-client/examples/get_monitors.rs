@@ -1,13 +1,14 @@
-use kuma_client::{Client, Config, Url};
+use kuma_client::{Client, Config};
 
 #[tokio::main()]
 async fn main() {
-    let client = Client::connect(Config {
-        url: Url::parse("http://localhost:3001").expect("Invalid URL"),
-        username: Some("Username".to_owned()),
-        password: Some("Password".to_owned()),
-        ..Default::default()
-    })
+    let client = Client::connect(
+        Config::builder()
+            .url("http://localhost:3001")
+            .basic_auth("Username", "Password")
+            .build()
+            .expect("Invalid config"),
+    )
     .await
     .expect("Failed to connect to server");
 
@@ -1,20 +1,24 @@
 use crate::{
+    backup::ImportHandle,
     docker_host::{DockerHost, DockerHostList},
     error::{Error, Result, TotpResult},
     event::Event,
+    heartbeat::{Heartbeat, HeartbeatList, UptimeList},
     maintenance::{Maintenance, MaintenanceList, MaintenanceMonitor, MaintenanceStatusPage},
-    monitor::{Monitor, MonitorList},
+    monitor::{Monitor, MonitorList, MonitorType},
     notification::{Notification, NotificationList},
+    proxy::ProxyList,
     response::LoginResponse,
-    status_page::{PublicGroupList, StatusPage, StatusPageList},
+    status_page::{Incident, PublicGroupList, StatusPage, StatusPageList},
     tag::{Tag, TagDefinition},
     util::ResultLogger,
-    Config,
+    ApiVersion, Config,
 };
-use futures_util::FutureExt;
+use futures_util::{future::BoxFuture, stream, FutureExt, StreamExt};
 use itertools::Itertools;
 use log::{debug, trace, warn};
-use native_tls::{Certificate, TlsConnector};
+use native_tls::{Certificate, Identity, TlsConnector};
+use rand::Rng as _;
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use rust_socketio::{
     asynchronous::{Client as SocketIO, ClientBuilder},
@@ -22,6 +26,7 @@ use rust_socketio::{
 };
 use serde::de::DeserializeOwned;
 use serde_json::{json, Value};
+use serde_path_to_error::Segment;
 use std::{
     collections::HashMap,
     fs, mem,
@@ -30,9 +35,83 @@ use std::{
     time::Duration,
 };
 use tap::prelude::*;
-use tokio::{runtime::Handle, sync::Mutex};
+use tokio::{
+    runtime::Handle,
+    sync::{Mutex, Notify},
+};
 use totp_rs::{Rfc6238, TOTP};
 
+/// Minimum timeout in seconds for calls which are known to take a long time on the server side.
+const LONG_CALL_TIMEOUT: f64 = 300.0;
+
+/// Interval in milliseconds for checking whether Uptime Kuma is ready after connecting.
+const READY_POLL_INTERVAL: u64 = 200;
+
+/// The first Uptime Kuma version sending a docker host list.
+const DOCKER_HOST_LIST_VERSION: (u32, u32) = (1, 18);
+
+/// Monitor properties which were added in Uptime Kuma 2.0 and are rejected by older servers.
+const V2_MONITOR_FIELDS: &[&str] = &["conditions", "cacheBust", "jsonPathOperator", "ipFamily"];
+
+/// Monitor types with a `url` property which need the workaround for https://github.com/BigBoot/AutoKuma/issues/72.
+const URL_WORKAROUND_TYPES: &[MonitorType] = &[
+    MonitorType::Http,
+    MonitorType::Keyword,
+    MonitorType::JsonQuery,
+    MonitorType::RealBrowser,
+];
+
+/// The first Uptime Kuma version which no longer needs the url workaround, `None` while the issue is unfixed.
+const URL_WORKAROUND_FIXED_VERSION: Option<(u32, u32)> = None;
+
+const PEM_CERT_BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+const PEM_CERT_END: &str = "-----END CERTIFICATE-----";
+
+/// Parse all certificates of a PEM file, allowing CA bundles consisting of multiple concatenated certificates.
+fn parse_pem_bundle(file: &str, content: &str) -> Result<Vec<Certificate>> {
+    let blocks = content
+        .match_indices(PEM_CERT_BEGIN)
+        .map(|(start, _)| {
+            let end = content[start..]
+                .find(PEM_CERT_END)
+                .map_or(content.len(), |end| start + end + PEM_CERT_END.len());
+            &content[start..end]
+        })
+        .collect_vec();
+
+    if blocks.is_empty() {
+        return Err(Error::InvalidTlsCert(
+            file.to_owned(),
+            "No certificate found".to_owned(),
+        ));
+    }
+
+    blocks
+        .into_iter()
+        .enumerate()
+        .map(|(index, block)| {
+            Certificate::from_pem(block.as_bytes()).map_err(|e| {
+                Error::InvalidTlsCert(
+                    file.to_owned(),
+                    format!("Certificate #{}: {}", index + 1, e),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Maximum number of tag calls sent concurrently when updating the tags of a monitor.
+const MAX_CONCURRENT_TAG_CALLS: usize = 8;
+
+/// Maximum number of heartbeats cached per monitor, matching the Uptime Kuma UI.
+const MAX_CACHED_HEARTBEATS: usize = 100;
+
+/// Delay in seconds before the first retry of a failed call, doubled for every following retry.
+const CALL_RETRY_DELAY: f64 = 0.5;
+
+/// Length of the push tokens generated by Uptime Kuma.
+const PUSH_TOKEN_LENGTH: usize = 32;
+
 struct Ready {
     pub monitor_list: bool,
     pub notification_list: bool,
@@ -56,15 +135,28 @@ impl Ready {
         *self = Ready::new()
     }
 
-    pub fn is_ready(&self) -> bool {
-        self.monitor_list
+    /// Whether all lists sent by the given server version have been received, if the version is unknown all lists are required.
+    pub fn is_ready(&self, server_version: Option<&str>, lazy_monitors: bool) -> bool {
+        let has_docker_host_list = server_version
+            .and_then(parse_version)
+            .map_or(true, |version| version >= DOCKER_HOST_LIST_VERSION);
+
+        (self.monitor_list || lazy_monitors)
             && self.notification_list
             && self.maintenance_list
             && self.status_page_list
-            && self.docker_host_list
+            && (self.docker_host_list || !has_docker_host_list)
     }
 }
 
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.trim_start_matches('v').split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+
+    Some((major, minor))
+}
+
 struct Worker {
     config: Arc<Config>,
     #[allow(dead_code)]
@@ -74,49 +166,54 @@ struct Worker {
     docker_hosts: Arc<Mutex<DockerHostList>>,
     maintenances: Arc<Mutex<MaintenanceList>>,
     status_pages: Arc<Mutex<StatusPageList>>,
+    proxies: Arc<Mutex<ProxyList>>,
+    heartbeats: Arc<Mutex<HeartbeatList>>,
+    uptimes: Arc<Mutex<UptimeList>>,
     is_connected: Arc<Mutex<bool>>,
     is_ready: Arc<Mutex<Ready>>,
+    ready_notify: Arc<Notify>,
     is_logged_in: Arc<Mutex<bool>>,
+    token_required: Arc<Mutex<bool>>,
+    auth_token: Arc<Mutex<Option<String>>>,
+    server_version: Arc<Mutex<Option<String>>>,
     reqwest: Arc<Mutex<reqwest::Client>>,
-    custom_cert: Option<(String, Certificate)>,
+    custom_cert: Option<(String, Vec<Certificate>)>,
+    client_identity: Option<(String, Identity)>,
 }
 
 impl Worker {
     fn new(config: Config) -> Result<Arc<Self>> {
+        config.validate()?;
+        let config = config.resolve_secrets()?;
+
         let custom_cert = config
             .tls
             .cert
             .as_ref()
-            .map(|file| -> Result<(String, Certificate)> {
-                fs::read(file)
+            .map(|file| -> Result<(String, Vec<Certificate>)> {
+                fs::read_to_string(file)
                     .map_err(|e| Error::InvalidTlsCert(file.clone(), e.to_string()))
-                    .and_then(|content| {
-                        Certificate::from_pem(&content)
-                            .map_err(|e| Error::InvalidTlsCert(file.clone(), e.to_string()))
-                    })
-                    .map(|cert| (file.clone(), cert))
+                    .and_then(|content| parse_pem_bundle(file, &content))
+                    .map(|certs| (file.clone(), certs))
             })
             .transpose()?;
 
         let mut reqwest_builder = reqwest::Client::builder()
             .danger_accept_invalid_certs(!config.tls.verify)
-            .default_headers(HeaderMap::from_iter(
-                config
-                    .headers
-                    .iter()
-                    .filter_map(|header| header.split_once("="))
-                    .filter_map(|(key, value)| {
-                        match (
-                            HeaderName::from_bytes(key.as_bytes()),
-                            HeaderValue::from_bytes(value.as_bytes()),
-                        ) {
-                            (Ok(key), Ok(value)) => Some((key, value)),
-                            _ => None,
-                        }
-                    }),
-            ));
+            .default_headers(HeaderMap::from_iter(config.headers.iter().filter_map(
+                |(key, value)| match (
+                    HeaderName::from_bytes(key.as_bytes()),
+                    HeaderValue::from_bytes(value.as_bytes()),
+                ) {
+                    (Ok(key), Ok(value)) => Some((key, value)),
+                    _ => None,
+                },
+            )));
 
-        if let Some((file, cert)) = &custom_cert {
+        for (file, cert) in custom_cert
+            .iter()
+            .flat_map(|(file, certs)| certs.iter().map(move |cert| (file, cert)))
+        {
             reqwest_builder = reqwest_builder.add_root_certificate(
                 reqwest::Certificate::from_der(
                     &cert
@@ -127,6 +224,28 @@ impl Worker {
             );
         }
 
+        let client_identity = match (&config.tls.client_cert, &config.tls.client_key) {
+            (Some(cert_file), Some(key_file)) => {
+                let read = |file: &String| {
+                    fs::read(file).map_err(|e| Error::InvalidTlsCert(file.clone(), e.to_string()))
+                };
+                let (cert, key) = (read(cert_file)?, read(key_file)?);
+
+                reqwest_builder =
+                    reqwest_builder
+                        .identity(reqwest::Identity::from_pkcs8_pem(&cert, &key).map_err(|e| {
+                            Error::InvalidTlsCert(cert_file.clone(), e.to_string())
+                        })?);
+
+                Some((
+                    cert_file.clone(),
+                    Identity::from_pkcs8(&cert, &key)
+                        .map_err(|e| Error::InvalidTlsCert(cert_file.clone(), e.to_string()))?,
+                ))
+            }
+            _ => None,
+        };
+
         Ok(Arc::new(Worker {
             config: Arc::new(config.clone()),
             socket_io: Arc::new(Mutex::new(None)),
@@ -135,11 +254,19 @@ impl Worker {
             maintenances: Default::default(),
             status_pages: Default::default(),
             docker_hosts: Default::default(),
+            proxies: Default::default(),
+            heartbeats: Default::default(),
+            uptimes: Default::default(),
             is_connected: Arc::new(Mutex::new(false)),
             is_ready: Arc::new(Mutex::new(Ready::new())),
+            ready_notify: Arc::new(Notify::new()),
             is_logged_in: Arc::new(Mutex::new(false)),
+            token_required: Arc::new(Mutex::new(false)),
+            auth_token: Arc::new(Mutex::new(config.auth_token.clone())),
+            server_version: Arc::new(Mutex::new(None)),
             reqwest: Arc::new(Mutex::new(reqwest_builder.build().unwrap())),
             custom_cert: custom_cert,
+            client_identity,
         }))
     }
 
@@ -154,13 +281,14 @@ impl Worker {
                 };
                 Some(totp.generate_current()?)
             }
-            None => self.config.mfa_secret.clone(),
+            None => self.config.mfa_token.clone(),
         })
     }
 
     async fn on_monitor_list(self: &Arc<Self>, monitor_list: MonitorList) -> Result<()> {
         *self.monitors.lock().await = monitor_list;
         self.is_ready.lock().await.monitor_list = true;
+        self.notify_if_ready().await;
 
         Ok(())
     }
@@ -171,6 +299,7 @@ impl Worker {
     ) -> Result<()> {
         *self.notifications.lock().await = notification_list;
         self.is_ready.lock().await.notification_list = true;
+        self.notify_if_ready().await;
 
         Ok(())
     }
@@ -181,6 +310,7 @@ impl Worker {
     ) -> Result<()> {
         *self.maintenances.lock().await = maintenance_list;
         self.is_ready.lock().await.maintenance_list = true;
+        self.notify_if_ready().await;
 
         Ok(())
     }
@@ -188,6 +318,7 @@ impl Worker {
     async fn on_status_page_list(self: &Arc<Self>, status_page_list: StatusPageList) -> Result<()> {
         *self.status_pages.lock().await = status_page_list;
         self.is_ready.lock().await.status_page_list = true;
+        self.notify_if_ready().await;
 
         Ok(())
     }
@@ -195,13 +326,95 @@ impl Worker {
     async fn on_docker_host_list(self: &Arc<Self>, docker_host_list: DockerHostList) -> Result<()> {
         *self.docker_hosts.lock().await = docker_host_list;
         self.is_ready.lock().await.docker_host_list = true;
+        self.notify_if_ready().await;
+
+        Ok(())
+    }
+
+    async fn on_proxy_list(self: &Arc<Self>, proxy_list: ProxyList) -> Result<()> {
+        *self.proxies.lock().await = proxy_list;
+        self.notify_if_ready().await;
 
         Ok(())
     }
 
-    async fn on_info(self: &Arc<Self>) -> Result<()> {
+    async fn on_heartbeat_list(
+        self: &Arc<Self>,
+        monitor_id: i32,
+        heartbeat_list: Vec<Heartbeat>,
+        overwrite: bool,
+    ) -> Result<()> {
+        let mut heartbeats = self.heartbeats.lock().await;
+        let entry = heartbeats.entry(monitor_id).or_default();
+
+        if overwrite {
+            entry.clear();
+        }
+
+        entry.extend(heartbeat_list);
+        entry.drain(..entry.len().saturating_sub(MAX_CACHED_HEARTBEATS));
+
+        Ok(())
+    }
+
+    async fn on_heartbeat(self: &Arc<Self>, heartbeat: Heartbeat) -> Result<()> {
+        if let Some(monitor_id) = heartbeat.monitor_id {
+            self.on_heartbeat_list(monitor_id, vec![heartbeat], false)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn on_avg_ping(self: &Arc<Self>, monitor_id: i32, avg_ping: Option<f64>) -> Result<()> {
+        self.uptimes
+            .lock()
+            .await
+            .entry(monitor_id)
+            .or_default()
+            .avg_ping = avg_ping;
+
+        Ok(())
+    }
+
+    async fn on_uptime(
+        self: &Arc<Self>,
+        monitor_id: i32,
+        period: Value,
+        uptime: f64,
+    ) -> Result<()> {
+        let mut uptimes = self.uptimes.lock().await;
+        let entry = uptimes.entry(monitor_id).or_default();
+
+        // Uptime Kuma 1.x sends the period in hours as a number, 2.x as a string
+        match period.to_string().trim_matches('"') {
+            "24" => entry.uptime_24h = Some(uptime),
+            "720" => entry.uptime_30d = Some(uptime),
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    async fn on_info(self: &Arc<Self>, info: Value) -> Result<()> {
         *self.is_connected.lock().await = true;
+        if let Some(version) = info.get("version").and_then(|version| version.as_str()) {
+            *self.server_version.lock().await = Some(version.to_owned());
+            self.notify_if_ready().await;
+        }
+
         let logged_in = *self.is_logged_in.lock().await;
+        let auth_token = self.auth_token.lock().await.clone();
+        if let (Some(auth_token), false) = (auth_token, logged_in) {
+            match self.login_by_token(auth_token).await {
+                Ok(()) => return Ok(()),
+                Err(e) if self.config.username.is_none() || self.config.password.is_none() => {
+                    return Err(e)
+                }
+                Err(_) => debug!("Token login failed, falling back to username/password"),
+            }
+        }
+
         if let (Some(username), Some(password), true) =
             (&self.config.username, &self.config.password, !logged_in)
         {
@@ -218,29 +431,60 @@ impl Worker {
         Ok(())
     }
 
-    async fn on_event(self: &Arc<Self>, event: Event, payload: Value) -> Result<()> {
+    async fn on_event(self: &Arc<Self>, event: Event, params: Vec<Value>) -> Result<()> {
+        let param = |index: usize| params.get(index).cloned().unwrap_or_default();
+        let payload = param(0);
+
         match event {
             Event::MonitorList => {
-                self.on_monitor_list(serde_json::from_value(payload).unwrap())
+                self.on_monitor_list(Self::deserialize_event(&event, payload)?)
                     .await?
             }
             Event::NotificationList => {
-                self.on_notification_list(serde_json::from_value(payload).unwrap())
+                self.on_notification_list(Self::deserialize_event(&event, payload)?)
                     .await?
             }
             Event::MaintenanceList => {
-                self.on_maintenance_list(serde_json::from_value(payload).unwrap())
+                self.on_maintenance_list(Self::deserialize_event(&event, payload)?)
                     .await?
             }
             Event::StatusPageList => {
-                self.on_status_page_list(serde_json::from_value(payload).unwrap())
+                self.on_status_page_list(Self::deserialize_event(&event, payload)?)
                     .await?
             }
             Event::DockerHostList => {
-                self.on_docker_host_list(serde_json::from_value(payload).unwrap())
+                self.on_docker_host_list(Self::deserialize_event(&event, payload)?)
+                    .await?
+            }
+            Event::ProxyList => {
+                self.on_proxy_list(Self::deserialize_event(&event, payload)?)
                     .await?
             }
-            Event::Info => self.on_info().await?,
+            Event::HeartbeatList => {
+                self.on_heartbeat_list(
+                    Self::deserialize_event(&event, payload)?,
+                    Self::deserialize_event(&event, param(1))?,
+                    param(2).as_bool().unwrap_or(false),
+                )
+                .await?
+            }
+            Event::Heartbeat => {
+                self.on_heartbeat(Self::deserialize_event(&event, payload)?)
+                    .await?
+            }
+            Event::AvgPing => {
+                self.on_avg_ping(Self::deserialize_event(&event, payload)?, param(1).as_f64())
+                    .await?
+            }
+            Event::Uptime => {
+                self.on_uptime(
+                    Self::deserialize_event(&event, payload)?,
+                    param(1),
+                    Self::deserialize_event(&event, param(2))?,
+                )
+                .await?
+            }
+            Event::Info => self.on_info(payload).await?,
             Event::AutoLogin => self.on_auto_login().await?,
             _ => {}
         }
@@ -248,7 +492,36 @@ impl Worker {
         Ok(())
     }
 
+    fn deserialize_event<T: DeserializeOwned>(event: &Event, payload: Value) -> Result<T> {
+        Self::deserialize_value(format!("{:?}", event), "", payload)
+    }
+
+    /// Deserializes `value`, errors contain the JSON pointer of the failing field prefixed with `pointer`.
+    fn deserialize_value<T: DeserializeOwned>(
+        method: impl AsRef<str>,
+        pointer: impl AsRef<str>,
+        value: Value,
+    ) -> Result<T> {
+        serde_path_to_error::deserialize(value).map_err(|e| Error::DeserializeResponse {
+            method: method.as_ref().to_owned(),
+            pointer: e
+                .path()
+                .iter()
+                .map(|segment| match segment {
+                    Segment::Seq { index } => index.to_string(),
+                    Segment::Map { key } => key.replace('~', "~0").replace('/', "~1"),
+                    Segment::Enum { variant } => variant.to_owned(),
+                    Segment::Unknown => "?".to_owned(),
+                })
+                .fold(pointer.as_ref().to_owned(), |pointer, segment| {
+                    format!("{}/{}", pointer, segment)
+                }),
+            source: e.into_inner(),
+        })
+    }
+
     fn extract_response<T: DeserializeOwned>(
+        method: impl AsRef<str>,
         response: Vec<Value>,
         result_ptr: impl AsRef<str>,
         verify: bool,
@@ -264,18 +537,23 @@ impl Worker {
                 .as_bool()
                 .unwrap_or_default()
         {
-            let error_msg = json
-                .pointer("/0/0/msg")
-                .unwrap_or_else(|| &json!(null))
-                .as_str()
-                .unwrap_or_else(|| "Unknown error");
-
-            return Err(Error::ServerError(error_msg.to_owned()));
+            let raw = json.pointer("/0/0").cloned().unwrap_or_default();
+            let msg = raw
+                .get("msg")
+                .and_then(|msg| msg.as_str())
+                .unwrap_or("Unknown error")
+                .to_owned();
+
+            return Err(Error::ServerError { msg, raw });
         }
 
-        json.pointer(&format!("/0/0{}", result_ptr.as_ref()))
-            .and_then(|value| serde_json::from_value(value.to_owned()).ok())
-            .ok_or_else(|| Error::InvalidResponse(response, result_ptr.as_ref().to_owned()))
+        let value = json
+            .pointer(&format!("/0/0{}", result_ptr.as_ref()))
+            .ok_or_else(|| {
+                Error::InvalidResponse(response.clone(), result_ptr.as_ref().to_owned())
+            })?;
+
+        Self::deserialize_value(method, result_ptr, value.to_owned())
     }
 
     async fn call<A, T>(
@@ -285,13 +563,66 @@ impl Worker {
         result_ptr: impl Into<String>,
         verify: bool,
     ) -> Result<T>
+    where
+        A: IntoIterator<Item = Value> + Send + Clone,
+        T: DeserializeOwned + Send + 'static,
+    {
+        self.call_with_timeout(method, args, result_ptr, verify, self.config.call_timeout)
+            .await
+    }
+
+    async fn call_with_timeout<A, T>(
+        self: &Arc<Self>,
+        method: impl Into<String>,
+        args: A,
+        result_ptr: impl Into<String>,
+        verify: bool,
+        timeout: f64,
+    ) -> Result<T>
     where
         A: IntoIterator<Item = Value> + Send + Clone,
         T: DeserializeOwned + Send + 'static,
     {
         let method = method.into();
         let result_ptr: String = result_ptr.into();
+        let mut retries = 0;
+
+        loop {
+            let result = self
+                .call_once(
+                    method.clone(),
+                    args.clone(),
+                    result_ptr.clone(),
+                    verify,
+                    timeout,
+                )
+                .await;
+
+            match result {
+                Err(e) if retries < self.config.call_max_retries && e.is_retryable(&method) => {
+                    let delay = CALL_RETRY_DELAY * 2_f64.powi(retries as i32);
+                    warn!("Call to '{}' failed, retrying in {}s: {}", method, delay, e);
+
+                    tokio::time::sleep(Duration::from_secs_f64(delay)).await;
+                    retries += 1;
+                }
+                result => return result,
+            }
+        }
+    }
 
+    async fn call_once<A, T>(
+        self: &Arc<Self>,
+        method: String,
+        args: A,
+        result_ptr: String,
+        verify: bool,
+        timeout: f64,
+    ) -> Result<T>
+    where
+        A: IntoIterator<Item = Value> + Send + Clone,
+        T: DeserializeOwned + Send + 'static,
+    {
         let method_ref = method.clone();
         let args: A = args.clone();
         let result_ptr = result_ptr.clone();
@@ -307,16 +638,19 @@ impl Worker {
             .emit_with_ack(
                 method.clone(),
                 Payload::Text(args.into_iter().collect_vec()),
-                Duration::from_secs_f64(self.config.call_timeout),
+                Duration::from_secs_f64(timeout),
                 move |message: Payload, _: SocketIO| {
                     debug!("call {} -> {:?}", method_ref, &message);
                     let tx = tx.clone();
+                    let method_ref = method_ref.clone();
                     let result_ptr = result_ptr.clone();
                     async move {
                         _ = match message {
                             Payload::Text(response) => {
-                                tx.send(Self::extract_response(response, result_ptr, verify))
-                                    .await
+                                tx.send(Self::extract_response(
+                                    method_ref, response, result_ptr, verify,
+                                ))
+                                .await
                             }
                             _ => tx.send(Err(Error::UnsupportedResponse)).await,
                         }
@@ -327,15 +661,18 @@ impl Worker {
             .await
             .map_err(|e| Error::CommunicationError(e.to_string()))?;
 
-        let result =
-            tokio::time::timeout(Duration::from_secs_f64(self.config.call_timeout), rx.recv())
-                .await
-                .map_err(|_| Error::CallTimeout(method.clone()))?
-                .ok_or_else(|| Error::CallTimeout(method))?;
+        let result = tokio::time::timeout(Duration::from_secs_f64(timeout), rx.recv())
+            .await
+            .map_err(|_| Error::CallTimeout(method.clone()))?
+            .ok_or_else(|| Error::CallTimeout(method))?;
 
         result
     }
 
+    fn long_call_timeout(self: &Arc<Self>) -> f64 {
+        self.config.call_timeout.max(LONG_CALL_TIMEOUT)
+    }
+
     pub async fn login(
         self: &Arc<Self>,
         username: impl AsRef<str>,
@@ -357,9 +694,17 @@ impl Worker {
             .await;
 
         match result {
-            Ok(LoginResponse::TokenRequired { .. }) => Err(Error::TokenRequired),
-            Ok(LoginResponse::Normal { ok: true, .. }) => {
+            Ok(LoginResponse::TokenRequired { .. }) => {
+                *self.token_required.lock().await = true;
+                Err(Error::TokenRequired)
+            }
+            Ok(LoginResponse::Normal {
+                ok: true, token, ..
+            }) => {
                 debug!("Logged in as {}!", username.as_ref());
+                if token.is_some() {
+                    *self.auth_token.lock().await = token;
+                }
                 *self.is_logged_in.lock().await = true;
                 Ok(())
             }
@@ -380,6 +725,28 @@ impl Worker {
         .log_warn(std::module_path!(), |e| e.to_string())
     }
 
+    pub async fn login_by_token(self: &Arc<Self>, auth_token: impl AsRef<str>) -> Result<()> {
+        let result: Result<LoginResponse> = self
+            .call("loginByToken", vec![json!(auth_token.as_ref())], "", false)
+            .await;
+
+        match result {
+            Ok(LoginResponse::Normal { ok: true, .. }) => {
+                debug!("Logged in using token!");
+                *self.is_logged_in.lock().await = true;
+                Ok(())
+            }
+            Ok(LoginResponse::Normal {
+                ok: false,
+                msg: Some(msg),
+                ..
+            }) => Err(Error::LoginError(msg)),
+            Err(e) => Err(e),
+            _ => Err(Error::LoginError("Unexpect login response".to_owned())),
+        }
+        .log_warn(std::module_path!(), |e| e.to_string())
+    }
+
     async fn get_tags(self: &Arc<Self>) -> Result<Vec<TagDefinition>> {
         self.call("getTags", vec![], "/tags", true).await
     }
@@ -444,6 +811,28 @@ impl Worker {
         Ok(())
     }
 
+    pub async fn test_notification(
+        self: &Arc<Self>,
+        notification: &Notification,
+    ) -> Result<String> {
+        let json = serde_json::to_value(notification.clone()).unwrap();
+        let config_json = serde_json::to_value(notification.config.clone()).unwrap();
+
+        let merge = serde_merge::omerge(config_json, &json).unwrap();
+
+        let msg: String = self
+            .call_with_timeout(
+                "testNotification",
+                vec![merge],
+                "/msg",
+                true,
+                self.long_call_timeout(),
+            )
+            .await?;
+
+        Ok(msg)
+    }
+
     pub async fn delete_notification(self: &Arc<Self>, notification_id: i32) -> Result<()> {
         let _: bool = self
             .call(
@@ -531,13 +920,25 @@ impl Worker {
         Ok(())
     }
 
-    async fn update_monitor_tags(self: &Arc<Self>, monitor_id: i32, tags: &Vec<Tag>) -> Result<()> {
+    async fn update_monitor_tags(self: &Arc<Self>, monitor_id: i32, tags: &[Tag]) -> Result<()> {
         let new_tags = tags
             .iter()
             .filter_map(|tag| tag.tag_id.and_then(|id| Some((id, tag))))
             .collect::<HashMap<_, _>>();
 
-        if let Some(monitor) = self.monitors.lock().await.get(&monitor_id.to_string()) {
+        let current = self
+            .monitors
+            .lock()
+            .await
+            .get(&monitor_id.to_string())
+            .cloned();
+
+        let current = match current {
+            None if self.config.lazy_monitors => self.get_monitor(monitor_id).await.ok(),
+            current => current,
+        };
+
+        if let Some(monitor) = current {
             let current_tags = monitor
                 .common()
                 .tags()
@@ -571,40 +972,98 @@ impl Worker {
                 })
                 .collect_vec();
 
-            for (tag_id, tag) in duplicates {
-                self.delete_monitor_tag(monitor_id, *tag_id, tag.value.clone())
-                    .await?;
-            }
+            // Duplicates and removed tags are deleted first, so the remaining tags can be added/updated without conflicts
+            Self::run_tag_operations(
+                monitor_id,
+                duplicates
+                    .into_iter()
+                    .chain(to_delete.into_iter().map(|(tag_id, tag)| (tag_id, *tag)))
+                    .map(|(tag_id, tag)| {
+                        (
+                            format!("delete tag {}", tag_id),
+                            self.delete_monitor_tag(monitor_id, *tag_id, tag.value.clone())
+                                .boxed(),
+                        )
+                    })
+                    .collect(),
+            )
+            .await?;
 
-            for (tag_id, tag) in to_delete {
-                self.delete_monitor_tag(monitor_id, *tag_id, tag.value.clone())
-                    .await?;
-            }
+            Self::run_tag_operations(
+                monitor_id,
+                to_create
+                    .into_iter()
+                    .map(|(tag_id, tag)| {
+                        (
+                            format!("add tag {}", tag_id),
+                            self.add_monitor_tag(monitor_id, *tag_id, tag.value.clone())
+                                .boxed(),
+                        )
+                    })
+                    .chain(
+                        to_update
+                            .into_iter()
+                            .filter(|(_, current, new)| current.value != new.value)
+                            .map(|(tag_id, _, new)| {
+                                (
+                                    format!("update tag {}", tag_id),
+                                    self.edit_monitor_tag(monitor_id, *tag_id, new.value.clone())
+                                        .boxed(),
+                                )
+                            }),
+                    )
+                    .collect(),
+            )
+            .await?;
+        } else {
+            Self::run_tag_operations(
+                monitor_id,
+                tags.iter()
+                    .filter_map(|tag| tag.tag_id.map(|tag_id| (tag_id, tag)))
+                    .map(|(tag_id, tag)| {
+                        (
+                            format!("add tag {}", tag_id),
+                            self.add_monitor_tag(monitor_id, tag_id, tag.value.clone())
+                                .boxed(),
+                        )
+                    })
+                    .collect(),
+            )
+            .await?;
+        }
 
-            for (tag_id, tag) in to_create {
-                self.add_monitor_tag(monitor_id, *tag_id, tag.value.clone())
-                    .await?
-            }
+        Ok(())
+    }
 
-            for (tag_id, current, new) in to_update {
-                if current.value != new.value {
-                    self.edit_monitor_tag(monitor_id, *tag_id, new.value.clone())
-                        .await?;
-                }
-            }
-        } else {
-            for tag in tags {
-                if let Some(tag_id) = tag.tag_id {
-                    self.add_monitor_tag(monitor_id, tag_id, tag.value.clone())
-                        .await?;
-                }
+    /// Runs independent tag operations of a monitor concurrently, every failed operation is logged and the first error is returned.
+    async fn run_tag_operations(
+        monitor_id: i32,
+        operations: Vec<(String, BoxFuture<'_, Result<()>>)>,
+    ) -> Result<()> {
+        let results = stream::iter(
+            operations
+                .into_iter()
+                .map(|(description, operation)| async move { (description, operation.await) }),
+        )
+        .buffer_unordered(MAX_CONCURRENT_TAG_CALLS)
+        .collect::<Vec<_>>()
+        .await;
+
+        let mut first_error = None;
+        for (description, result) in results {
+            if let Err(e) = result {
+                warn!("Unable to {} of monitor {}: {}", description, monitor_id, e);
+                first_error.get_or_insert(e);
             }
         }
 
-        Ok(())
+        first_error.map_or(Ok(()), Err)
     }
 
     pub async fn add_monitor(self: &Arc<Self>, monitor: &mut Monitor) -> Result<()> {
+        self.check_monitor_type(monitor).await?;
+        self.check_docker_host(monitor).await;
+
         let tags = mem::take(monitor.common_mut().tags_mut());
         let notifications = mem::take(monitor.common_mut().notification_id_list_mut());
 
@@ -628,7 +1087,7 @@ impl Worker {
             .clone()
             .call(
                 "add",
-                vec![serde_json::to_value(&monitor).unwrap()],
+                vec![self.monitor_to_value(monitor).await],
                 "/monitorID",
                 true,
             )
@@ -676,23 +1135,53 @@ impl Worker {
         )
         .await
         .map_err(|e| match e {
-            Error::ServerError(msg) if msg.contains("Cannot read properties of null") => {
-                Error::IdNotFound("Monitor".to_owned(), monitor_id)
-            }
+            e if e.is_not_found() => Error::IdNotFound("Monitor".to_owned(), monitor_id),
             _ => e,
         })
     }
 
+    pub async fn clone_monitor(
+        self: &Arc<Self>,
+        monitor_id: i32,
+        new_name: impl AsRef<str>,
+    ) -> Result<Monitor> {
+        let mut monitor = self.get_monitor(monitor_id).await?;
+
+        *monitor.common_mut().id_mut() = None;
+        *monitor.common_mut().name_mut() = Some(new_name.as_ref().to_owned());
+
+        // Push tokens have to be unique, generate a new one like Uptime Kuma does
+        if let Monitor::Push { value } = &mut monitor {
+            value.push_token = Some(
+                rand::thread_rng()
+                    .sample_iter(&rand::distributions::Alphanumeric)
+                    .take(PUSH_TOKEN_LENGTH)
+                    .map(char::from)
+                    .collect(),
+            );
+        }
+
+        self.add_monitor(&mut monitor).await?;
+
+        Ok(monitor)
+    }
+
     pub async fn edit_monitor(self: &Arc<Self>, monitor: &mut Monitor) -> Result<()> {
+        self.check_monitor_type(monitor).await?;
+        self.check_docker_host(monitor).await;
+
         let tags = mem::take(monitor.common_mut().tags_mut());
 
         #[cfg(feature = "private-api")]
         let create_paused = mem::take(monitor.common_mut().create_paused_mut());
 
-        let mut monitor_json = serde_json::to_value(&monitor).unwrap();
+        let mut monitor_json = self.monitor_to_value(monitor).await;
 
         // Workaround for https://github.com/BigBoot/AutoKuma/issues/72 until fixed in UptimeKuma
-        if let Some(monitor_json) = monitor_json.as_object_mut() {
+        if let (true, Some(monitor_json)) = (
+            self.needs_url_workaround(monitor).await,
+            monitor_json.as_object_mut(),
+        ) {
             if !monitor_json.contains_key("url") {
                 monitor_json.insert("url".to_owned(), json!("https://"));
             }
@@ -740,6 +1229,45 @@ impl Worker {
         Ok(())
     }
 
+    /// Returns the id of a monitor followed by the ids of all its descendants, walking the `parent` of each monitor.
+    async fn monitor_with_descendants(self: &Arc<Self>, monitor_id: i32) -> Result<Vec<i32>> {
+        let monitors = self.get_monitors().await?;
+
+        let mut ids = vec![monitor_id];
+        let mut next = 0;
+
+        while let Some(parent) = ids.get(next).copied() {
+            let children = monitors
+                .values()
+                .filter(|monitor| *monitor.common().parent() == Some(parent))
+                .filter_map(|monitor| *monitor.common().id())
+                .filter(|id| !ids.contains(id))
+                .sorted()
+                .collect_vec();
+
+            ids.extend(children);
+            next += 1;
+        }
+
+        Ok(ids)
+    }
+
+    pub async fn pause_monitor_recursive(self: &Arc<Self>, monitor_id: i32) -> Result<()> {
+        for id in self.monitor_with_descendants(monitor_id).await? {
+            self.pause_monitor(id).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn resume_monitor_recursive(self: &Arc<Self>, monitor_id: i32) -> Result<()> {
+        for id in self.monitor_with_descendants(monitor_id).await? {
+            self.resume_monitor(id).await?;
+        }
+
+        Ok(())
+    }
+
     async fn get_maintenance_monitors(
         self: &Arc<Self>,
         maintenance_id: i32,
@@ -850,7 +1378,7 @@ impl Worker {
             )
             .await
             .map_err(|e| match e {
-                Error::ServerError(msg) if msg.contains("Cannot read properties of null") => {
+                e if e.is_not_found() => {
                     Error::IdNotFound("Maintenance".to_owned(), maintenance_id)
                 }
                 _ => e,
@@ -867,7 +1395,7 @@ impl Worker {
     pub async fn edit_maintenance(self: &Arc<Self>, maintenance: &mut Maintenance) -> Result<()> {
         let id = self
             .call(
-                "addMaintenance",
+                "editMaintenance",
                 vec![serde_json::to_value(maintenance.clone()).unwrap()],
                 "/maintenanceID",
                 true,
@@ -918,8 +1446,8 @@ impl Worker {
             .await
             .get(
                 self.config
-                    .url
-                    .join(&format!("/api/status-page/{}", slug))
+                    .base_url()
+                    .join(&format!("api/status-page/{}", slug))
                     .map_err(|e| Error::InvalidUrl(e.to_string()))?,
             )
             .send()
@@ -949,6 +1477,8 @@ impl Worker {
     }
 
     pub async fn add_status_page(self: &Arc<Self>, status_page: &mut StatusPage) -> Result<()> {
+        status_page.validate(status_page.validation_id())?;
+
         let ok: bool = self
             .call(
                 "addStatusPage",
@@ -962,7 +1492,10 @@ impl Worker {
             .await?;
 
         if !ok {
-            return Err(Error::ServerError("Unable to add status page".to_owned()));
+            return Err(Error::ServerError {
+                msg: "Unable to add status page".to_owned(),
+                raw: Value::Null,
+            });
         }
 
         self.edit_status_page(status_page).await?;
@@ -970,6 +1503,20 @@ impl Worker {
         Ok(())
     }
 
+    pub async fn get_monitor_beats(
+        self: &Arc<Self>,
+        monitor_id: i32,
+        period_hours: u32,
+    ) -> Result<Vec<Heartbeat>> {
+        self.call(
+            "getMonitorBeats",
+            vec![json!(monitor_id), json!(period_hours)],
+            "/data",
+            true,
+        )
+        .await
+    }
+
     pub async fn get_status_page(self: &Arc<Self>, slug: &str) -> Result<StatusPage> {
         let mut status_page: StatusPage = self
             .call(
@@ -980,7 +1527,7 @@ impl Worker {
             )
             .await
             .map_err(|e| match e {
-                Error::ServerError(msg) if msg.contains("Cannot read properties of null") => {
+                e if e.is_not_found() => {
                     Error::SlugNotFound("StatusPage".to_owned(), slug.to_owned())
                 }
                 _ => e,
@@ -990,11 +1537,15 @@ impl Worker {
             self.get_public_group_list(&status_page.slug.clone().unwrap_or_default())
                 .await?,
         );
+        status_page.normalize();
 
         Ok(status_page)
     }
 
     pub async fn edit_status_page(self: &Arc<Self>, status_page: &mut StatusPage) -> Result<()> {
+        status_page.validate(status_page.validation_id())?;
+        status_page.normalize();
+
         let mut config = serde_json::to_value(status_page.clone()).unwrap();
         config
             .as_object_mut()
@@ -1018,19 +1569,19 @@ impl Worker {
         Ok(())
     }
 
-    pub async fn add_docker_host(self: &Arc<Self>, docker_host: &mut DockerHost) -> Result<()> {
-        self.edit_docker_host(docker_host).await
-    }
-
-    pub async fn edit_docker_host(self: &Arc<Self>, docker_host: &mut DockerHost) -> Result<()> {
-        docker_host.id = self
+    pub async fn post_incident(
+        self: &Arc<Self>,
+        slug: &str,
+        incident: &mut Incident,
+    ) -> Result<()> {
+        *incident = self
             .call(
-                "addDockerHost",
+                "postIncident",
                 vec![
-                    serde_json::to_value(docker_host.clone()).unwrap(),
-                    serde_json::to_value(docker_host.id.clone()).unwrap(),
+                    serde_json::to_value(slug).unwrap(),
+                    serde_json::to_value(incident.clone()).unwrap(),
                 ],
-                "/id",
+                "/incident",
                 true,
             )
             .await?;
@@ -1038,11 +1589,11 @@ impl Worker {
         Ok(())
     }
 
-    pub async fn delete_docker_host(self: &Arc<Self>, docker_host_id: i32) -> Result<()> {
+    pub async fn unpin_incident(self: &Arc<Self>, slug: &str) -> Result<()> {
         let _: bool = self
             .call(
-                "deleteDockerHost",
-                vec![serde_json::to_value(docker_host_id).unwrap()],
+                "unpinIncident",
+                vec![serde_json::to_value(slug).unwrap()],
                 "/ok",
                 true,
             )
@@ -1051,36 +1602,153 @@ impl Worker {
         Ok(())
     }
 
-    pub async fn test_docker_host(self: &Arc<Self>, docker_host: &DockerHost) -> Result<String> {
-        let msg: String = self
-            .call(
-                "testDockerHost",
-                vec![serde_json::to_value(docker_host).unwrap()],
-                "/msg",
+    pub async fn add_docker_host(self: &Arc<Self>, docker_host: &mut DockerHost) -> Result<()> {
+        self.edit_docker_host(docker_host).await
+    }
+
+    pub async fn edit_docker_host(self: &Arc<Self>, docker_host: &mut DockerHost) -> Result<()> {
+        docker_host.validate(docker_host.validation_id())?;
+
+        docker_host.id = self
+            .call(
+                "addDockerHost",
+                vec![
+                    serde_json::to_value(docker_host.clone()).unwrap(),
+                    serde_json::to_value(docker_host.id.clone()).unwrap(),
+                ],
+                "/id",
+                true,
+            )
+            .await?;
+
+        // The updated docker host list is sent asynchronously, keep the cache current for monitors added right after
+        let mut docker_hosts = self.docker_hosts.lock().await;
+        match docker_hosts
+            .iter_mut()
+            .find(|host| host.id.is_some() && host.id == docker_host.id)
+        {
+            Some(host) => *host = docker_host.clone(),
+            None => docker_hosts.push(docker_host.clone()),
+        }
+
+        Ok(())
+    }
+
+    pub async fn delete_docker_host(self: &Arc<Self>, docker_host_id: i32) -> Result<()> {
+        let _: bool = self
+            .call(
+                "deleteDockerHost",
+                vec![serde_json::to_value(docker_host_id).unwrap()],
+                "/ok",
                 true,
             )
             .await?;
 
+        self.docker_hosts
+            .lock()
+            .await
+            .retain(|host| host.id != Some(docker_host_id));
+
+        Ok(())
+    }
+
+    pub async fn test_docker_host(self: &Arc<Self>, docker_host: &DockerHost) -> Result<String> {
+        docker_host.validate(docker_host.validation_id())?;
+
+        let msg: String = self
+            .call_with_timeout(
+                "testDockerHost",
+                vec![serde_json::to_value(docker_host).unwrap()],
+                "/msg",
+                true,
+                self.long_call_timeout(),
+            )
+            .await?;
+
         Ok(msg)
     }
 
+    pub async fn get_database_size(self: &Arc<Self>) -> Result<u64> {
+        self.call("getDatabaseSize", vec![], "/size", true).await
+    }
+
+    pub async fn shrink_database(self: &Arc<Self>) -> Result<()> {
+        let _: bool = self
+            .call_with_timeout(
+                "shrinkDatabase",
+                vec![],
+                "/ok",
+                true,
+                self.long_call_timeout(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn export_backup(self: &Arc<Self>) -> Result<Value> {
+        let version = self.server_version.lock().await.clone();
+        let notifications = self.notifications.lock().await.clone();
+        let monitors = self
+            .monitors
+            .lock()
+            .await
+            .values()
+            .cloned()
+            .sorted_by_key(|monitor| *monitor.common().id())
+            .collect_vec();
+
+        Ok(json!({
+            "version": version,
+            "notificationList": notifications,
+            "monitorList": monitors,
+        }))
+    }
+
+    pub async fn import_backup(
+        self: &Arc<Self>,
+        backup: &Value,
+        import_handle: ImportHandle,
+    ) -> Result<()> {
+        let _: bool = self
+            .call_with_timeout(
+                "uploadBackup",
+                vec![
+                    Value::String(backup.to_string()),
+                    Value::String(import_handle.to_string()),
+                ],
+                "/ok",
+                true,
+                self.long_call_timeout(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn connect(self: &Arc<Self>) -> Result<()> {
         let mut tls_config = TlsConnector::builder();
 
         tls_config.danger_accept_invalid_certs(!self.config.tls.verify);
 
-        if let Some((_, cert)) = &self.custom_cert {
+        for cert in self.custom_cert.iter().flat_map(|(_, certs)| certs) {
             tls_config.add_root_certificate(cert.clone());
         }
 
+        if let Some((_, identity)) = &self.client_identity {
+            tls_config.identity(identity.clone());
+        }
+
         self.is_ready.lock().await.reset();
+        *self.is_connected.lock().await = false;
         *self.is_logged_in.lock().await = false;
+        *self.token_required.lock().await = false;
         *self.socket_io.lock().await = None;
 
         let mut builder = ClientBuilder::new(
             self.config
-                .url
-                .join("/socket.io/")
+                .base_url()
+                .join("socket.io/")
                 .map_err(|e| Error::InvalidUrl(e.to_string()))?,
         )
         .tls_config(tls_config.build().map_err(|e| {
@@ -1094,15 +1762,23 @@ impl Worker {
         })?)
         .transport_type(rust_socketio::TransportType::Websocket);
 
-        for (key, value) in self
-            .config
-            .headers
-            .iter()
-            .filter_map(|header| header.split_once("="))
-        {
-            builder = builder.opening_header(key, value);
+        for (key, value) in &self.config.headers {
+            builder = builder.opening_header(key.as_str(), value.as_str());
         }
 
+        let self_ref = Arc::downgrade(self);
+        builder = builder.on(SocketIOEvent::Close, move |_, _| {
+            let self_ref: Weak<Worker> = self_ref.clone();
+            async move {
+                if let Some(arc) = self_ref.upgrade() {
+                    debug!("Connection closed by server");
+                    *arc.is_connected.lock().await = false;
+                    *arc.is_logged_in.lock().await = false;
+                }
+            }
+            .boxed()
+        });
+
         let handle = Handle::current();
         let self_ref = Arc::downgrade(self);
         let client = builder
@@ -1123,7 +1799,7 @@ impl Worker {
                                         .unwrap_or(""),
                                 ) {
                                     handle.clone().spawn(async move {
-                                        _ = arc.on_event(e, json!(null)).await.log_warn(
+                                        _ = arc.on_event(e, vec![]).await.log_warn(
                                             std::module_path!(),
                                             |e| {
                                                 format!(
@@ -1138,16 +1814,16 @@ impl Worker {
                             (event, Payload::Text(params)) => {
                                 if let Ok(e) = Event::from_str(&String::from(event)) {
                                     handle.clone().spawn(async move {
-                                        _ = arc
-                                            .on_event(e.clone(), params.into_iter().next().unwrap())
-                                            .await
-                                            .log_warn(std::module_path!(), |err| {
+                                        _ = arc.on_event(e.clone(), params).await.log_warn(
+                                            std::module_path!(),
+                                            |err| {
                                                 format!(
                                                     "Error while handling '{:?}' event: {}",
                                                     e,
                                                     err.to_string()
                                                 )
-                                            });
+                                            },
+                                        );
                                     });
                                 }
                             }
@@ -1159,22 +1835,29 @@ impl Worker {
             })
             .connect()
             .await
-            .log_error(std::module_path!(), |_| "Error during connect")
-            .ok();
-
-        debug!("Waiting for connection");
+            .map_err(|e| Error::ConnectFailed(e.to_string()))?;
 
         debug!("Connection opened!");
-        *self.socket_io.lock().await = client;
+        *self.socket_io.lock().await = Some(client);
 
-        for i in 0..10 {
+        let deadline =
+            tokio::time::Instant::now() + Duration::from_secs_f64(self.config.ready_timeout);
+        loop {
             if self.is_ready().await {
                 debug!("Connected!");
                 return Ok(());
             }
 
+            if *self.token_required.lock().await {
+                return Err(Error::TokenRequired);
+            }
+
+            if tokio::time::Instant::now() >= deadline {
+                break;
+            }
+
             debug!("Waiting for Kuma to get ready...");
-            tokio::time::sleep(Duration::from_millis(200 * i)).await;
+            tokio::time::sleep(Duration::from_millis(READY_POLL_INTERVAL)).await;
         }
 
         warn!("Timeout while waiting for Kuma to get ready...");
@@ -1193,6 +1876,8 @@ impl Worker {
             }
             drop(socket_io);
             *self_ref.socket_io.lock().await = None;
+            *self_ref.is_connected.lock().await = false;
+            *self_ref.is_logged_in.lock().await = false;
             debug!("Connection closed!");
         })
         .await
@@ -1211,8 +1896,164 @@ impl Worker {
         Ok(())
     }
 
+    /// Whether the server has to be treated as Uptime Kuma 1.x, see [api_version](crate::Config::api_version).
+    async fn uses_v1_api(self: &Arc<Self>) -> bool {
+        match self.config.api_version {
+            ApiVersion::V1 => true,
+            ApiVersion::V2 => false,
+            ApiVersion::Auto => self
+                .server_version
+                .lock()
+                .await
+                .as_deref()
+                .and_then(parse_version)
+                .is_some_and(|(major, _)| major < 2),
+        }
+    }
+
+    /// Rejects monitors with a type the server doesn't know, instead of letting the server store a broken monitor.
+    async fn check_monitor_type(self: &Arc<Self>, monitor: &Monitor) -> Result<()> {
+        let monitor_type = monitor.monitor_type();
+
+        if monitor_type.requires_v2() && self.uses_v1_api().await {
+            return Err(Error::ValidationError(
+                monitor.common().name().clone().unwrap_or_default(),
+                vec![format!(
+                    "Monitor type '{}' requires Uptime Kuma 2.0",
+                    serde_json::to_value(&monitor_type)
+                        .ok()
+                        .and_then(|value| value.as_str().map(|value| value.to_owned()))
+                        .unwrap_or_default()
+                )],
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Warns about docker monitors referencing a docker host which isn't known, the server fails when checking the container of such a monitor.
+    async fn check_docker_host(self: &Arc<Self>, monitor: &Monitor) {
+        let Monitor::Docker { value } = monitor else {
+            return;
+        };
+
+        let Some(docker_host) = value.docker_host else {
+            return;
+        };
+
+        // Older servers don't send a docker host list, so there is nothing to check against
+        if !self.is_ready.lock().await.docker_host_list {
+            return;
+        }
+
+        if !self
+            .docker_hosts
+            .lock()
+            .await
+            .iter()
+            .any(|host| host.id == Some(docker_host))
+        {
+            warn!(
+                "Docker host with id {} of monitor {} is not known",
+                docker_host,
+                monitor.common().name().clone().unwrap_or_default()
+            );
+        }
+    }
+
+    /// Serialize a monitor, leaving out any properties which aren't supported by the connected server.
+    async fn monitor_to_value(self: &Arc<Self>, monitor: &Monitor) -> Value {
+        let mut monitor_json = serde_json::to_value(monitor).unwrap();
+
+        if let (true, Some(monitor_json)) = (self.uses_v1_api().await, monitor_json.as_object_mut())
+        {
+            for field in V2_MONITOR_FIELDS {
+                monitor_json.remove(*field);
+            }
+        }
+
+        monitor_json
+    }
+
+    /// Whether an edited monitor needs a placeholder url, see https://github.com/BigBoot/AutoKuma/issues/72.
+    async fn needs_url_workaround(self: &Arc<Self>, monitor: &Monitor) -> bool {
+        let is_fixed = match (
+            URL_WORKAROUND_FIXED_VERSION,
+            self.server_version
+                .lock()
+                .await
+                .as_deref()
+                .and_then(parse_version),
+        ) {
+            (Some(fixed_version), Some(version)) => version >= fixed_version,
+            _ => false,
+        };
+
+        !is_fixed && URL_WORKAROUND_TYPES.contains(&monitor.monitor_type())
+    }
+
     pub async fn is_ready(self: &Arc<Self>) -> bool {
-        self.is_ready.lock().await.is_ready()
+        let server_version = self.server_version.lock().await.clone();
+        self.is_ready
+            .lock()
+            .await
+            .is_ready(server_version.as_deref(), self.config.lazy_monitors)
+    }
+
+    /// Wakes all tasks waiting in [await_ready](Self::await_ready) once the server sent all required data.
+    async fn notify_if_ready(self: &Arc<Self>) {
+        if self.is_ready().await {
+            self.ready_notify.notify_waiters();
+        }
+    }
+
+    pub async fn await_ready(self: &Arc<Self>, timeout: Duration) -> Result<()> {
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        loop {
+            // Register as waiter before checking, otherwise a notification between the check and the await would be missed.
+            let notified = self.ready_notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if self.is_ready().await {
+                return Ok(());
+            }
+
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                return Err(Error::NotReady);
+            }
+        }
+    }
+
+    /// Whether the client is ready, waiting up to [ready_wait_timeout](crate::Config::ready_wait_timeout) if it isn't yet.
+    async fn wait_ready(self: &Arc<Self>) -> bool {
+        match self.config.ready_wait_timeout {
+            timeout if timeout > 0.0 => self
+                .await_ready(Duration::from_secs_f64(timeout))
+                .await
+                .is_ok(),
+            _ => self.is_ready().await,
+        }
+    }
+
+    /// Returns the monitor list, when using lazy monitors it's requested from the server if it hasn't been received yet.
+    pub async fn get_monitors(self: &Arc<Self>) -> Result<MonitorList> {
+        if !self.is_ready.lock().await.monitor_list {
+            let _: bool = self.call("getMonitorList", vec![], "/ok", true).await?;
+
+            let deadline =
+                tokio::time::Instant::now() + Duration::from_secs_f64(self.config.ready_timeout);
+            while !self.is_ready.lock().await.monitor_list {
+                if tokio::time::Instant::now() >= deadline {
+                    return Err(Error::CallTimeout("getMonitorList".to_owned()));
+                }
+
+                tokio::time::sleep(Duration::from_millis(READY_POLL_INTERVAL)).await;
+            }
+        }
+
+        Ok(self.monitors.lock().await.clone())
     }
 }
 
@@ -1221,12 +2062,13 @@ impl Worker {
 /// Example:
 /// ```
 /// // Connect to the server
-/// let client = Client::connect(Config {
-///         url: Url::parse("http://localhost:3001").expect("Invalid URL"),
-///         username: Some("Username".to_owned()),
-///         password: Some("Password".to_owned()),
-///         ..Default::default()
-///     })
+/// let client = Client::connect(
+///         Config::builder()
+///             .url("http://localhost:3001")
+///             .basic_auth("Username", "Password")
+///             .build()
+///             .expect("Invalid config"),
+///     )
 ///     .await
 ///     .expect("Failed to connect to server");
 ///
@@ -1315,10 +2157,42 @@ impl Client {
         }
     }
 
+    /// Whether the client is currently connected to the Uptime Kuma server.
+    pub async fn is_connected(&self) -> bool {
+        *self.worker.is_connected.lock().await
+    }
+
+    /// Whether the client is currently logged in (or auth is disabled on the server).
+    pub async fn is_logged_in(&self) -> bool {
+        *self.worker.is_logged_in.lock().await
+    }
+
+    /// Whether the server sent all data required to access its state.
+    pub async fn is_ready(&self) -> bool {
+        self.worker.is_ready().await
+    }
+
+    /// Waits until the server sent all data required to access its state, returns [Error::NotReady] if that doesn't happen within the timeout.
+    pub async fn await_ready(&self, timeout: Duration) -> Result<()> {
+        self.worker.await_ready(timeout).await
+    }
+
+    /// Returns the token of the current session, it can be used as [auth_token](crate::Config::auth_token) to log in again without the username/password.
+    pub async fn get_auth_token(&self) -> Option<String> {
+        self.worker.auth_token.lock().await.clone()
+    }
+
+    /// Returns the version reported by the connected Uptime Kuma server.
+    pub async fn server_version(&self) -> Option<String> {
+        self.worker.server_version.lock().await.clone()
+    }
+
     /// Retrieves a list of monitors from Uptime Kuma.
+    ///
+    /// With [lazy_monitors](crate::Config::lazy_monitors) enabled this explicitly requests the list from the server the first time it's called.
     pub async fn get_monitors(&self) -> Result<MonitorList> {
-        match self.worker.is_ready().await {
-            true => Ok(self.worker.monitors.lock().await.clone()),
+        match self.worker.wait_ready().await {
+            true => self.worker.get_monitors().await,
             false => Err(Error::NotReady),
         }
     }
@@ -1335,6 +2209,17 @@ impl Client {
         Ok(monitor)
     }
 
+    /// Creates a copy of an existing monitor with a new name, including its tags and notifications.
+    ///
+    /// Push monitors get a new random push token, as it has to be unique.
+    pub async fn clone_monitor(
+        &self,
+        monitor_id: i32,
+        new_name: impl AsRef<str>,
+    ) -> Result<Monitor> {
+        self.worker.clone_monitor(monitor_id, new_name).await
+    }
+
     /// Edits an existing monitor in Uptime Kuma.
     pub async fn edit_monitor<T: Into<Monitor>>(&self, monitor: T) -> Result<Monitor> {
         let mut monitor = monitor.into();
@@ -1357,6 +2242,43 @@ impl Client {
         self.worker.resume_monitor(monitor_id).await
     }
 
+    /// Pauses a monitor in Uptime Kuma based on its ID, including all monitors below it if it's a group.
+    pub async fn pause_monitor_recursive(&self, monitor_id: i32) -> Result<()> {
+        self.worker.pause_monitor_recursive(monitor_id).await
+    }
+
+    /// Resumes a paused monitor in Uptime Kuma based on its ID, including all monitors below it if it's a group.
+    pub async fn resume_monitor_recursive(&self, monitor_id: i32) -> Result<()> {
+        self.worker.resume_monitor_recursive(monitor_id).await
+    }
+
+    /// Retrieves the most recent heartbeats of each monitor pushed by Uptime Kuma.
+    pub async fn get_heartbeats(&self) -> Result<HeartbeatList> {
+        match self.worker.wait_ready().await {
+            true => Ok(self.worker.heartbeats.lock().await.clone()),
+            false => Err(Error::NotReady),
+        }
+    }
+
+    /// Retrieves the heartbeats of a monitor from the last `period_hours` hours.
+    pub async fn get_monitor_beats(
+        &self,
+        monitor_id: i32,
+        period_hours: u32,
+    ) -> Result<Vec<Heartbeat>> {
+        self.worker
+            .get_monitor_beats(monitor_id, period_hours)
+            .await
+    }
+
+    /// Retrieves the average ping and uptime of each monitor pushed by Uptime Kuma.
+    pub async fn get_uptimes(&self) -> Result<UptimeList> {
+        match self.worker.wait_ready().await {
+            true => Ok(self.worker.uptimes.lock().await.clone()),
+            false => Err(Error::NotReady),
+        }
+    }
+
     /// Retrieves a list of tags from Uptime Kuma.
     pub async fn get_tags(&self) -> Result<Vec<TagDefinition>> {
         self.worker.get_tags().await
@@ -1390,7 +2312,7 @@ impl Client {
 
     /// Retrieves a list of notifications from Uptime Kuma.
     pub async fn get_notifications(&self) -> Result<NotificationList> {
-        match self.worker.is_ready().await {
+        match self.worker.wait_ready().await {
             true => Ok(self.worker.notifications.lock().await.clone()),
             false => Err(Error::NotReady),
         }
@@ -1418,6 +2340,27 @@ impl Client {
         Ok(notification)
     }
 
+    /// Calls an arbitrary socket method of Uptime Kuma and returns the raw JSON found at `result_ptr` (use `""` for the whole response).
+    ///
+    /// This is an escape hatch for methods which aren't modeled by this crate yet, the arguments and the response bypass any typed validation.
+    /// The call fails if the server responds with `ok: false`.
+    pub async fn call_raw(
+        &self,
+        method: &str,
+        args: Vec<Value>,
+        result_ptr: &str,
+    ) -> Result<Value> {
+        self.worker.call(method, args, result_ptr, true).await
+    }
+
+    /// Sends a test message using the given notification.
+    pub async fn test_notification<T: std::borrow::Borrow<Notification>>(
+        &self,
+        notification: T,
+    ) -> Result<String> {
+        self.worker.test_notification(notification.borrow()).await
+    }
+
     /// Deletes a notification from Uptime Kuma based on its ID.
     pub async fn delete_notification(&self, notification_id: i32) -> Result<()> {
         self.worker.delete_notification(notification_id).await
@@ -1425,7 +2368,7 @@ impl Client {
 
     /// Retrieves a list of maintenances from Uptime Kuma.
     pub async fn get_maintenances(&self) -> Result<MaintenanceList> {
-        match self.worker.is_ready().await {
+        match self.worker.wait_ready().await {
             true => Ok(self.worker.maintenances.lock().await.clone()),
             false => Err(Error::NotReady),
         }
@@ -1465,7 +2408,7 @@ impl Client {
 
     /// Retrieves a list of status pages from Uptime Kuma.
     pub async fn get_status_pages(&self) -> Result<StatusPageList> {
-        match self.worker.is_ready().await {
+        match self.worker.wait_ready().await {
             true => Ok(self.worker.status_pages.lock().await.clone()),
             false => Err(Error::NotReady),
         }
@@ -1493,14 +2436,41 @@ impl Client {
         self.worker.delete_status_page(slug.as_ref()).await
     }
 
+    /// Posts an incident to the status page identified by its slug, replacing any pinned incident.
+    pub async fn post_incident<T: AsRef<str>>(
+        &self,
+        slug: T,
+        mut incident: Incident,
+    ) -> Result<Incident> {
+        self.worker
+            .post_incident(slug.as_ref(), &mut incident)
+            .await?;
+        Ok(incident)
+    }
+
+    /// Unpins the current incident from the status page identified by its slug.
+    pub async fn unpin_incident<T: AsRef<str>>(&self, slug: T) -> Result<()> {
+        self.worker.unpin_incident(slug.as_ref()).await
+    }
+
     /// Retrieves a list of status pages from Uptime Kuma.
     pub async fn get_docker_hosts(&self) -> Result<DockerHostList> {
-        match self.worker.is_ready().await {
+        match self.worker.wait_ready().await {
             true => Ok(self.worker.docker_hosts.lock().await.clone()),
             false => Err(Error::NotReady),
         }
     }
 
+    /// Retrieves a list of proxies from Uptime Kuma.
+    ///
+    /// The proxy list isn't required for the client to become ready, so it's empty if Uptime Kuma hasn't sent it yet.
+    pub async fn get_proxies(&self) -> Result<ProxyList> {
+        match self.worker.wait_ready().await {
+            true => Ok(self.worker.proxies.lock().await.clone()),
+            false => Err(Error::NotReady),
+        }
+    }
+
     /// Retrieves information about a specific docker host identified by its id.
     pub async fn get_docker_host(&self, docker_host_id: i32) -> Result<DockerHost> {
         self.get_docker_hosts().await.and_then(|docker_host| {
@@ -1536,6 +2506,40 @@ impl Client {
         self.worker.test_docker_host(docker_host.borrow()).await
     }
 
+    /// Exports a backup of all monitors and notifications from Uptime Kuma.
+    pub async fn export_backup(&self) -> Result<Value> {
+        match self.worker.wait_ready().await {
+            true => self.worker.export_backup().await,
+            false => Err(Error::NotReady),
+        }
+    }
+
+    /// Returns the size of the Uptime Kuma database in bytes.
+    pub async fn get_database_size(&self) -> Result<u64> {
+        self.worker.get_database_size().await
+    }
+
+    /// Shrinks the Uptime Kuma database (`VACUUM` for SQLite), this can take a long time for large databases.
+    pub async fn shrink_database(&self) -> Result<()> {
+        self.worker.shrink_database().await
+    }
+
+    /// Imports a backup previously created using [Client::export_backup] into Uptime Kuma.
+    pub async fn import_backup<T: std::borrow::Borrow<Value>>(
+        &self,
+        backup: T,
+        import_handle: ImportHandle,
+    ) -> Result<()> {
+        match self.worker.wait_ready().await {
+            true => {
+                self.worker
+                    .import_backup(backup.borrow(), import_handle)
+                    .await
+            }
+            false => Err(Error::NotReady),
+        }
+    }
+
     /// Disconnects the client from Uptime Kuma.
     pub async fn disconnect(&self) -> Result<()> {
         self.worker.disconnect().await
@@ -1553,3 +2557,407 @@ impl Drop for Client {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{fixtures, MockServer};
+
+    /// Two self-signed certificates, preceded by a comment like in the bundles of most distributions.
+    const PEM_BUNDLE: &str = "# Test CA 1
+-----BEGIN CERTIFICATE-----
+MIIBkjCCATegAwIBAgIUCBNLPg7z7kPpTipbh9mO1yVTwGgwCgYIKoZIzj0EAwIw
+HTEbMBkGA1UEAwwSQXV0b0t1bWEgVGVzdCBDQSAxMCAXDTI2MTAxNjEzMzgxMVoY
+DzIxMjYwOTIyMTMzODExWjAdMRswGQYDVQQDDBJBdXRvS3VtYSBUZXN0IENBIDEw
+WTATBgcqhkjOPQIBBggqhkjOPQMBBwNCAAQyRons/W7oaZn3r1mipwplh1GBHB41
+sxbseEvkcKwmvOrKo/nw31kNruHSNYN8AFfRBrv8DYyxK3tPGavm1QgNo1MwUTAd
+BgNVHQ4EFgQUjd3mw5tOHMSC+lkkND/x3HnFPuAwHwYDVR0jBBgwFoAUjd3mw5tO
+HMSC+lkkND/x3HnFPuAwDwYDVR0TAQH/BAUwAwEB/zAKBggqhkjOPQQDAgNJADBG
+AiEAn6XXp2NmsDHtl1eaUhP+2vt5Irma6mr7TZ2MGCZNKekCIQDIPHD0GJ0k4PkK
+syymWLUiZUu2xebgrIhV6D/v43wMEg==
+-----END CERTIFICATE-----
+# Test CA 2
+-----BEGIN CERTIFICATE-----
+MIIBkTCCATegAwIBAgIUWnPj/iUdnUHq4lHi2Xn4w6AxH6MwCgYIKoZIzj0EAwIw
+HTEbMBkGA1UEAwwSQXV0b0t1bWEgVGVzdCBDQSAyMCAXDTI2MTAxNjEzMzgxMVoY
+DzIxMjYwOTIyMTMzODExWjAdMRswGQYDVQQDDBJBdXRvS3VtYSBUZXN0IENBIDIw
+WTATBgcqhkjOPQIBBggqhkjOPQMBBwNCAAR0pcCjmzn3g8iMn5oBBwJvmyvlkDiq
+HsPWKhNyDjCm2Ylyo4gay93clHziGWFlhCBRus+VTx/AchfIAmJKdOy6o1MwUTAd
+BgNVHQ4EFgQUl3p+EWCr19tl09cMAlXdPd24QA0wHwYDVR0jBBgwFoAUl3p+EWCr
+19tl09cMAlXdPd24QA0wDwYDVR0TAQH/BAUwAwEB/zAKBggqhkjOPQQDAgNIADBF
+AiA8R7lYVbYGPq5D8klU+tg9++KF1aCJ6fymsT7n5ZpgTwIhAP+I2e4M/sbuRcjJ
+kF8L5Hs5i/60IE/AEXV4rZooLTBf
+-----END CERTIFICATE-----
+";
+
+    async fn calls(server: &MockServer, method: &str) -> usize {
+        server
+            .calls()
+            .await
+            .iter()
+            .filter(|(call, _)| call == method)
+            .count()
+    }
+
+    async fn connect(server: &MockServer, call_max_retries: u32) -> Client {
+        Client::connect(Config {
+            call_timeout: 0.2,
+            call_max_retries,
+            ..server.config()
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn call_retries_transient_errors() {
+        let server = MockServer::start()
+            .await
+            .with_monitors(fixtures::monitor_list())
+            .await;
+        let client = connect(&server, 2).await;
+
+        server.ignore("getMonitor", 2).await;
+
+        assert!(client.get_monitor(2).await.is_ok());
+        assert_eq!(calls(&server, "getMonitor").await, 3);
+    }
+
+    #[tokio::test]
+    async fn call_gives_up_after_max_retries() {
+        let server = MockServer::start()
+            .await
+            .with_monitors(fixtures::monitor_list())
+            .await;
+        let client = connect(&server, 1).await;
+
+        server.ignore("getMonitor", 2).await;
+
+        assert!(matches!(
+            client.get_monitor(2).await,
+            Err(Error::CallTimeout(_))
+        ));
+        assert_eq!(calls(&server, "getMonitor").await, 2);
+    }
+
+    #[tokio::test]
+    async fn call_does_not_retry_server_errors() {
+        let server = MockServer::start().await;
+        let client = connect(&server, 2).await;
+
+        server
+            .respond("getMonitor", json!({"ok": false, "msg": "Nope"}))
+            .await;
+
+        assert!(matches!(
+            client.get_monitor(2).await,
+            Err(Error::ServerError { .. })
+        ));
+        assert_eq!(calls(&server, "getMonitor").await, 1);
+    }
+
+    #[tokio::test]
+    async fn call_does_not_retry_timed_out_changes() {
+        let server = MockServer::start()
+            .await
+            .with_monitors(fixtures::monitor_list())
+            .await;
+        let client = connect(&server, 2).await;
+
+        server.ignore("deleteMonitor", 1).await;
+
+        assert!(matches!(
+            client.delete_monitor(2).await,
+            Err(Error::CallTimeout(_))
+        ));
+        assert_eq!(calls(&server, "deleteMonitor").await, 1);
+    }
+
+    #[tokio::test]
+    async fn missing_status_pages_are_not_found() {
+        let server = MockServer::start().await;
+        let client = connect(&server, 0).await;
+
+        assert!(matches!(
+            client.get_status_page("missing").await,
+            Err(Error::SlugNotFound(_, slug)) if slug == "missing"
+        ));
+    }
+
+    #[tokio::test]
+    async fn cloned_push_monitors_get_a_new_push_token() {
+        let server = MockServer::start().await;
+        let client = connect(&server, 0).await;
+
+        let original = client
+            .add_monitor(crate::monitor::MonitorPush {
+                name: Some("Push".to_owned()),
+                push_token: Some("original".to_owned()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let clone = client
+            .clone_monitor(original.common().id().unwrap(), "Push (Clone)")
+            .await
+            .unwrap();
+
+        let Monitor::Push { value } = clone else {
+            panic!("Expected a push monitor, got {:?}", clone);
+        };
+        let push_token = value.push_token.unwrap();
+        assert_eq!(push_token.len(), PUSH_TOKEN_LENGTH);
+        assert!(push_token.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert_ne!(push_token, "original");
+        assert_ne!(value.id, original.common().id().clone());
+    }
+
+    #[tokio::test]
+    async fn url_workaround_is_scoped_to_monitors_with_url() {
+        let server = MockServer::start().await;
+        let client = connect(&server, 0).await;
+
+        client
+            .add_monitor(crate::monitor::MonitorPing {
+                name: Some("Ping".to_owned()),
+                hostname: Some("example.com".to_owned()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        client
+            .add_monitor(crate::monitor::MonitorHttp {
+                name: Some("Http".to_owned()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let edits = server
+            .calls()
+            .await
+            .into_iter()
+            .filter(|(method, _)| method == "editMonitor")
+            .map(|(_, args)| args[0].clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(edits.len(), 2);
+        assert_eq!(edits[0]["type"], "ping");
+        assert!(edits[0].get("url").is_none());
+        assert_eq!(edits[1]["type"], "http");
+        assert_eq!(edits[1]["url"], "https://");
+    }
+
+    #[test]
+    fn pem_bundle_with_two_certificates() {
+        let certificates = parse_pem_bundle("bundle.pem", PEM_BUNDLE).unwrap();
+        assert_eq!(certificates.len(), 2);
+    }
+
+    #[test]
+    fn pem_bundle_reports_invalid_certificate() {
+        let bundle = PEM_BUNDLE.replacen("MIIBkTCC", "MIIBkTXX", 1);
+
+        match parse_pem_bundle("bundle.pem", &bundle) {
+            Err(Error::InvalidTlsCert(file, msg)) => {
+                assert_eq!(file, "bundle.pem");
+                assert!(msg.starts_with("Certificate #2"), "{}", msg);
+            }
+            result => panic!(
+                "Expected an invalid certificate error, got {:?}",
+                result.map(|c| c.len())
+            ),
+        }
+
+        assert!(matches!(
+            parse_pem_bundle("empty.pem", ""),
+            Err(Error::InvalidTlsCert(_, _))
+        ));
+    }
+
+    #[test]
+    fn ready_requires_the_lists_sent_by_the_server_version() {
+        let mut ready = Ready::new();
+        ready.monitor_list = true;
+        ready.notification_list = true;
+        ready.maintenance_list = true;
+        ready.status_page_list = true;
+
+        assert!(ready.is_ready(Some("1.17.1"), false));
+        assert!(!ready.is_ready(Some("1.23.16"), false));
+        assert!(!ready.is_ready(None, false));
+
+        ready.docker_host_list = true;
+        assert!(ready.is_ready(Some("1.23.16"), false));
+        assert!(ready.is_ready(None, false));
+
+        ready.monitor_list = false;
+        assert!(!ready.is_ready(None, false));
+        assert!(ready.is_ready(None, true));
+    }
+
+    #[tokio::test]
+    async fn recursive_pause_and_resume_include_nested_groups() {
+        let monitor = |id: i32, monitor_type: &str, parent: Option<i32>| {
+            json!({
+                "id": id,
+                "name": format!("Monitor {}", id),
+                "type": monitor_type,
+                "active": true,
+                "interval": 60,
+                "retryInterval": 60,
+                "maxretries": 0,
+                "upsideDown": false,
+                "parent": parent,
+                "url": "https://example.com",
+                "tags": [],
+                "notificationIDList": {},
+                "accepted_statuscodes": ["200-299"],
+            })
+        };
+        let server = MockServer::start()
+            .await
+            .with_monitors(json!({
+                "1": monitor(1, "group", None),
+                "2": monitor(2, "group", Some(1)),
+                "3": monitor(3, "http", Some(2)),
+                "4": monitor(4, "http", Some(1)),
+                "5": monitor(5, "http", None),
+            }))
+            .await;
+        let client = connect(&server, 0).await;
+
+        let ids = |method: &'static str| {
+            let server = &server;
+            async move {
+                server
+                    .calls()
+                    .await
+                    .into_iter()
+                    .filter(|(call, _)| call == method)
+                    .map(|(_, args)| args[0].as_i64().unwrap())
+                    .collect_vec()
+            }
+        };
+
+        client.pause_monitor_recursive(1).await.unwrap();
+        assert_eq!(ids("pauseMonitor").await, vec![1, 2, 4, 3]);
+
+        client.resume_monitor_recursive(2).await.unwrap();
+        assert_eq!(ids("resumeMonitor").await, vec![2, 3]);
+    }
+
+    #[cfg(feature = "uptime-kuma-v2")]
+    #[tokio::test]
+    async fn v2_monitor_types_are_rejected_by_v1_servers() {
+        use crate::monitor::MonitorSmtp;
+
+        let smtp = || MonitorSmtp {
+            name: Some("Mail".to_owned()),
+            hostname: Some("mail.example.com".to_owned()),
+            ..Default::default()
+        };
+
+        let server = MockServer::start().await.with_version("1.23.16").await;
+        let client = connect(&server, 0).await;
+
+        assert!(matches!(
+            client.add_monitor(smtp()).await,
+            Err(Error::ValidationError(name, _)) if name == "Mail"
+        ));
+        assert_eq!(calls(&server, "add").await, 0);
+
+        let server = MockServer::start().await;
+        let client = connect(&server, 0).await;
+
+        assert!(client.add_monitor(smtp()).await.is_ok());
+        assert_eq!(calls(&server, "add").await, 1);
+    }
+
+    #[test]
+    fn deserialize_errors_point_at_the_failing_field() {
+        let error = Worker::extract_response::<HashMap<String, Vec<i32>>>(
+            "getValues",
+            vec![json!([{"ok": true, "values": {"a": [1, 2], "b/c": [3, "4"]}}])],
+            "/values",
+            true,
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            error,
+            Error::DeserializeResponse { method, pointer, .. }
+                if method == "getValues" && pointer == "/values/b~1c/1"
+        ));
+    }
+
+    #[tokio::test]
+    async fn info_arriving_last_wakes_waiting_calls() {
+        let server = MockServer::start().await.with_version("1.17.1").await;
+        let client = connect(&server, 0).await;
+
+        // Without a version all lists are required, so the client isn't ready until `info` arrives
+        *client.worker.server_version.lock().await = None;
+        assert!(!client.is_ready().await);
+
+        let waiting = tokio::spawn({
+            let worker = client.worker.clone();
+            async move { worker.await_ready(Duration::from_secs(5)).await }
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        server.emit("info", json!({"version": "1.17.1"}));
+
+        assert!(matches!(
+            tokio::time::timeout(Duration::from_secs(1), waiting).await,
+            Ok(Ok(Ok(())))
+        ));
+    }
+
+    #[tokio::test]
+    async fn added_docker_hosts_are_known_immediately() {
+        let server = MockServer::start().await;
+        let client = connect(&server, 0).await;
+
+        server
+            .respond(
+                "addDockerHost",
+                json!({"ok": true, "msg": "Saved.", "id": 4}),
+            )
+            .await;
+        server
+            .respond("deleteDockerHost", json!({"ok": true, "msg": "Deleted."}))
+            .await;
+
+        let docker_host = client
+            .add_docker_host(DockerHost {
+                name: Some("Local".to_owned()),
+                connection_type: Some(crate::docker_host::DockerConnectionType::Socket),
+                host: Some("/var/run/docker.sock".to_owned()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(docker_host.id, Some(4));
+        assert_eq!(client.get_docker_host(4).await.unwrap(), docker_host);
+
+        client
+            .edit_docker_host(DockerHost {
+                name: Some("Renamed".to_owned()),
+                ..docker_host
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(client.get_docker_hosts().await.unwrap().len(), 1);
+        assert_eq!(
+            client.get_docker_host(4).await.unwrap().name,
+            Some("Renamed".to_owned())
+        );
+
+        client.delete_docker_host(4).await.unwrap();
+
+        assert!(client.get_docker_host(4).await.is_err());
+    }
+}
@@ -1,15 +1,35 @@
-use crate::deserialize::DeserializeVecLenient;
+use crate::{
+    deserialize::DeserializeHeadersLenient,
+    error::{Error, Result},
+};
+use reqwest::header::{HeaderName, HeaderValue};
 use serde::{Deserialize, Serialize};
 use serde_alias::serde_alias;
 use serde_inline_default::serde_inline_default;
-use serde_with::{formats::CommaSeparator, serde_as, PickFirst, StringWithSeparator};
+use serde_with::serde_as;
+use std::collections::HashMap;
 use url::Url;
 
+/// The Uptime Kuma API the [Client](crate::Client) talks to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ApiVersion {
+    /// Use the version reported by the server.
+    #[default]
+    #[serde(rename = "auto")]
+    Auto,
+    /// Behave like Uptime Kuma 1.x, properties and monitor types added in 2.0 are never sent.
+    #[serde(rename = "v1")]
+    V1,
+    /// Behave like Uptime Kuma 2.x, regardless of the version reported by the server.
+    #[serde(rename = "v2")]
+    V2,
+}
+
 /// TLS Configuration for the [Client](crate::Client).
 #[serde_alias(ScreamingSnakeCase)]
 #[serde_inline_default]
 #[serde_as]
-#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TlsConfig {
     /// Whether to verify the TLS certificate or not.
     ///
@@ -28,9 +48,17 @@ pub struct TlsConfig {
     /// The path to a custom tls certificate in PEM format.
     ///
     /// This can be used to connect to a server that has a self-signed
-    /// certificate for example.
+    /// certificate for example. The file can contain multiple certificates (e.g. a CA bundle).
     #[serde(default)]
     pub cert: Option<String>,
+
+    /// The path to a client certificate in PEM format, used to authenticate against servers requiring mutual TLS.
+    #[serde(default)]
+    pub client_cert: Option<String>,
+
+    /// The path to the PKCS #8 private key of the client certificate in PEM format.
+    #[serde(default)]
+    pub client_key: Option<String>,
 }
 
 /// Configuration for the [Client](crate::Client).
@@ -42,24 +70,45 @@ pub struct Config {
     /// The URL for connecting to Uptime Kuma.
     pub url: Url,
 
+    /// The path Uptime Kuma is served under when running behind a reverse proxy with a prefix, e.g. `/kuma/`.
+    ///
+    /// Defaults to `/`, the path of the [url](Self::url) is ignored.
+    #[serde(default)]
+    pub base_path: Option<String>,
+
     /// The username for logging into Uptime Kuma (required unless auth is disabled).                      .
     pub username: Option<String>,
 
     /// The password for logging into Uptime Kuma (required unless auth is disabled).
     pub password: Option<String>,
 
+    /// Path to a file containing the password for logging into Uptime Kuma (alternative to password).
+    pub password_file: Option<String>,
+
     /// The MFA token for logging into Uptime Kuma (required if MFA is enabled).
     pub mfa_token: Option<String>,
 
     /// The MFA secret. Used to generate a tokens for logging into Uptime Kuma (alternative to a single_use mfa_token).
     pub mfa_secret: Option<String>,
 
-    /// List of HTTP headers to send when connecting to Uptime Kuma.
-    #[serde_as(
-        as = "PickFirst<(DeserializeVecLenient<String>, StringWithSeparator::<CommaSeparator, String>)>"
-    )]
+    /// Path to a file containing the MFA secret (alternative to mfa_secret).
+    pub mfa_secret_file: Option<String>,
+
+    /// A token from a previous login (see [get_auth_token](crate::Client::get_auth_token)), used instead of username/password.
+    pub auth_token: Option<String>,
+
+    /// Path to a file containing the auth token (alternative to auth_token).
+    pub auth_token_file: Option<String>,
+
+    /// HTTP headers to send when connecting to Uptime Kuma.
+    ///
+    /// Can be deserialized from a map, a list of entries or a comma separated string of entries,
+    /// where each entry is either `key=value` or `Name: value`.
+    /// Map keys coming from environment variables are lowercased and cannot contain `-`,
+    /// use the entry forms for such headers instead.
+    #[serde_as(as = "DeserializeHeadersLenient")]
     #[serde(default)]
-    pub headers: Vec<String>,
+    pub headers: HashMap<String, String>,
 
     /// The timeout for the initial connection to Uptime Kuma.
     #[serde_inline_default(30.0)]
@@ -69,22 +118,379 @@ pub struct Config {
     #[serde_inline_default(30.0)]
     pub call_timeout: f64,
 
+    /// How long to wait for Uptime Kuma to send its initial data after connecting.
+    #[serde_inline_default(10.0)]
+    pub ready_timeout: f64,
+
+    /// How long getters like [get_monitors](crate::Client::get_monitors) wait for the initial data (e.g. after a reconnect)
+    /// before returning [NotReady](crate::error::Error::NotReady), `0` returns the error immediately.
+    #[serde_inline_default(0.0)]
+    pub ready_wait_timeout: f64,
+
+    /// Don't wait for the monitor list when connecting, this speeds up connecting to instances with many monitors
+    /// when only single monitors are accessed, but [get_monitors](crate::Client::get_monitors) has to request the list first.
+    #[serde_inline_default(false)]
+    pub lazy_monitors: bool,
+
+    /// How many times a call should be retried after a transient error (e.g. a timeout or a connection problem).
+    #[serde_inline_default(0)]
+    pub call_max_retries: u32,
+
+    /// Which Uptime Kuma API to use, controls whether properties and monitor types added in Uptime Kuma 2.0 are sent to the server.
+    #[serde_inline_default(ApiVersion::Auto)]
+    pub api_version: ApiVersion,
+
     /// TLS Configuration for the [Client](crate::Client).
+    #[serde(default)]
     pub tls: TlsConfig,
 }
 
+impl Default for TlsConfig {
+    fn default() -> Self {
+        Self {
+            verify: true,
+            cert: None,
+            client_cert: None,
+            client_key: None,
+        }
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
             url: Url::parse("http://localhost:3001").unwrap(),
+            base_path: None,
             username: None,
             password: None,
+            password_file: None,
             mfa_token: None,
             mfa_secret: None,
-            headers: Vec::new(),
+            mfa_secret_file: None,
+            auth_token: None,
+            auth_token_file: None,
+            headers: HashMap::new(),
             connect_timeout: 30.0,
             call_timeout: 30.0,
+            ready_timeout: 10.0,
+            ready_wait_timeout: 0.0,
+            lazy_monitors: false,
+            call_max_retries: 0,
+            api_version: ApiVersion::Auto,
             tls: TlsConfig::default(),
         }
     }
 }
+
+fn read_secret(
+    name: &str,
+    value: &Option<String>,
+    file: &Option<String>,
+) -> Result<Option<String>> {
+    match (value, file) {
+        (Some(_), Some(_)) => Err(Error::InvalidConfig(format!(
+            "Only one of '{name}' and '{name}_file' can be set"
+        ))),
+        (None, Some(file)) => std::fs::read_to_string(file)
+            .map(|content| Some(content.trim_end_matches(['\r', '\n']).to_owned()))
+            .map_err(|e| Error::InvalidConfig(format!("Unable to read '{name}_file' {file}: {e}"))),
+        (value, None) => Ok(value.clone()),
+    }
+}
+
+impl Config {
+    /// Creates a [ConfigBuilder] to construct a validated [Config].
+    ///
+    /// ```
+    /// # use kuma_client::Config;
+    /// let config = Config::builder()
+    ///     .url("http://localhost:3001")
+    ///     .basic_auth("admin", "password")
+    ///     .call_timeout(60.0)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::default()
+    }
+
+    /// Checks the config for invalid values and conflicting options.
+    pub fn validate(&self) -> Result<()> {
+        if !matches!(self.url.scheme(), "http" | "https") {
+            return Err(Error::InvalidConfig(format!(
+                "Unsupported url scheme '{}', expected 'http' or 'https'",
+                self.url.scheme()
+            )));
+        }
+
+        for (name, value, file) in [
+            ("password", &self.password, &self.password_file),
+            ("mfa_secret", &self.mfa_secret, &self.mfa_secret_file),
+            ("auth_token", &self.auth_token, &self.auth_token_file),
+        ] {
+            if value.is_some() && file.is_some() {
+                return Err(Error::InvalidConfig(format!(
+                    "Only one of '{name}' and '{name}_file' can be set"
+                )));
+            }
+        }
+
+        if self.mfa_token.is_some() && (self.mfa_secret.is_some() || self.mfa_secret_file.is_some())
+        {
+            return Err(Error::InvalidConfig(
+                "Only one of 'mfa_token' and 'mfa_secret' can be set".to_owned(),
+            ));
+        }
+
+        if self.tls.client_cert.is_some() != self.tls.client_key.is_some() {
+            return Err(Error::InvalidConfig(
+                "Both 'tls.client_cert' and 'tls.client_key' need to be set".to_owned(),
+            ));
+        }
+
+        for (key, value) in &self.headers {
+            if HeaderName::from_bytes(key.as_bytes()).is_err()
+                || HeaderValue::from_str(value).is_err()
+            {
+                return Err(Error::InvalidConfig(format!(
+                    "Invalid header '{}: {}'",
+                    key, value
+                )));
+            }
+        }
+
+        for (name, timeout) in [
+            ("connect_timeout", self.connect_timeout),
+            ("call_timeout", self.call_timeout),
+            ("ready_timeout", self.ready_timeout),
+        ] {
+            if timeout <= 0.0 || timeout.is_nan() {
+                return Err(Error::InvalidConfig(format!(
+                    "'{name}' needs to be greater than 0"
+                )));
+            }
+        }
+
+        if self.ready_wait_timeout < 0.0 || self.ready_wait_timeout.is_nan() {
+            return Err(Error::InvalidConfig(
+                "'ready_wait_timeout' can't be negative".to_owned(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// The url Uptime Kuma is served under, i.e. the [url](Self::url) with the [base_path](Self::base_path) applied, always ending with a `/`.
+    pub fn base_url(&self) -> Url {
+        let mut url = self.url.clone();
+        url.set_path(self.base_path.as_deref().unwrap_or("/"));
+
+        if !url.path().ends_with('/') {
+            url.set_path(&format!("{}/", url.path()));
+        }
+
+        url
+    }
+
+    /// Resolves all `*_file` options by reading the referenced files.
+    pub(crate) fn resolve_secrets(mut self) -> Result<Self> {
+        self.password = read_secret("password", &self.password, &self.password_file)?;
+        self.mfa_secret = read_secret("mfa_secret", &self.mfa_secret, &self.mfa_secret_file)?;
+        self.auth_token = read_secret("auth_token", &self.auth_token, &self.auth_token_file)?;
+
+        Ok(self)
+    }
+}
+
+/// Builder for a [Config], created using [Config::builder].
+///
+/// All options which are not set keep their [default](Config::default) value.
+#[derive(Clone, Debug, Default)]
+pub struct ConfigBuilder {
+    url: Option<String>,
+    config: Config,
+}
+
+impl ConfigBuilder {
+    /// The URL for connecting to Uptime Kuma, see [Config::url].
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    /// The path Uptime Kuma is served under, see [Config::base_path].
+    pub fn base_path(mut self, base_path: impl Into<String>) -> Self {
+        self.config.base_path = Some(base_path.into());
+        self
+    }
+
+    /// The username and password for logging into Uptime Kuma.
+    pub fn basic_auth(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+        self.config.username = Some(username.into());
+        self.config.password = Some(password.into());
+        self
+    }
+
+    /// A token from a previous login, see [Config::auth_token].
+    ///
+    /// If [basic_auth](Self::basic_auth) is set as well, it is used when logging in with the token fails.
+    pub fn token(mut self, auth_token: impl Into<String>) -> Self {
+        self.config.auth_token = Some(auth_token.into());
+        self
+    }
+
+    /// A single use MFA token, see [Config::mfa_token].
+    pub fn mfa_token(mut self, mfa_token: impl Into<String>) -> Self {
+        self.config.mfa_token = Some(mfa_token.into());
+        self
+    }
+
+    /// The MFA secret used to generate tokens, see [Config::mfa_secret].
+    pub fn mfa_secret(mut self, mfa_secret: impl Into<String>) -> Self {
+        self.config.mfa_secret = Some(mfa_secret.into());
+        self
+    }
+
+    /// Whether to verify the TLS certificate, see [TlsConfig::verify].
+    pub fn tls_verify(mut self, verify: bool) -> Self {
+        self.config.tls.verify = verify;
+        self
+    }
+
+    /// The path to a custom tls certificate in PEM format, see [TlsConfig::cert].
+    pub fn tls_cert(mut self, cert: impl Into<String>) -> Self {
+        self.config.tls.cert = Some(cert.into());
+        self
+    }
+
+    /// The paths to a client certificate and its private key in PEM format, see [TlsConfig::client_cert].
+    pub fn tls_client_cert(mut self, cert: impl Into<String>, key: impl Into<String>) -> Self {
+        self.config.tls.client_cert = Some(cert.into());
+        self.config.tls.client_key = Some(key.into());
+        self
+    }
+
+    /// Adds an HTTP header to send when connecting to Uptime Kuma.
+    pub fn header(mut self, key: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        self.config
+            .headers
+            .insert(key.as_ref().to_owned(), value.as_ref().to_owned());
+        self
+    }
+
+    /// Adds multiple HTTP headers to send when connecting to Uptime Kuma.
+    pub fn headers<K: AsRef<str>, V: AsRef<str>>(
+        self,
+        headers: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        headers
+            .into_iter()
+            .fold(self, |builder, (key, value)| builder.header(key, value))
+    }
+
+    /// The timeout in seconds for the initial connection, see [Config::connect_timeout].
+    pub fn connect_timeout(mut self, timeout: f64) -> Self {
+        self.config.connect_timeout = timeout;
+        self
+    }
+
+    /// The timeout in seconds for calls to the Uptime Kuma server, see [Config::call_timeout].
+    pub fn call_timeout(mut self, timeout: f64) -> Self {
+        self.config.call_timeout = timeout;
+        self
+    }
+
+    /// How long getters wait for the initial data in seconds, see [Config::ready_wait_timeout].
+    pub fn ready_wait_timeout(mut self, timeout: f64) -> Self {
+        self.config.ready_wait_timeout = timeout;
+        self
+    }
+
+    /// Which Uptime Kuma API to use, see [Config::api_version].
+    pub fn api_version(mut self, api_version: ApiVersion) -> Self {
+        self.config.api_version = api_version;
+        self
+    }
+
+    /// How many times a call should be retried after a transient error, see [Config::call_max_retries].
+    pub fn call_max_retries(mut self, retries: u32) -> Self {
+        self.config.call_max_retries = retries;
+        self
+    }
+
+    /// Validates the options and creates the [Config].
+    pub fn build(self) -> Result<Config> {
+        let mut config = self.config;
+
+        if let Some(url) = self.url {
+            config.url = Url::parse(&url)
+                .map_err(|e| Error::InvalidConfig(format!("Invalid url '{}': {}", url, e)))?;
+        }
+
+        config.validate()?;
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(url: &str, base_path: Option<&str>) -> Config {
+        Config {
+            url: Url::parse(url).unwrap(),
+            base_path: base_path.map(|path| path.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn base_url_ignores_the_url_path_by_default() {
+        let config = config("https://host/kuma/", None);
+
+        assert_eq!(config.base_url().as_str(), "https://host/");
+        assert_eq!(
+            config.base_url().join("socket.io/").unwrap().as_str(),
+            "https://host/socket.io/"
+        );
+    }
+
+    #[test]
+    fn base_url_adds_a_trailing_slash() {
+        assert_eq!(
+            config("https://host", Some("/kuma")).base_url().as_str(),
+            "https://host/kuma/"
+        );
+        assert_eq!(
+            config("https://host", None).base_url().as_str(),
+            "https://host/"
+        );
+    }
+
+    #[test]
+    fn base_path_replaces_the_url_path() {
+        assert_eq!(
+            config("https://host/other/", Some("/kuma"))
+                .base_url()
+                .join("api/status-page/main")
+                .unwrap()
+                .as_str(),
+            "https://host/kuma/api/status-page/main"
+        );
+    }
+
+    #[test]
+    fn invalid_headers_are_rejected() {
+        let with_header = |key: &str, value: &str| Config {
+            headers: HashMap::from([(key.to_owned(), value.to_owned())]),
+            ..Default::default()
+        };
+
+        assert!(with_header("X-Forwarded-For", "10.0.0.1, 10.0.0.2")
+            .validate()
+            .is_ok());
+        assert!(with_header("Invalid Header", "value").validate().is_err());
+        assert!(with_header("X-Header", "line\nbreak").validate().is_err());
+    }
+}
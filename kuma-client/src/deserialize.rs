@@ -5,7 +5,7 @@ use serde::{
     Deserialize, Deserializer, Serialize,
 };
 use serde_json::Value;
-use serde_with::{DeserializeAs, SerializeAs};
+use serde_with::{ser::SerializeAsWrap, DeserializeAs, SerializeAs};
 use std::{collections::HashMap, hash::Hash, marker::PhantomData, str::FromStr};
 use time::{format_description::well_known::Iso8601, PrimitiveDateTime, Time};
 
@@ -56,6 +56,95 @@ where
     }
 }
 
+/// Deserializes a duration in seconds, either from a number or a string containing a number or a
+/// humantime-style duration like `5m` or `1h 30m`, durations are rounded down to whole seconds.
+pub struct DeserializeDurationLenient;
+
+impl<'de, T> DeserializeAs<'de, T> for DeserializeDurationLenient
+where
+    T: TryFrom<i64>,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer).map_err(serde::de::Error::custom)?;
+        let seconds = match &value {
+            Value::Number(n) => n.as_i64(),
+            Value::String(s) => s.trim().parse::<i64>().ok().or_else(|| {
+                humantime::parse_duration(s.trim())
+                    .ok()
+                    .and_then(|duration| duration.as_secs().try_into().ok())
+            }),
+            _ => {
+                return Err(serde::de::Error::custom(
+                    "Unexpected type for deserialization",
+                ))
+            }
+        };
+
+        seconds
+            .and_then(|seconds| seconds.try_into().ok())
+            .ok_or_else(|| {
+                serde::de::Error::custom(format!(
+                    "Unable to parse {} as a duration in seconds of type {}",
+                    value,
+                    std::any::type_name::<T>()
+                ))
+            })
+    }
+}
+
+impl<T> SerializeAs<T> for DeserializeDurationLenient
+where
+    T: Serialize,
+{
+    fn serialize_as<S>(source: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        source.serialize(serializer)
+    }
+}
+
+/// Treats empty strings and `null` as `None`, any other value is deserialized using `T`.
+pub struct DeserializeOptionLenient<T>(PhantomData<T>);
+
+impl<'de, T, U> DeserializeAs<'de, Option<U>> for DeserializeOptionLenient<T>
+where
+    T: DeserializeAs<'de, U>,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<Option<U>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer).map_err(serde::de::Error::custom)?;
+
+        match value {
+            Value::Null => Ok(None),
+            Value::String(s) if s.trim().is_empty() => Ok(None),
+            value => T::deserialize_as(value)
+                .map(Some)
+                .map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+impl<T, U> SerializeAs<Option<U>> for DeserializeOptionLenient<T>
+where
+    T: SerializeAs<U>,
+{
+    fn serialize_as<S>(source: &Option<U>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match source {
+            Some(value) => serializer.serialize_some(&SerializeAsWrap::<U, T>::new(value)),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
 pub struct DeserializeBoolLenient;
 
 impl<'de> DeserializeAs<'de, bool> for DeserializeBoolLenient {
@@ -177,6 +266,65 @@ where
     }
 }
 
+/// HTTP headers given as a map, a list of entries or a comma separated string of entries.
+///
+/// Entries use either the `key=value` or the `Name: value` form, split at whichever separator comes first.
+/// Values containing a `,` can only be given using the map or list form.
+pub struct DeserializeHeadersLenient;
+
+impl<'de> DeserializeAs<'de, HashMap<String, String>> for DeserializeHeadersLenient {
+    fn deserialize_as<D>(deserializer: D) -> Result<HashMap<String, String>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let parse_entry = |entry: &str| {
+            entry
+                .split_once(['=', ':'])
+                .map(|(key, value)| (key.trim().to_owned(), value.trim().to_owned()))
+                .ok_or_else(|| {
+                    serde::de::Error::custom(format!(
+                        "Invalid header '{}', expected 'key=value' or 'Name: value'",
+                        entry
+                    ))
+                })
+        };
+
+        let value = Value::deserialize(deserializer).map_err(serde::de::Error::custom)?;
+
+        match value {
+            Value::Object(_) => HashMap::<String, String>::deserialize(value.into_deserializer())
+                .map_err(serde::de::Error::custom),
+            Value::Array(entries) => entries
+                .iter()
+                .map(|entry| match entry {
+                    Value::String(entry) => parse_entry(entry),
+                    _ => Err(serde::de::Error::custom(
+                        "Unexpected type for deserialization",
+                    )),
+                })
+                .collect(),
+            Value::String(s) => s
+                .split(',')
+                .filter(|entry| !entry.trim().is_empty())
+                .map(parse_entry)
+                .collect(),
+            Value::Null => Ok(HashMap::new()),
+            _ => Err(serde::de::Error::custom(
+                "Unexpected type for deserialization",
+            )),
+        }
+    }
+}
+
+impl SerializeAs<HashMap<String, String>> for DeserializeHeadersLenient {
+    fn serialize_as<S>(source: &HashMap<String, String>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        source.serialize(serializer)
+    }
+}
+
 pub struct DeserializeValueLenient;
 
 impl<'de> DeserializeAs<'de, Value> for DeserializeValueLenient {
@@ -290,9 +438,59 @@ impl SerializeAs<Option<Range<PrimitiveDateTime>>> for SerializeDateRange {
 pub struct TimePoint {
     pub hours: u8,
     pub minutes: u8,
+    #[serde(default)]
     pub seconds: Option<u8>,
 }
 
+impl FromStr for TimePoint {
+    type Err = String;
+
+    /// Parses a time point in the format `HH:MM` or `HH:MM:SS`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s
+            .trim()
+            .split(':')
+            .map(|part| part.parse::<u8>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Unable to parse time '{}': {}", s, e))?;
+
+        match parts[..] {
+            [hours, minutes] => Ok(TimePoint {
+                hours,
+                minutes,
+                seconds: None,
+            }),
+            [hours, minutes, seconds] => Ok(TimePoint {
+                hours,
+                minutes,
+                seconds: Some(seconds),
+            }),
+            _ => Err(format!(
+                "Unable to parse time '{}', expected HH:MM or HH:MM:SS",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TimePointLenient {
+    Object(TimePoint),
+    String(String),
+}
+
+impl TryFrom<TimePointLenient> for TimePoint {
+    type Error = String;
+
+    fn try_from(value: TimePointLenient) -> Result<Self, Self::Error> {
+        match value {
+            TimePointLenient::Object(time) => Ok(time),
+            TimePointLenient::String(s) => s.parse(),
+        }
+    }
+}
+
 pub struct SerializeTimeRange;
 
 impl<'de> DeserializeAs<'de, Range<Time>> for SerializeTimeRange {
@@ -300,11 +498,14 @@ impl<'de> DeserializeAs<'de, Range<Time>> for SerializeTimeRange {
     where
         D: Deserializer<'de>,
     {
-        let value = Vec::<TimePoint>::deserialize(deserializer)?
+        let value = Vec::<TimePointLenient>::deserialize(deserializer)?
             .into_iter()
-            .map(|time| Time::from_hms(time.hours, time.minutes, time.seconds.unwrap_or_default()))
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(serde::de::Error::custom)?;
+            .map(|time| {
+                let time = TimePoint::try_from(time).map_err(serde::de::Error::custom)?;
+                Time::from_hms(time.hours, time.minutes, time.seconds.unwrap_or_default())
+                    .map_err(serde::de::Error::custom)
+            })
+            .collect::<Result<Vec<_>, D::Error>>()?;
 
         if value.len() != 2 {
             return Err(serde::de::Error::custom(format!(
@@ -337,3 +538,258 @@ impl SerializeAs<Range<Time>> for SerializeTimeRange {
         seq.end()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::Monitor;
+    use serde_json::json;
+    use serde_with::serde_as;
+
+    #[serde_as]
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct OptionalNumber {
+        #[serde(default)]
+        #[serde_as(as = "DeserializeOptionLenient<DeserializeNumberLenient>")]
+        value: Option<i32>,
+    }
+
+    #[serde_as]
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct TimeRange {
+        #[serde_as(as = "SerializeTimeRange")]
+        value: Range<Time>,
+    }
+
+    #[serde_as]
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Headers {
+        #[serde(default)]
+        #[serde_as(as = "DeserializeHeadersLenient")]
+        value: HashMap<String, String>,
+    }
+
+    #[serde_as]
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Seconds {
+        #[serde_as(as = "DeserializeDurationLenient")]
+        value: i32,
+    }
+
+    fn duration(value: Value) -> Result<i32, serde_json::Error> {
+        serde_json::from_value::<Seconds>(json!({ "value": value })).map(|seconds| seconds.value)
+    }
+
+    fn headers(value: Value) -> HashMap<String, String> {
+        serde_json::from_value::<Headers>(json!({ "value": value }))
+            .unwrap()
+            .value
+    }
+
+    fn optional_number(value: Value) -> Option<i32> {
+        serde_json::from_value::<OptionalNumber>(json!({ "value": value }))
+            .unwrap()
+            .value
+    }
+
+    #[test]
+    fn option_lenient() {
+        assert_eq!(optional_number(json!("")), None);
+        assert_eq!(optional_number(json!("0")), Some(0));
+        assert_eq!(optional_number(json!(0)), Some(0));
+        assert_eq!(optional_number(json!(null)), None);
+        assert_eq!(
+            serde_json::from_value::<OptionalNumber>(json!({}))
+                .unwrap()
+                .value,
+            None
+        );
+        assert!(serde_json::from_value::<OptionalNumber>(json!({"value": "abc"})).is_err());
+    }
+
+    #[test]
+    fn option_lenient_monitor_fields() {
+        let monitor = serde_json::from_value::<Monitor>(json!({
+            "type": "http",
+            "name": "Example",
+            "url": "https://example.com",
+            "proxyId": "",
+            "resendInterval": null,
+        }))
+        .unwrap();
+
+        let Monitor::Http { value } = monitor else {
+            panic!("Expected an http monitor, got {:?}", monitor);
+        };
+        assert_eq!(value.proxy_id, None);
+        assert_eq!(value.resend_interval, None);
+
+        let monitor = serde_json::from_value::<Monitor>(json!({
+            "type": "dns",
+            "name": "Example",
+            "hostname": "example.com",
+            "port": "",
+        }))
+        .unwrap();
+
+        let Monitor::Dns { value } = monitor else {
+            panic!("Expected a dns monitor, got {:?}", monitor);
+        };
+        assert_eq!(value.port, None);
+    }
+
+    fn time_range(value: Value) -> Range<Time> {
+        serde_json::from_value::<TimeRange>(json!({ "value": value }))
+            .unwrap()
+            .value
+    }
+
+    #[test]
+    fn time_range_lenient() {
+        let expected = Range {
+            start: Time::from_hms(2, 0, 0).unwrap(),
+            end: Time::from_hms(3, 30, 15).unwrap(),
+        };
+
+        assert_eq!(
+            time_range(
+                json!([{"hours": 2, "minutes": 0}, {"hours": 3, "minutes": 30, "seconds": 15}])
+            ),
+            expected
+        );
+        assert_eq!(time_range(json!(["02:00", "03:30:15"])), expected);
+        assert_eq!(
+            time_range(json!([{"hours": 2, "minutes": 0, "seconds": null}, "3:30:15"])),
+            expected
+        );
+
+        assert!(serde_json::from_value::<TimeRange>(json!({"value": ["02:00"]})).is_err());
+        assert!(serde_json::from_value::<TimeRange>(json!({"value": ["02", "03:00"]})).is_err());
+        assert!(serde_json::from_value::<TimeRange>(json!({"value": ["25:00", "03:00"]})).is_err());
+    }
+
+    #[test]
+    fn time_range_serializes_seconds() {
+        let value = serde_json::to_value(TimeRange {
+            value: time_range(json!(["02:00", "03:30"])),
+        })
+        .unwrap();
+
+        assert_eq!(
+            value,
+            json!({"value": [
+                {"hours": 2, "minutes": 0, "seconds": 0},
+                {"hours": 3, "minutes": 30, "seconds": 0},
+            ]})
+        );
+    }
+
+    #[test]
+    fn maintenance_list_without_seconds() {
+        let maintenances = serde_json::from_value::<crate::maintenance::MaintenanceList>(
+            crate::test_util::fixtures::maintenance_list(),
+        )
+        .unwrap();
+
+        let Some(crate::maintenance::Maintenance::RecurringWeekday { schedule, .. }) =
+            maintenances.get("1")
+        else {
+            panic!(
+                "Expected a recurring-weekday maintenance, got {:?}",
+                maintenances
+            );
+        };
+
+        assert_eq!(
+            schedule.time_range,
+            Some(Range {
+                start: Time::from_hms(2, 0, 0).unwrap(),
+                end: Time::from_hms(3, 0, 0).unwrap(),
+            })
+        );
+    }
+
+    #[test]
+    fn headers_lenient() {
+        let expected = HashMap::from([
+            (
+                "Authorization".to_owned(),
+                "Basic dXNlcjpwYXNz==".to_owned(),
+            ),
+            (
+                "X-Forwarded-For".to_owned(),
+                "10.0.0.1, 10.0.0.2".to_owned(),
+            ),
+        ]);
+
+        assert_eq!(
+            headers(json!({
+                "Authorization": "Basic dXNlcjpwYXNz==",
+                "X-Forwarded-For": "10.0.0.1, 10.0.0.2",
+            })),
+            expected
+        );
+        assert_eq!(
+            headers(json!([
+                "Authorization=Basic dXNlcjpwYXNz==",
+                "X-Forwarded-For=10.0.0.1, 10.0.0.2",
+            ])),
+            expected
+        );
+        assert_eq!(
+            headers(json!("X-First=1, X-Second=a=b")),
+            HashMap::from([
+                ("X-First".to_owned(), "1".to_owned()),
+                ("X-Second".to_owned(), "a=b".to_owned()),
+            ])
+        );
+        assert_eq!(
+            headers(json!([
+                "Authorization: Basic dXNlcjpwYXNz==",
+                "X-Forwarded-For: 10.0.0.1, 10.0.0.2",
+            ])),
+            expected
+        );
+        assert_eq!(
+            headers(json!("X-Api-Key: a=b, X-Second=c:d")),
+            HashMap::from([
+                ("X-Api-Key".to_owned(), "a=b".to_owned()),
+                ("X-Second".to_owned(), "c:d".to_owned()),
+            ])
+        );
+        assert_eq!(headers(json!(null)), HashMap::new());
+        assert!(serde_json::from_value::<Headers>(json!({"value": ["missing-value"]})).is_err());
+    }
+
+    #[test]
+    fn duration_lenient() {
+        assert_eq!(duration(json!("90")).unwrap(), 90);
+        assert_eq!(duration(json!(90)).unwrap(), 90);
+        assert_eq!(duration(json!(" 90 ")).unwrap(), 90);
+        assert_eq!(duration(json!("5m")).unwrap(), 300);
+        assert_eq!(duration(json!("1h30m")).unwrap(), 5400);
+        assert_eq!(duration(json!("1500ms")).unwrap(), 1);
+        assert!(duration(json!("soon")).is_err());
+        assert!(duration(json!(true)).is_err());
+        assert!(duration(json!(i64::MAX)).is_err());
+    }
+
+    #[test]
+    fn duration_lenient_monitor_fields() {
+        let monitor = serde_json::from_value::<Monitor>(json!({
+            "type": "http",
+            "name": "Example",
+            "url": "https://example.com",
+            "interval": "5m",
+            "retry_interval": "1h30m",
+        }))
+        .unwrap();
+
+        assert_eq!(monitor.common().interval(), &Some(300));
+        assert_eq!(monitor.common().retry_interval(), &Some(5400));
+
+        let serialized = serde_json::to_value(&monitor).unwrap();
+        assert_eq!(serialized["interval"], json!(300));
+        assert_eq!(serialized["retryInterval"], json!(5400));
+    }
+}
@@ -5,6 +5,10 @@ use thiserror::Error;
 /// Custom error type for handling various errors in the kuma_client library.
 #[derive(Error, Debug)]
 pub enum Error {
+    /// The config contains invalid or conflicting options.
+    #[error("Invalid config: {0}")]
+    InvalidConfig(String),
+
     /// The config contains an invalid url.
     #[error("Invalid URL: {0}")]
     InvalidUrl(String),
@@ -13,6 +17,10 @@ pub enum Error {
     #[error("Timeout while trying to connect to Uptime Kuma server")]
     ConnectionTimeout,
 
+    /// The connection to the Uptime Kuma server could not be established, e.g. because of a TLS or handshake error.
+    #[error("Unable to connect to Uptime Kuma server: {0}")]
+    ConnectFailed(String),
+
     /// Timeout error while trying to call a specific function.
     #[error("Timeout while trying to call '{0}'.")]
     CallTimeout(String),
@@ -41,9 +49,18 @@ pub enum Error {
     #[error("Received invalid response from server (missing key '{1}'): {0:?}")]
     InvalidResponse(Vec<serde_json::Value>, String),
 
-    /// The server responded with an unexpected error.
-    #[error("Server responded with an error: {0}")]
-    ServerError(String),
+    /// A response or event from the server could not be deserialized.
+    #[error("Unable to deserialize response of '{method}' at '{pointer}': {source}")]
+    DeserializeResponse {
+        method: String,
+        pointer: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// The server responded with an unexpected error, `raw` contains the complete error response.
+    #[error("Server responded with an error: {msg}")]
+    ServerError { msg: String, raw: serde_json::Value },
 
     /// Unsupported message received from the server.
     #[error("Received unsupported message from server")]
@@ -78,6 +95,39 @@ pub enum Error {
     Totp(#[from] TotpError),
 }
 
+/// The messages Uptime Kuma responds with when the requested entity doesn't exist.
+///
+/// The handlers don't check for missing entities and fail while accessing a property of the missing database row,
+/// the wording of the resulting message depends on the Node.js version.
+const NOT_FOUND_MESSAGES: &[&str] = &[
+    "Cannot read properties of null (reading 'id')",
+    "Cannot read properties of null (reading 'toJSON')",
+    "Cannot read property 'id' of null",
+    "Cannot read property 'toJSON' of null",
+];
+
+impl Error {
+    /// Whether the server responded with an error indicating the requested entity doesn't exist.
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            Error::ServerError { msg, .. } => NOT_FOUND_MESSAGES.contains(&msg.as_str()),
+            _ => false,
+        }
+    }
+
+    /// Whether a failed call to `method` is caused by a transient error and can safely be sent again.
+    ///
+    /// Calls that never reached the server can always be retried, timed out calls are only
+    /// retried for methods which don't modify any data on the server.
+    pub fn is_retryable(&self, method: &str) -> bool {
+        match self {
+            Error::CommunicationError(_) => true,
+            Error::CallTimeout(_) => method.starts_with("get"),
+            _ => false,
+        }
+    }
+}
+
 /// Custom result type for handling various errors in the kuma_client library.
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -103,3 +153,38 @@ pub enum TotpError {
 
 /// Custom result type for handling totp_rs errors.
 pub type TotpResult<T> = std::result::Result<T, TotpError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn server_error(msg: &str) -> Error {
+        Error::ServerError {
+            msg: msg.to_owned(),
+            raw: json!({"ok": false, "msg": msg}),
+        }
+    }
+
+    #[test]
+    fn missing_rows_are_not_found() {
+        for msg in NOT_FOUND_MESSAGES {
+            assert!(server_error(msg).is_not_found(), "{}", msg);
+        }
+    }
+
+    #[test]
+    fn other_errors_are_not_not_found() {
+        for msg in [
+            "Cannot read properties of undefined (reading 'id')",
+            "Cannot read property 'length' of undefined",
+            "Cannot read properties of null (reading 'length')",
+            "Notification not found in the list",
+            "You do not own this monitor.",
+        ] {
+            assert!(!server_error(msg).is_not_found(), "{}", msg);
+        }
+
+        assert!(!Error::CallTimeout("getMonitor".to_owned()).is_not_found());
+    }
+}
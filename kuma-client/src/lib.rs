@@ -53,6 +53,9 @@ pub mod models;
 #[doc(hidden)]
 pub mod util;
 
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+
 #[doc(inline)]
 pub use client::*;
 #[doc(inline)]
@@ -0,0 +1,22 @@
+//! Models related to Uptime Kuma backups
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+/// Defines how existing monitors and notifications are handled when importing a backup.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, EnumString, Display,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum ImportHandle {
+    /// Skip entries which already exist.
+    #[default]
+    Skip,
+
+    /// Keep both, the existing and the imported entries.
+    Keep,
+
+    /// Replace all existing entries with the imported ones.
+    Overwrite,
+}
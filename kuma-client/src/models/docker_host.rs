@@ -1,6 +1,9 @@
 //! Models related to Uptime Kuma docker hosts
 
-use crate::deserialize::DeserializeNumberLenient;
+use crate::{
+    deserialize::DeserializeNumberLenient,
+    error::{Error, Result},
+};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, skip_serializing_none};
 
@@ -47,6 +50,53 @@ impl DockerHost {
     pub fn new() -> Self {
         Default::default()
     }
+
+    pub fn validate(&self, id: impl AsRef<str>) -> Result<()> {
+        let mut errors = vec![];
+
+        if self.name.is_none() {
+            errors.push("Missing property 'name'".to_owned());
+        }
+
+        let host = self.host.as_deref().map(str::trim).unwrap_or_default();
+
+        match self.connection_type {
+            None => errors.push("Missing property 'connection_type'".to_owned()),
+            Some(DockerConnectionType::Socket) if host.is_empty() => errors
+                .push("A socket docker host requires the path to the socket in 'host'".to_owned()),
+            Some(DockerConnectionType::Tcp) if host.is_empty() => errors.push(
+                "A tcp docker host requires a daemon url in 'host', e.g. 'tcp://localhost:2375'"
+                    .to_owned(),
+            ),
+            Some(DockerConnectionType::Tcp) => match url::Url::parse(host) {
+                Ok(url) if !["tcp", "http", "https"].contains(&url.scheme()) => {
+                    errors.push(format!(
+                        "Invalid daemon url '{}', expected a tcp://, http:// or https:// url",
+                        host
+                    ))
+                }
+                Ok(url) if url.host_str().is_none() => {
+                    errors.push(format!("Invalid daemon url '{}', missing host", host))
+                }
+                Ok(_) => {}
+                Err(e) => errors.push(format!("Invalid daemon url '{}': {}", host, e)),
+            },
+            Some(DockerConnectionType::Socket) => {}
+        }
+
+        if !errors.is_empty() {
+            return Err(Error::ValidationError(id.as_ref().to_owned(), errors));
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn validation_id(&self) -> String {
+        self.name
+            .clone()
+            .or_else(|| self.id.map(|id| id.to_string()))
+            .unwrap_or_else(|| "docker host".to_owned())
+    }
 }
 
 /// A list of docker hosts.
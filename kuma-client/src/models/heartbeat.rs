@@ -0,0 +1,69 @@
+//! Models related to Uptime Kuma heartbeats
+
+use crate::deserialize::{DeserializeBoolLenient, DeserializeNumberLenient};
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use serde_with::{serde_as, skip_serializing_none};
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum HeartbeatStatus {
+    Down = 0,
+    Up = 1,
+    Pending = 2,
+    Maintenance = 3,
+}
+
+#[skip_serializing_none]
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Heartbeat {
+    #[serde(rename = "id")]
+    #[serde_as(as = "Option<DeserializeNumberLenient>")]
+    pub id: Option<i32>,
+
+    #[serde(rename = "monitorID", alias = "monitor_id")]
+    #[serde_as(as = "Option<DeserializeNumberLenient>")]
+    pub monitor_id: Option<i32>,
+
+    #[serde(rename = "status")]
+    pub status: Option<HeartbeatStatus>,
+
+    #[serde(rename = "time")]
+    pub time: Option<String>,
+
+    #[serde(rename = "msg")]
+    pub msg: Option<String>,
+
+    #[serde(rename = "ping")]
+    pub ping: Option<f64>,
+
+    #[serde(rename = "important")]
+    #[serde_as(as = "Option<DeserializeBoolLenient>")]
+    pub important: Option<bool>,
+
+    #[serde(rename = "duration")]
+    #[serde_as(as = "Option<DeserializeNumberLenient>")]
+    pub duration: Option<i64>,
+}
+
+/// Uptime statistics Uptime Kuma pushes for a monitor.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Uptime {
+    /// Average response time in milliseconds over the last 24 hours.
+    pub avg_ping: Option<f64>,
+
+    /// Uptime ratio (`0.0` - `1.0`) over the last 24 hours.
+    pub uptime_24h: Option<f64>,
+
+    /// Uptime ratio (`0.0` - `1.0`) over the last 30 days.
+    pub uptime_30d: Option<f64>,
+}
+
+/// The most recent heartbeats of each monitor, keyed by monitor id.
+pub type HeartbeatList = HashMap<i32, Vec<Heartbeat>>;
+
+/// Uptime statistics of each monitor, keyed by monitor id.
+pub type UptimeList = HashMap<i32, Uptime>;
@@ -1,8 +1,12 @@
 //! Models related to Uptime Kuma maintenances
 
-use crate::deserialize::{
-    DeserializeBoolLenient, DeserializeNumberLenient, SerializeDateRange, SerializeTimeRange,
+use crate::{
+    deserialize::{
+        DeserializeBoolLenient, DeserializeNumberLenient, SerializeDateRange, SerializeTimeRange,
+    },
+    error::Error,
 };
+use derivative::Derivative;
 use serde::{
     de::{self, MapAccess, Visitor},
     ser::SerializeStruct,
@@ -252,7 +256,8 @@ pub struct Range<T> {
 #[serde_inline_default]
 #[skip_serializing_none]
 #[serde_as]
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Derivative, Serialize, Deserialize)]
+#[derivative(PartialEq)]
 pub struct MaintenanceCommon {
     #[serde(rename = "id")]
     #[serde_as(as = "Option<DeserializeNumberLenient>")]
@@ -276,6 +281,12 @@ pub struct MaintenanceCommon {
     #[serde(default)]
     pub monitors: Option<Vec<MaintenanceMonitor>>,
 
+    #[cfg(feature = "private-api")]
+    #[serde(rename = "monitor_names")]
+    #[derivative(PartialEq = "ignore")]
+    #[serde_as(as = "Option<crate::deserialize::DeserializeVecLenient<String>>")]
+    pub monitor_names: Option<Vec<String>>,
+
     #[serde(rename = "statusPages")]
     #[serde(default)]
     pub status_pages: Option<Vec<MaintenanceStatusPage>>,
@@ -437,6 +448,158 @@ impl Maintenance {
             Maintenance::RecurringDayOfMonth { common, .. } => common,
         }
     }
+
+    pub fn schedule(&self) -> Option<&MaintenanceSchedule> {
+        match self {
+            Maintenance::Manual { .. } => None,
+            Maintenance::Single { schedule, .. } => Some(schedule),
+            Maintenance::Cron { schedule, .. } => Some(schedule),
+            Maintenance::RecurringInterval { schedule, .. } => Some(schedule),
+            Maintenance::RecurringWeekday { schedule, .. } => Some(schedule),
+            Maintenance::RecurringDayOfMonth { schedule, .. } => Some(schedule),
+        }
+    }
+
+    pub fn validate(&self, id: impl AsRef<str>) -> crate::error::Result<()> {
+        let mut errors = vec![];
+
+        if self.common().title.is_none() {
+            errors.push("Missing property 'title'".to_owned());
+        }
+
+        if let Some(Range { start, end }) = self
+            .schedule()
+            .and_then(|schedule| schedule.date_range.as_ref())
+        {
+            if start > end {
+                errors.push(format!(
+                    "Invalid date_range, start ({}) is after end ({})",
+                    start, end
+                ));
+            }
+        }
+
+        match self {
+            Maintenance::Cron { cron, .. } => {
+                match cron.cron.as_deref().map(str::trim) {
+                    None | Some("") => errors.push("Missing property 'cron'".to_owned()),
+                    Some(expression) => {
+                        if let Err(e) = validate_cron(expression) {
+                            errors.push(format!("Invalid cron expression '{}': {}", expression, e));
+                        }
+                    }
+                }
+
+                if let Some(duration_minutes) = cron.duration_minutes {
+                    if duration_minutes <= 0.0 || duration_minutes.is_nan() {
+                        errors.push(format!(
+                            "Invalid duration_minutes {}, duration_minutes should be greater than 0",
+                            duration_minutes
+                        ));
+                    }
+                }
+            }
+            Maintenance::RecurringInterval {
+                recurring_interval, ..
+            } => {
+                if recurring_interval.interval == Some(0) {
+                    errors.push("Invalid interval 0, interval should be at least 1 day".to_owned());
+                }
+            }
+            Maintenance::RecurringWeekday {
+                recurring_weekday, ..
+            } => {
+                if recurring_weekday.weekdays.is_empty() {
+                    errors.push(
+                        "Missing property 'weekdays', at least one weekday is required".to_owned(),
+                    );
+                }
+            }
+            Maintenance::RecurringDayOfMonth {
+                recurring_day_of_month,
+                ..
+            } => {
+                if recurring_day_of_month.days_of_month.is_empty() {
+                    errors.push(
+                        "Missing property 'days_of_month', at least one day is required".to_owned(),
+                    );
+                }
+
+                for day in &recurring_day_of_month.days_of_month {
+                    if let DayOfMonth::Day(day) = day {
+                        if !(1..=31).contains(day) {
+                            errors.push(format!(
+                                "Invalid day of month {}, day should be between 1 and 31",
+                                day
+                            ));
+                        }
+                    }
+                }
+            }
+            Maintenance::Manual { .. } | Maintenance::Single { .. } => {}
+        }
+
+        if !errors.is_empty() {
+            return Err(Error::ValidationError(id.as_ref().to_owned(), errors));
+        }
+
+        Ok(())
+    }
+}
+
+/// Check a cron expression using croner like Uptime Kuma does, the seconds field is optional.
+fn validate_cron(expression: &str) -> Result<(), String> {
+    croner::Cron::new(expression)
+        .with_seconds_optional()
+        .parse()
+        .map(|_| ())
+        .map_err(|e| e.to_string())
 }
 
 pub type MaintenanceList = HashMap<String, Maintenance>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cron_accepts_sunday_as_0_and_7() {
+        assert_eq!(validate_cron("0 3 * * 0"), Ok(()));
+        assert_eq!(validate_cron("0 3 * * 7"), Ok(()));
+        assert_eq!(validate_cron("0 3 * * SUN"), Ok(()));
+        assert_eq!(validate_cron("0 3 * * 5-7"), Ok(()));
+        assert!(validate_cron("0 3 * * 8").is_err());
+    }
+
+    #[test]
+    fn cron_accepts_weekday_ranges() {
+        assert_eq!(validate_cron("30 2 * * 1-5"), Ok(()));
+        assert_eq!(validate_cron("30 2 * * MON-FRI"), Ok(()));
+        assert_eq!(validate_cron("30 2 * * 1,3,5"), Ok(()));
+        assert_eq!(validate_cron("30 2 * * 5#2"), Ok(()));
+        assert_eq!(validate_cron("30 2 * * 5L"), Ok(()));
+        assert_eq!(validate_cron("0 0 15W * *"), Ok(()));
+        assert!(validate_cron("30 2 * * 5-1").is_err());
+        assert!(validate_cron("30 2 * * 5#6").is_err());
+    }
+
+    #[test]
+    fn cron_seconds_are_optional() {
+        assert_eq!(validate_cron("*/5 * * * *"), Ok(()));
+        assert_eq!(validate_cron("30 */5 * * * *"), Ok(()));
+        assert_eq!(validate_cron("0 0 1 L * ?"), Ok(()));
+        assert_eq!(validate_cron("@daily"), Ok(()));
+        assert!(validate_cron("60 * * * * *").is_err());
+        assert!(validate_cron("* * * *").is_err());
+        assert!(validate_cron("0 0 * * * * *").is_err());
+    }
+
+    #[test]
+    fn cron_rejects_out_of_range_values() {
+        assert!(validate_cron("0 24 * * *").is_err());
+        assert!(validate_cron("0 0 0 * *").is_err());
+        assert!(validate_cron("0 0 * 13 *").is_err());
+        assert!(validate_cron("0 0 * FOO *").is_err());
+        assert!(validate_cron("*/0 * * * *").is_err());
+    }
+}
@@ -1,9 +1,12 @@
 pub(crate) mod event;
 pub(crate) mod response;
 
+pub mod backup;
 pub mod docker_host;
+pub mod heartbeat;
 pub mod maintenance;
 pub mod monitor;
 pub mod notification;
+pub mod proxy;
 pub mod status_page;
 pub mod tag;
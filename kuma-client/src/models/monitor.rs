@@ -2,18 +2,20 @@
 
 use crate::{
     deserialize::{
-        DeserializeBoolLenient, DeserializeHashMapLenient, DeserializeNumberLenient,
-        DeserializeVecLenient,
+        DeserializeBoolLenient, DeserializeDurationLenient, DeserializeHashMapLenient,
+        DeserializeNumberLenient, DeserializeOptionLenient, DeserializeVecLenient,
     },
     error::{Error, Result},
     models::tag::Tag,
 };
 use derivative::Derivative;
 use regex::Regex;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_inline_default::serde_inline_default;
+use serde_json::json;
 use serde_with::{serde_as, skip_serializing_none};
 use std::collections::{HashMap, HashSet};
+use url::Url;
 
 pub trait MonitorCommon {
     fn id(&self) -> &Option<i32>;
@@ -57,6 +59,32 @@ pub trait MonitorCommon {
     fn tag_names(&self) -> &Option<Vec<super::tag::TagValue>>;
     #[cfg(feature = "private-api")]
     fn tag_names_mut(&mut self) -> &mut Option<Vec<super::tag::TagValue>>;
+
+    /// The accepted status codes as inclusive ranges, or the first entry which isn't a valid status code or range.
+    fn accepted_status_code_ranges(&self) -> std::result::Result<Vec<(u16, u16)>, String> {
+        self.accepted_statuscodes()
+            .iter()
+            .map(|codes| parse_status_code_range(codes).ok_or_else(|| codes.clone()))
+            .collect()
+    }
+}
+
+/// Parses a status code (e.g. `404`) or an inclusive range of status codes (e.g. `200-299`) as used by `accepted_statuscodes`.
+pub fn parse_status_code_range(codes: &str) -> Option<(u16, u16)> {
+    let parse = |code: &str| {
+        let code = code.trim();
+        match !code.is_empty() && code.chars().all(|c| c.is_ascii_digit()) {
+            true => code.parse::<u16>().ok(),
+            false => None,
+        }
+    };
+
+    let (from, to) = match codes.split_once('-') {
+        Some((from, to)) => (parse(from)?, parse(to)?),
+        None => (parse(codes)?, parse(codes)?),
+    };
+
+    (from <= to).then_some((from, to))
 }
 
 macro_rules! monitor_type {
@@ -81,7 +109,7 @@ macro_rules! monitor_type {
 
             #[serde(rename = "interval")]
             #[serde_inline_default(Some(60))]
-            #[serde_as(as = "Option<DeserializeNumberLenient>")]
+            #[serde_as(as = "Option<DeserializeDurationLenient>")]
             pub interval: Option<i32>,
 
             #[serde(rename = "active")]
@@ -99,7 +127,7 @@ macro_rules! monitor_type {
             #[serde(rename = "retryInterval")]
             #[serde(alias = "retry_interval")]
             #[serde_inline_default(Some(60))]
-            #[serde_as(as = "Option<DeserializeNumberLenient>")]
+            #[serde_as(as = "Option<DeserializeDurationLenient>")]
             pub retry_interval: Option<i32>,
 
             #[serde(rename = "upsideDown")]
@@ -122,6 +150,7 @@ macro_rules! monitor_type {
             #[serde(rename = "notificationIDList")]
             #[serde(alias = "notification_id_list")]
             #[serde_as(as = "Option<DeserializeHashMapLenient<String, bool>>")]
+            #[derivative(PartialEq(compare_with = "compare_notification_id_list"))]
             pub notification_id_list: Option<HashMap<String, bool>>,
 
             #[serde(rename = "accepted_statuscodes")]
@@ -296,6 +325,25 @@ pub enum MonitorType {
     #[cfg(feature = "uptime-kuma-v2")]
     #[serde(rename = "rabbitmq")]
     RabbitMQ,
+
+    #[cfg(feature = "uptime-kuma-v2")]
+    #[serde(rename = "smtp")]
+    Smtp,
+
+    /// A monitor type which is not supported by this version of kuma-client.
+    #[serde(other)]
+    Unknown,
+}
+
+impl MonitorType {
+    /// Whether the monitor type was added in Uptime Kuma 2.0 and is unknown to older servers.
+    pub fn requires_v2(&self) -> bool {
+        match self {
+            #[cfg(feature = "uptime-kuma-v2")]
+            MonitorType::SNMP | MonitorType::RabbitMQ | MonitorType::Smtp => true,
+            _ => false,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -370,8 +418,8 @@ pub enum KafkaProducerSaslOptions {
         #[serde(alias = "secret_access_key")]
         secret_access_key: Option<String>,
 
-        #[serde(alias = "sessionToken")]
-        #[serde(rename = "session_token")]
+        #[serde(rename = "sessionToken")]
+        #[serde(alias = "session_token")]
         session_token: Option<String>,
     },
 }
@@ -384,6 +432,22 @@ fn compare_tags(a: &Vec<Tag>, b: &Vec<Tag>) -> bool {
     a.iter().collect::<HashSet<_>>() == b.iter().collect::<HashSet<_>>()
 }
 
+/// Compare the enabled notifications only, disabled entries and missing lists are equivalent.
+fn compare_notification_id_list(
+    a: &Option<HashMap<String, bool>>,
+    b: &Option<HashMap<String, bool>>,
+) -> bool {
+    let enabled = |list: &Option<HashMap<String, bool>>| {
+        list.iter()
+            .flatten()
+            .filter(|(_, enabled)| **enabled)
+            .map(|(id, _)| id.trim().to_owned())
+            .collect::<HashSet<_>>()
+    };
+
+    enabled(a) == enabled(b)
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum HttpOAuthMethod {
     #[serde(rename = "client_secret_basic")]
@@ -518,6 +582,19 @@ pub enum SNMPVersion {
     SNMPv2c,
 }
 
+#[cfg(feature = "uptime-kuma-v2")]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SmtpSecurity {
+    #[serde(rename = "secure")]
+    Secure,
+
+    #[serde(rename = "nostarttls")]
+    NoStartTls,
+
+    #[serde(rename = "starttls")]
+    StartTls,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
 pub enum HttpBodyEncoding {
     #[default]
@@ -542,6 +619,16 @@ pub enum MqttCheckType {
     JsonQuery,
 }
 
+#[cfg(feature = "uptime-kuma-v2")]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum IpFamily {
+    #[serde(rename = "ipv4")]
+    IPv4,
+
+    #[serde(rename = "ipv6")]
+    IPv6,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
 pub enum JsonPathOperator {
     #[serde(rename = ">")]
@@ -626,6 +713,7 @@ monitor_type! {
         #[serde(alias = "database_connection_string")]
         pub database_connection_string: Option<String>,
 
+        // Uptime Kuma stores the MySQL password in the radius password column
         #[serde(rename = "radiusPassword")]
         #[serde(alias = "radius_password")]
         pub password: Option<String>,
@@ -660,7 +748,8 @@ monitor_type! {
         pub dns_resolve_server: Option<String>,
 
         #[serde(rename = "port")]
-        #[serde_as(as = "Option<DeserializeNumberLenient>")]
+        #[serde(default)]
+        #[serde_as(as = "DeserializeOptionLenient<DeserializeNumberLenient>")]
         pub port: Option<u16>,
 
         #[serde(rename = "dns_resolve_type")]
@@ -669,6 +758,23 @@ monitor_type! {
     }
 }
 
+/// Whether the given string is a plausible hostname or ip address (IPv6 addresses may be given without brackets).
+fn is_valid_host(host: &str) -> bool {
+    !host.is_empty()
+        && !host.contains(char::is_whitespace)
+        && url::Host::parse(host)
+            .or_else(|_| url::Host::parse(&format!("[{}]", host)))
+            .is_ok()
+}
+
+/// Whether the resolver is an ip address or hostname, Uptime Kuma 2.0 also accepts a comma separated list of resolvers.
+fn is_valid_dns_resolve_server(dns_resolve_server: &str) -> bool {
+    dns_resolve_server
+        .split(',')
+        .map(str::trim)
+        .all(is_valid_host)
+}
+
 monitor_type! {
     MonitorDocker Docker {
         #[serde(rename = "docker_container")]
@@ -695,11 +801,13 @@ monitor_type! {
         pub hostname: Option<String>,
 
         #[serde(rename = "port")]
-        #[serde_as(as = "Option<DeserializeNumberLenient>")]
+        #[serde(default)]
+        #[serde_as(as = "DeserializeOptionLenient<DeserializeNumberLenient>")]
         pub port: Option<u16>,
 
         #[serde(rename = "gamedigGivenPortOnly")]
         #[serde(alias = "gamedig_given_port_only")]
+        #[serde_inline_default(Some(true))]
         #[serde_as(as = "Option<DeserializeBoolLenient>")]
         pub gamedig_given_port_only: Option<bool>,
     }
@@ -710,6 +818,8 @@ monitor_type! {
         #[serde(rename = "keyword")]
         pub keyword: Option<String>,
 
+        #[serde(rename = "invertKeyword")]
+        #[serde(alias = "invert_keyword")]
         #[serde_as(as = "Option<DeserializeBoolLenient>")]
         pub invert_keyword: Option<bool>,
 
@@ -749,8 +859,8 @@ monitor_type! {
         pub grpc_metadata: Option<String>,
 
         #[cfg(feature = "uptime-kuma-v2")]
-        #[serde(rename = "grpcMetadata")]
-        #[serde(alias = "grpc_metadata")]
+        #[serde(rename = "cacheBust")]
+        #[serde(alias = "cache_bust")]
         #[serde_as(as = "Option<DeserializeBoolLenient>")]
         pub cache_bust: Option<bool>,
     }
@@ -768,7 +878,8 @@ monitor_type! {
 
         #[serde(rename = "resendInterval")]
         #[serde(alias = "resend_interval")]
-        #[serde_as(as = "Option<DeserializeNumberLenient>")]
+        #[serde(default)]
+        #[serde_as(as = "DeserializeOptionLenient<DeserializeNumberLenient>")]
         pub resend_interval: Option<i32>,
 
         #[serde(rename = "expiryNotification")]
@@ -788,7 +899,8 @@ monitor_type! {
 
         #[serde(rename = "proxyId")]
         #[serde(alias = "proxy_id")]
-        #[serde_as(as = "Option<DeserializeNumberLenient>")]
+        #[serde(default)]
+        #[serde_as(as = "DeserializeOptionLenient<DeserializeNumberLenient>")]
         pub proxy_id: Option<i32>,
 
         #[serde(rename = "method")]
@@ -841,7 +953,8 @@ monitor_type! {
 
         #[serde(rename = "resendInterval")]
         #[serde(alias = "resend_interval")]
-        #[serde_as(as = "Option<DeserializeNumberLenient>")]
+        #[serde(default)]
+        #[serde_as(as = "DeserializeOptionLenient<DeserializeNumberLenient>")]
         pub resend_interval: Option<i32>,
 
         #[serde(rename = "expiryNotification")]
@@ -861,7 +974,9 @@ monitor_type! {
 
         #[serde(rename = "proxyId")]
         #[serde(alias = "proxy_id")]
-        pub proxy_id: Option<String>,
+        #[serde(default)]
+        #[serde_as(as = "DeserializeOptionLenient<DeserializeNumberLenient>")]
+        pub proxy_id: Option<i32>,
 
         #[serde(rename = "method")]
         #[serde_inline_default(Some(HttpMethod::GET))]
@@ -881,8 +996,8 @@ monitor_type! {
         pub auth: Option<HttpAuth>,
 
         #[cfg(feature = "uptime-kuma-v2")]
-        #[serde(rename = "grpcMetadata")]
-        #[serde(alias = "grpc_metadata")]
+        #[serde(rename = "cacheBust")]
+        #[serde(alias = "cache_bust")]
         #[serde_as(as = "Option<DeserializeBoolLenient>")]
         pub cache_bust: Option<bool>,
     }
@@ -939,7 +1054,8 @@ monitor_type! {
 
         #[serde(rename = "resendInterval")]
         #[serde(alias = "resend_interval")]
-        #[serde_as(as = "Option<DeserializeNumberLenient>")]
+        #[serde(default)]
+        #[serde_as(as = "DeserializeOptionLenient<DeserializeNumberLenient>")]
         pub resend_interval: Option<i32>,
 
         #[serde(rename = "expiryNotification")]
@@ -959,7 +1075,9 @@ monitor_type! {
 
         #[serde(rename = "proxyId")]
         #[serde(alias = "proxy_id")]
-        pub proxy_id: Option<String>,
+        #[serde(default)]
+        #[serde_as(as = "DeserializeOptionLenient<DeserializeNumberLenient>")]
+        pub proxy_id: Option<i32>,
 
         #[serde(rename = "method")]
         #[serde_inline_default(Some(HttpMethod::GET))]
@@ -977,6 +1095,12 @@ monitor_type! {
 
         #[serde(flatten)]
         pub auth: Option<HttpAuth>,
+
+        #[cfg(feature = "uptime-kuma-v2")]
+        #[serde(rename = "cacheBust")]
+        #[serde(alias = "cache_bust")]
+        #[serde_as(as = "Option<DeserializeBoolLenient>")]
+        pub cache_bust: Option<bool>,
     }
 }
 
@@ -986,7 +1110,8 @@ monitor_type! {
         pub hostname: Option<String>,
 
         #[serde(rename = "port")]
-        #[serde_as(as = "Option<DeserializeNumberLenient>")]
+        #[serde(default)]
+        #[serde_as(as = "DeserializeOptionLenient<DeserializeNumberLenient>")]
         pub port: Option<u16>,
 
         #[serde(rename = "mqttUsername")]
@@ -1035,6 +1160,12 @@ monitor_type! {
         #[serde_inline_default(Some(56))]
         #[serde_as(as = "Option<DeserializeNumberLenient>")]
         pub packet_size: Option<i32>,
+
+        /// Force resolving the hostname to an IPv4 or IPv6 address, uses the system default if not set.
+        #[cfg(feature = "uptime-kuma-v2")]
+        #[serde(rename = "ipFamily")]
+        #[serde(alias = "ip_family")]
+        pub ip_family: Option<IpFamily>,
     }
 }
 
@@ -1044,7 +1175,8 @@ monitor_type! {
         pub hostname: Option<String>,
 
         #[serde(rename = "port")]
-        #[serde_as(as = "Option<DeserializeNumberLenient>")]
+        #[serde(default)]
+        #[serde_as(as = "DeserializeOptionLenient<DeserializeNumberLenient>")]
         pub port: Option<u16>,
     }
 }
@@ -1057,13 +1189,48 @@ monitor_type! {
     }
 }
 
+impl MonitorPush {
+    /// Build the URL used to send heartbeats to this push monitor, e.g. `<base_url>/api/push/<token>?status=up&msg=OK&ping=`.
+    pub fn push_url(&self, base_url: &Url) -> Result<Url> {
+        let push_token = self
+            .push_token
+            .as_ref()
+            .filter(|push_token| is_valid_push_token(push_token))
+            .ok_or_else(|| {
+                Error::ValidationError(
+                    "push_token".to_owned(),
+                    vec!["Missing or invalid push_token".to_owned()],
+                )
+            })?;
+
+        let mut url = base_url.clone();
+        if !url.path().ends_with('/') {
+            url.set_path(&format!("{}/", url.path()));
+        }
+
+        let mut url = url
+            .join(&format!("api/push/{}", push_token))
+            .map_err(|e| Error::InvalidUrl(e.to_string()))?;
+        url.set_query(Some("status=up&msg=OK&ping="));
+
+        Ok(url)
+    }
+}
+
+fn is_valid_push_token(push_token: &str) -> bool {
+    Regex::new("^[A-Za-z0-9]{32}$")
+        .unwrap()
+        .is_match(push_token)
+}
+
 monitor_type! {
     MonitorRadius Radius {
         #[serde(rename = "hostname")]
         pub hostname: Option<String>,
 
         #[serde(rename = "port")]
-        #[serde_as(as = "Option<DeserializeNumberLenient>")]
+        #[serde(default)]
+        #[serde_as(as = "DeserializeOptionLenient<DeserializeNumberLenient>")]
         pub port: Option<u16>,
 
         #[serde(rename = "radiusUsername")]
@@ -1099,7 +1266,25 @@ monitor_type! {
         pub remote_browsers_toggle: Option<bool>,
 
         #[serde(rename = "remote_browser")]
-        pub remote_browser: Option<String>,
+        #[serde(default)]
+        #[serde_as(as = "DeserializeOptionLenient<DeserializeNumberLenient>")]
+        pub remote_browser: Option<i32>,
+
+        #[serde(rename = "timeout")]
+        #[serde_inline_default(Some(48))]
+        #[serde_as(as = "Option<DeserializeNumberLenient>")]
+        pub timeout: Option<i32>,
+
+        #[serde(rename = "resendInterval")]
+        #[serde(alias = "resend_interval")]
+        #[serde(default)]
+        #[serde_as(as = "DeserializeOptionLenient<DeserializeNumberLenient>")]
+        pub resend_interval: Option<i32>,
+
+        #[serde(rename = "expiryNotification")]
+        #[serde(alias = "expiry_notification")]
+        #[serde_as(as = "Option<DeserializeBoolLenient>")]
+        pub expiry_notification: Option<bool>,
     }
 }
 
@@ -1109,7 +1294,8 @@ monitor_type! {
         pub hostname: Option<String>,
 
         #[serde(rename = "port")]
-        #[serde_as(as = "Option<DeserializeNumberLenient>")]
+        #[serde(default)]
+        #[serde_as(as = "DeserializeOptionLenient<DeserializeNumberLenient>")]
         pub port: Option<u16>,
     }
 }
@@ -1117,7 +1303,7 @@ monitor_type! {
 monitor_type! {
     MonitorTailscalePing TailscalePing {
         #[serde(rename = "hostname")]
-        hostname: Option<String>,
+        pub hostname: Option<String>,
     }
 }
 
@@ -1128,11 +1314,14 @@ monitor_type! {
         pub hostname: Option<String>,
 
         #[serde(rename = "port")]
-        #[serde_as(as = "Option<DeserializeNumberLenient>")]
+        #[serde(default)]
+        #[serde_as(as = "DeserializeOptionLenient<DeserializeNumberLenient>")]
         pub port: Option<u16>,
 
+        // Uptime Kuma stores the SNMP community string in the radius password column
         #[serde(rename = "radiusPassword")]
         #[serde(alias = "radius_password")]
+        #[serde(alias = "community")]
         pub password: Option<String>,
 
         #[serde(rename = "snmpOid")]
@@ -1170,14 +1359,67 @@ monitor_type! {
         #[serde(alias = "username")]
         pub username: Option<String>,
 
-        #[serde(rename = "rabbitmqUsername")]
+        #[serde(rename = "rabbitmqPassword")]
         #[serde(alias = "password")]
         pub password: Option<String>,
     }
 }
 
+#[cfg(feature = "uptime-kuma-v2")]
+monitor_type! {
+    MonitorSmtp Smtp {
+        #[serde(rename = "hostname")]
+        pub hostname: Option<String>,
+
+        #[serde(rename = "port")]
+        #[serde(default)]
+        #[serde_as(as = "DeserializeOptionLenient<DeserializeNumberLenient>")]
+        pub port: Option<u16>,
+
+        #[serde(rename = "smtpSecurity")]
+        #[serde(alias = "smtp_security")]
+        pub smtp_security: Option<SmtpSecurity>,
+    }
+}
+
+monitor_type! {
+    MonitorUnknown Unknown {
+        /// The type reported by Uptime Kuma.
+        #[serde(skip)]
+        pub type_name: String,
+
+        /// The monitor as received from Uptime Kuma, used to send unknown properties back unchanged.
+        #[serde(skip)]
+        pub raw: serde_json::Value,
+    }
+}
+
+impl MonitorUnknown {
+    fn from_raw(type_name: String, raw: serde_json::Value) -> Self {
+        let mut monitor: Self = serde_json::from_value(raw.clone()).unwrap_or_default();
+        monitor.type_name = type_name;
+        monitor.raw = raw;
+        monitor
+    }
+
+    fn to_raw(&self) -> serde_json::Result<serde_json::Value> {
+        let mut raw = match &self.raw {
+            serde_json::Value::Object(raw) => raw.clone(),
+            _ => serde_json::Map::new(),
+        };
+
+        if let serde_json::Value::Object(common) = serde_json::to_value(self)? {
+            raw.extend(common);
+        }
+
+        raw.insert("type".to_owned(), json!(self.type_name));
+
+        Ok(serde_json::Value::Object(raw))
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-#[serde(tag = "type")]
+#[serde(tag = "type", remote = "Self")]
 pub enum Monitor {
     #[serde(rename = "group")]
     Group {
@@ -1322,6 +1564,59 @@ pub enum Monitor {
         #[serde(flatten)]
         value: MonitorRabbitMQ,
     },
+    #[cfg(feature = "uptime-kuma-v2")]
+    #[serde(rename = "smtp")]
+    Smtp {
+        #[serde(flatten)]
+        value: MonitorSmtp,
+    },
+
+    /// A monitor with a type which is not supported by this version of kuma-client, it's kept as is so it can be sent back without losing any properties.
+    #[serde(skip)]
+    Unknown { value: MonitorUnknown },
+}
+
+impl Serialize for Monitor {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Monitor::Unknown { value } => value
+                .to_raw()
+                .map_err(serde::ser::Error::custom)?
+                .serialize(serializer),
+            monitor => Monitor::serialize(monitor, serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Monitor {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = serde_json::Value::deserialize(deserializer)?;
+
+        match Monitor::deserialize(raw.clone()) {
+            Ok(monitor) => Ok(monitor),
+            Err(e) => {
+                let type_name = raw.get("type").and_then(|value| value.as_str());
+
+                match type_name.map(|type_name| {
+                    (
+                        type_name,
+                        serde_json::from_value::<MonitorType>(json!(type_name)),
+                    )
+                }) {
+                    Some((type_name, Ok(MonitorType::Unknown))) => Ok(Monitor::Unknown {
+                        value: MonitorUnknown::from_raw(type_name.to_owned(), raw),
+                    }),
+                    _ => Err(serde::de::Error::custom(e)),
+                }
+            }
+        }
+    }
 }
 
 impl Monitor {
@@ -1353,6 +1648,9 @@ impl Monitor {
             Monitor::SNMP { .. } => MonitorType::SNMP,
             #[cfg(feature = "uptime-kuma-v2")]
             Monitor::RabbitMQ { .. } => MonitorType::RabbitMQ,
+            #[cfg(feature = "uptime-kuma-v2")]
+            Monitor::Smtp { .. } => MonitorType::Smtp,
+            Monitor::Unknown { .. } => MonitorType::Unknown,
         }
     }
 
@@ -1384,6 +1682,9 @@ impl Monitor {
             Monitor::SNMP { value } => Box::new(value),
             #[cfg(feature = "uptime-kuma-v2")]
             Monitor::RabbitMQ { value } => Box::new(value),
+            #[cfg(feature = "uptime-kuma-v2")]
+            Monitor::Smtp { value } => Box::new(value),
+            Monitor::Unknown { value } => Box::new(value),
         }
     }
 
@@ -1415,6 +1716,9 @@ impl Monitor {
             Monitor::SNMP { value } => Box::new(value),
             #[cfg(feature = "uptime-kuma-v2")]
             Monitor::RabbitMQ { value } => Box::new(value),
+            #[cfg(feature = "uptime-kuma-v2")]
+            Monitor::Smtp { value } => Box::new(value),
+            Monitor::Unknown { value } => Box::new(value),
         }
     }
 
@@ -1425,10 +1729,88 @@ impl Monitor {
             errors.push("Missing property 'name'".to_owned());
         }
 
+        if let Some(interval) = self.common().interval() {
+            if *interval < 20 {
+                errors.push(format!(
+                    "Invalid interval {}, interval should be at least 20 seconds",
+                    interval
+                ));
+            }
+        }
+
+        if let Some(retry_interval) = self.common().retry_interval() {
+            if *retry_interval < 0 {
+                errors.push(format!(
+                    "Invalid retry_interval {}, retry_interval should not be negative",
+                    retry_interval
+                ));
+            }
+        }
+
+        if let Some(max_retries) = self.common().max_retries() {
+            if *max_retries < 0 {
+                errors.push(format!(
+                    "Invalid max_retries {}, max_retries should not be negative",
+                    max_retries
+                ));
+            }
+        }
+
+        let keyword = match self {
+            Monitor::Keyword { value } => Some(&value.keyword),
+            Monitor::GrpcKeyword { value } => Some(&value.keyword),
+            _ => None,
+        };
+
+        if let Some(keyword) = keyword {
+            if keyword.as_ref().map_or(true, |keyword| keyword.is_empty()) {
+                errors.push("Missing property 'keyword'".to_owned());
+            }
+        }
+
+        if let &Monitor::Dns { value } = &self {
+            if let Some(dns_resolve_server) = &value.dns_resolve_server {
+                if !is_valid_dns_resolve_server(dns_resolve_server) {
+                    errors.push(format!(
+                        "Invalid dns_resolve_server '{}', expected an ip address or hostname",
+                        dns_resolve_server
+                    ));
+                }
+            }
+        }
+
+        if let Err(codes) = self.common().accepted_status_code_ranges() {
+            errors.push(format!(
+                "Invalid accepted_statuscodes entry '{}', expected a status code (e.g. 404) or a range (e.g. 200-299)",
+                codes
+            ));
+        }
+
+        if let &Monitor::Ping { value } = &self {
+            if let Some(packet_size) = value.packet_size {
+                if !(1..=65500).contains(&packet_size) {
+                    errors.push(format!(
+                        "Invalid packet_size {}, packet_size should be between 1 and 65500",
+                        packet_size
+                    ));
+                }
+            }
+        }
+
+        if let &Monitor::TailscalePing { value } = &self {
+            match value.hostname.as_deref().map(str::trim) {
+                None | Some("") => errors.push("Missing property 'hostname'".to_owned()),
+                Some(hostname) if !is_valid_host(hostname) => errors.push(format!(
+                    "Invalid hostname '{}', expected a tailscale machine name or ip address",
+                    hostname
+                )),
+                _ => {}
+            }
+        }
+
         if let &Monitor::Push { value } = &self {
             if let Some(push_token) = &value.push_token {
-                let regex = Regex::new("^[A-Za-z0-9]{32}$").unwrap();
-                if !regex.is_match(&push_token) {
+                if !is_valid_push_token(push_token) {
                     errors.push("Invalid push_token, push token should be 32 characters and contain only letters and numbers".to_owned());
                 }
             }
@@ -1443,3 +1825,786 @@ impl Monitor {
 }
 
 pub type MonitorList = HashMap<String, Monitor>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    /// Deserializes a monitor payload sent by Uptime Kuma and asserts that serializing it again
+    /// sends back the same value for each of `keys`.
+    fn assert_keys_round_trip(payload: Value, keys: &[&str]) -> Monitor {
+        let monitor = serde_json::from_value::<Monitor>(payload.clone()).unwrap();
+        let serialized = serde_json::to_value(&monitor).unwrap();
+
+        for key in keys {
+            assert_eq!(
+                serialized.get(key),
+                payload.get(key),
+                "Value of '{}' changed during the round-trip",
+                key
+            );
+        }
+
+        monitor
+    }
+
+    #[test]
+    fn real_browser_round_trip() {
+        let payload = serde_json::from_str::<Value>(
+            r#"{
+                "id": 7,
+                "name": "Dashboard",
+                "description": null,
+                "pathName": "Dashboard",
+                "parent": null,
+                "childrenIDs": [],
+                "url": "https://dashboard.example.com",
+                "method": "GET",
+                "hostname": null,
+                "port": null,
+                "maxretries": 2,
+                "weight": 2000,
+                "active": true,
+                "forceInactive": false,
+                "type": "real-browser",
+                "timeout": 30,
+                "interval": 120,
+                "retryInterval": 90,
+                "resendInterval": 10,
+                "keyword": null,
+                "invertKeyword": false,
+                "expiryNotification": true,
+                "ignoreTls": false,
+                "upsideDown": false,
+                "packetSize": 56,
+                "maxredirects": 10,
+                "accepted_statuscodes": ["200-299"],
+                "dns_resolve_type": "A",
+                "dns_resolve_server": "1.1.1.1",
+                "dns_last_result": null,
+                "docker_container": null,
+                "docker_host": null,
+                "proxyId": null,
+                "notificationIDList": {"1": true},
+                "tags": [],
+                "maintenance": false,
+                "mqttTopic": "",
+                "mqttSuccessMessage": "",
+                "databaseQuery": null,
+                "authMethod": null,
+                "grpcUrl": null,
+                "grpcProtobuf": null,
+                "grpcMethod": null,
+                "grpcServiceName": null,
+                "grpcEnableTls": false,
+                "radiusCalledStationId": null,
+                "radiusCallingStationId": null,
+                "game": null,
+                "gamedigGivenPortOnly": true,
+                "httpBodyEncoding": "json",
+                "jsonPath": null,
+                "expectedValue": null,
+                "kafkaProducerTopic": null,
+                "kafkaProducerBrokers": [],
+                "kafkaProducerSsl": false,
+                "kafkaProducerAllowAutoTopicCreation": false,
+                "kafkaProducerMessage": null,
+                "screenshot": "/screenshots/7.png",
+                "remote_browser": 3,
+                "remoteBrowsersToggle": true,
+                "includeSensitiveData": false
+            }"#,
+        )
+        .unwrap();
+
+        let monitor = assert_keys_round_trip(
+            payload,
+            &[
+                "id",
+                "name",
+                "type",
+                "url",
+                "interval",
+                "retryInterval",
+                "maxretries",
+                "timeout",
+                "resendInterval",
+                "expiryNotification",
+                "remote_browser",
+                "remoteBrowsersToggle",
+                "accepted_statuscodes",
+                "notificationIDList",
+            ],
+        );
+
+        let Monitor::RealBrowser { value } = monitor else {
+            panic!("Expected a real-browser monitor, got {:?}", monitor);
+        };
+        assert_eq!(value.timeout, Some(30));
+        assert_eq!(value.resend_interval, Some(10));
+        assert_eq!(value.expiry_notification, Some(true));
+        assert_eq!(value.remote_browser, Some(3));
+        assert_eq!(value.remote_browsers_toggle, Some(true));
+    }
+
+    /// Keys Uptime Kuma computes for the monitors it sends, which aren't part of the models and are never sent back.
+    const SERVER_COMPUTED_KEYS: &[&str] = &[
+        "pathName",
+        "childrenIDs",
+        "weight",
+        "maintenance",
+        "forceInactive",
+        "screenshot",
+        "dns_last_result",
+        "includeSensitiveData",
+    ];
+
+    /// The properties of each monitor type, all of them are expected to be sent back unchanged.
+    fn monitor_payloads() -> Vec<Value> {
+        let http = json!({
+            "url": "https://example.com",
+            "timeout": 48,
+            "resendInterval": 5,
+            "expiryNotification": true,
+            "ignoreTls": false,
+            "maxredirects": 10,
+            "proxyId": 1,
+            "method": "POST",
+            "httpBodyEncoding": "json",
+            "body": "{\"key\": \"value\"}",
+            "headers": "{\"X-Test\": \"1\"}",
+        });
+        let with_http = |mut payload: Value| {
+            payload
+                .as_object_mut()
+                .unwrap()
+                .extend(http.as_object().unwrap().clone());
+            #[cfg(feature = "uptime-kuma-v2")]
+            {
+                payload["cacheBust"] = json!(true);
+            }
+            payload
+        };
+
+        let payloads = vec![
+            json!({"type": "group"}),
+            json!({
+                "type": "sqlserver",
+                "databaseConnectionString": "Server=db.example.com;Database=app",
+                "databaseQuery": "SELECT 1",
+            }),
+            json!({
+                "type": "postgres",
+                "databaseConnectionString": "postgres://db.example.com:5432/app",
+                "databaseQuery": "SELECT 1",
+            }),
+            json!({
+                "type": "mongodb",
+                "databaseConnectionString": "mongodb://db.example.com:27017",
+            }),
+            json!({
+                "type": "mysql",
+                "databaseConnectionString": "mysql://db.example.com:3306/app",
+                "radiusPassword": "secret",
+                "databaseQuery": "SELECT 1",
+            }),
+            json!({
+                "type": "redis",
+                "databaseConnectionString": "redis://cache.example.com:6379",
+            }),
+            json!({
+                "type": "dns",
+                "hostname": "example.com",
+                "dns_resolve_server": "1.1.1.1",
+                "port": 53,
+                "dns_resolve_type": "MX",
+            }),
+            json!({
+                "type": "docker",
+                "docker_container": "web",
+                "docker_host": 1,
+            }),
+            json!({
+                "type": "gamedig",
+                "game": "minecraft",
+                "hostname": "mc.example.com",
+                "port": 25565,
+                "gamedigGivenPortOnly": false,
+            }),
+            json!({
+                "type": "grpc-keyword",
+                "keyword": "SERVING",
+                "invertKeyword": true,
+                "grpcUrl": "grpc.example.com:50051",
+                "maxredirects": 10,
+                "grpcEnableTls": true,
+                "grpcServiceName": "Health",
+                "grpcMethod": "Check",
+                "grpcProtobuf": "syntax = \"proto3\";",
+                "grpcBody": "{}",
+                "grpcMetadata": "{\"authorization\": \"token\"}",
+            }),
+            with_http(json!({
+                "type": "http",
+                "authMethod": "basic",
+                "basic_auth_user": "user",
+                "basic_auth_pass": "secret",
+            })),
+            with_http(json!({
+                "type": "json-query",
+                "jsonPath": "$.status",
+                "expectedValue": "ok",
+                "authMethod": "oauth2-cc",
+                "oauth_auth_method": "client_secret_basic",
+                "oauth_client_id": "client",
+                "oauth_token_url": "https://auth.example.com/token",
+                "oauth_client_secret": "secret",
+                "oauth_scopes": "read",
+            })),
+            json!({
+                "type": "kafka-producer",
+                "kafkaProducerBrokers": ["kafka.example.com:9092"],
+                "kafkaProducerTopic": "events",
+                "kafkaProducerMessage": "ping",
+                "kafkaProducerSsl": true,
+                "kafkaProducerAllowAutoTopicCreation": false,
+                "kafkaProducerSaslOptions": {
+                    "mechanism": "plain",
+                    "username": "user",
+                    "password": "secret",
+                },
+            }),
+            with_http(json!({
+                "type": "keyword",
+                "keyword": "Welcome",
+                "invertKeyword": false,
+                "authMethod": "ntlm",
+                "basic_auth_user": "user",
+                "basic_auth_pass": "secret",
+                "authDomain": "EXAMPLE",
+                "authWorkstation": "WS01",
+            })),
+            json!({
+                "type": "mqtt",
+                "hostname": "mqtt.example.com",
+                "port": 1883,
+                "mqttUsername": "user",
+                "mqttPassword": "secret",
+                "mqttTopic": "status",
+                "mqttCheckType": "keyword",
+                "mqttSuccessMessage": "online",
+                "expectedValue": "online",
+            }),
+            json!({
+                "type": "ping",
+                "hostname": "192.168.1.1",
+                "packetSize": 56,
+            }),
+            json!({
+                "type": "port",
+                "hostname": "example.com",
+                "port": 22,
+            }),
+            json!({
+                "type": "push",
+                "pushToken": "Zk2cQRGUsV",
+            }),
+            json!({
+                "type": "radius",
+                "hostname": "radius.example.com",
+                "port": 1812,
+                "radiusUsername": "user",
+                "radiusPassword": "secret",
+                "radiusSecret": "shared",
+                "radiusCalledStationId": "00-00-00-00-00-01",
+                "radiusCallingStationId": "00-00-00-00-00-02",
+            }),
+            json!({
+                "type": "real-browser",
+                "url": "https://example.com",
+                "remoteBrowsersToggle": true,
+                "remote_browser": 3,
+                "timeout": 30,
+                "resendInterval": 10,
+                "expiryNotification": true,
+            }),
+            json!({
+                "type": "steam",
+                "hostname": "cs.example.com",
+                "port": 27015,
+            }),
+            json!({
+                "type": "tailscale-ping",
+                "hostname": "node.tail1234.ts.net",
+            }),
+            json!({
+                "type": "future-type",
+                "futureProperty": {"nested": [1, 2, 3]},
+            }),
+        ];
+
+        #[cfg(feature = "uptime-kuma-v2")]
+        let payloads = [
+            payloads,
+            vec![
+                json!({
+                    "type": "mongodb",
+                    "databaseConnectionString": "mongodb://db.example.com:27017",
+                    "databaseQuery": "{\"ping\": 1}",
+                    "jsonPath": "$.ok",
+                    "expectedValue": "1",
+                }),
+                json!({
+                    "type": "redis",
+                    "databaseConnectionString": "rediss://cache.example.com:6380",
+                    "ignoreTls": true,
+                }),
+                with_http(json!({
+                    "type": "json-query",
+                    "jsonPath": "$.count",
+                    "jsonPathOperator": ">=",
+                    "expectedValue": "3",
+                })),
+                json!({
+                    "type": "mqtt",
+                    "hostname": "mqtt.example.com",
+                    "mqttTopic": "status",
+                    "mqttCheckType": "json-query",
+                    "jsonPath": "$.state",
+                    "jsonPathOperator": "==",
+                    "expectedValue": "online",
+                }),
+                json!({
+                    "type": "ping",
+                    "hostname": "router.example.com",
+                    "ipFamily": "ipv6",
+                }),
+                json!({
+                    "type": "snmp",
+                    "hostname": "switch.example.com",
+                    "port": 161,
+                    "radiusPassword": "public",
+                    "snmpOid": "1.3.6.1.2.1.1.3.0",
+                    "snmp_version": "2c",
+                    "jsonPath": "$",
+                    "jsonPathOperator": ">",
+                    "expectedValue": "0",
+                }),
+                json!({
+                    "type": "rabbitmq",
+                    "rabbitmqNodes": ["https://rabbit.example.com:15672"],
+                    "rabbitmqUsername": "guest",
+                    "rabbitmqPassword": "secret",
+                }),
+                json!({
+                    "type": "smtp",
+                    "hostname": "mail.example.com",
+                    "port": 587,
+                    "smtpSecurity": "starttls",
+                }),
+            ],
+        ]
+        .concat();
+
+        payloads
+    }
+
+    #[test]
+    fn all_monitor_types_round_trip() {
+        for payload in monitor_payloads() {
+            let payload = json!({
+                "id": 42,
+                "name": "Example",
+                "description": "An example monitor",
+                "interval": 120,
+                "active": true,
+                "maxretries": 3,
+                "retryInterval": 30,
+                "upsideDown": false,
+                "parent": 7,
+                "notificationIDList": {"1": true},
+                "accepted_statuscodes": ["200-299", "301"],
+                "pathName": "Parent / Example",
+                "childrenIDs": [],
+                "weight": 2000,
+                "maintenance": false,
+                "forceInactive": false,
+                "screenshot": null,
+                "dns_last_result": null,
+                "includeSensitiveData": false,
+            })
+            .as_object()
+            .unwrap()
+            .clone()
+            .into_iter()
+            .chain(payload.as_object().unwrap().clone())
+            .collect::<serde_json::Map<_, _>>();
+
+            #[cfg(feature = "uptime-kuma-v2")]
+            let payload = payload
+                .into_iter()
+                .chain([("conditions".to_owned(), json!([]))])
+                .collect::<serde_json::Map<_, _>>();
+
+            let monitor_type = payload["type"].as_str().unwrap().to_owned();
+            let keys = payload
+                .keys()
+                .filter(|key| {
+                    monitor_type == "future-type" || !SERVER_COMPUTED_KEYS.contains(&key.as_str())
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+
+            let monitor = assert_keys_round_trip(
+                Value::Object(payload),
+                &keys.iter().map(|key| key.as_str()).collect::<Vec<_>>(),
+            );
+
+            let serialized = serde_json::to_value(&monitor).unwrap();
+            assert_eq!(
+                serde_json::from_value::<Monitor>(serialized.clone()).unwrap(),
+                monitor,
+                "'{}' monitor changed during the round-trip",
+                monitor_type
+            );
+
+            if monitor_type != "future-type" {
+                for key in SERVER_COMPUTED_KEYS {
+                    assert_eq!(
+                        serialized.get(key),
+                        None,
+                        "'{}' monitor sent back the server computed '{}'",
+                        monitor_type,
+                        key
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn notification_order_is_ignored() {
+        let monitor = |notifications: &[(&str, bool)]| {
+            let mut monitor = serde_json::from_value::<Monitor>(json!({
+                "type": "http",
+                "name": "Example",
+                "url": "https://example.com",
+            }))
+            .unwrap();
+            *monitor.common_mut().notification_id_list_mut() = Some(
+                notifications
+                    .iter()
+                    .map(|(id, enabled)| (id.to_string(), *enabled))
+                    .collect(),
+            );
+            monitor
+        };
+
+        assert_eq!(
+            monitor(&[("1", true), ("2", true), ("3", true)]),
+            monitor(&[("3", true), ("1", true), ("2", true)])
+        );
+        assert_eq!(
+            monitor(&[("2", false), ("1", true)]),
+            monitor(&[("1", true)])
+        );
+        assert_ne!(
+            monitor(&[("2", true), ("1", true)]),
+            monitor(&[("1", true)])
+        );
+    }
+
+    #[test]
+    fn gamedig_and_steam_round_trip() {
+        let monitor = assert_keys_round_trip(
+            json!({
+                "id": 12,
+                "name": "Minecraft",
+                "type": "gamedig",
+                "interval": 60,
+                "game": "minecraft",
+                "hostname": "mc.example.com",
+                "port": 25565,
+                "gamedigGivenPortOnly": false,
+                "steam_api_key": null,
+            }),
+            &[
+                "id",
+                "name",
+                "type",
+                "game",
+                "hostname",
+                "port",
+                "gamedigGivenPortOnly",
+            ],
+        );
+
+        let Monitor::GameDig { value } = monitor else {
+            panic!("Expected a gamedig monitor, got {:?}", monitor);
+        };
+        assert_eq!(value.port, Some(25565));
+        assert_eq!(value.gamedig_given_port_only, Some(false));
+
+        let monitor = serde_json::from_value::<Monitor>(json!({
+            "type": "gamedig",
+            "name": "Minecraft",
+            "game": "minecraft",
+            "hostname": "mc.example.com",
+            "port": "25565",
+        }))
+        .unwrap();
+
+        let Monitor::GameDig { value } = monitor else {
+            panic!("Expected a gamedig monitor, got {:?}", monitor);
+        };
+        assert_eq!(value.port, Some(25565));
+        assert_eq!(value.gamedig_given_port_only, Some(true));
+
+        assert_keys_round_trip(
+            json!({
+                "id": 13,
+                "name": "Counter-Strike",
+                "type": "steam",
+                "interval": 60,
+                "hostname": "cs.example.com",
+                "port": 27015,
+            }),
+            &["id", "name", "type", "hostname", "port"],
+        );
+    }
+
+    #[test]
+    fn credentials_use_their_own_keys() {
+        let monitor = assert_keys_round_trip(
+            json!({
+                "type": "mysql",
+                "name": "Database",
+                "databaseConnectionString": "mysql://db.example.com:3306/app",
+                "radiusPassword": "secret",
+            }),
+            &["databaseConnectionString", "radiusPassword"],
+        );
+
+        let Monitor::Mysql { value } = monitor else {
+            panic!("Expected a mysql monitor, got {:?}", monitor);
+        };
+        assert_eq!(value.password, Some("secret".to_owned()));
+    }
+
+    #[cfg(feature = "uptime-kuma-v2")]
+    #[test]
+    fn v2_credentials_use_their_own_keys() {
+        let monitor = assert_keys_round_trip(
+            json!({
+                "type": "rabbitmq",
+                "name": "Queue",
+                "rabbitmqNodes": ["https://rabbit.example.com:15672"],
+                "rabbitmqUsername": "guest",
+                "rabbitmqPassword": "secret",
+            }),
+            &["rabbitmqNodes", "rabbitmqUsername", "rabbitmqPassword"],
+        );
+
+        let Monitor::RabbitMQ { value } = monitor else {
+            panic!("Expected a rabbitmq monitor, got {:?}", monitor);
+        };
+        assert_eq!(value.username, Some("guest".to_owned()));
+        assert_eq!(value.password, Some("secret".to_owned()));
+
+        let monitor = serde_json::from_value::<Monitor>(json!({
+            "type": "snmp",
+            "name": "Switch",
+            "hostname": "switch.example.com",
+            "community": "public",
+        }))
+        .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&monitor)
+                .unwrap()
+                .get("radiusPassword"),
+            Some(&json!("public"))
+        );
+    }
+
+    #[test]
+    fn ping_round_trip() {
+        let monitor = assert_keys_round_trip(
+            json!({
+                "id": 3,
+                "type": "ping",
+                "name": "Router",
+                "hostname": "192.168.1.1",
+                "packetSize": 1200,
+            }),
+            &["id", "type", "name", "hostname", "packetSize"],
+        );
+
+        let Monitor::Ping { value } = monitor else {
+            panic!("Expected a ping monitor, got {:?}", monitor);
+        };
+        assert_eq!(value.packet_size, Some(1200));
+
+        #[cfg(feature = "uptime-kuma-v2")]
+        assert_keys_round_trip(
+            json!({
+                "type": "ping",
+                "name": "Router",
+                "hostname": "router.example.com",
+                "ipFamily": "ipv6",
+            }),
+            &["hostname", "ipFamily"],
+        );
+    }
+
+    #[test]
+    fn ping_packet_size_is_validated() {
+        let ping = |packet_size: i32| {
+            serde_json::from_value::<Monitor>(json!({
+                "type": "ping",
+                "name": "Router",
+                "hostname": "192.168.1.1",
+                "packetSize": packet_size,
+            }))
+            .unwrap()
+            .validate("router")
+        };
+
+        assert!(ping(1).is_ok());
+        assert!(ping(56).is_ok());
+        assert!(ping(65500).is_ok());
+        assert!(ping(0).is_err());
+        assert!(ping(65501).is_err());
+    }
+
+    #[cfg(feature = "uptime-kuma-v2")]
+    #[test]
+    fn cache_bust_uses_its_own_key() {
+        let monitor = assert_keys_round_trip(
+            json!({
+                "type": "grpc-keyword",
+                "name": "Service",
+                "grpcUrl": "grpc.example.com:50051",
+                "keyword": "SERVING",
+                "grpcMetadata": "{\"authorization\": \"token\"}",
+                "cacheBust": true,
+            }),
+            &["grpcMetadata", "cacheBust"],
+        );
+
+        let Monitor::GrpcKeyword { value } = monitor else {
+            panic!("Expected a grpc-keyword monitor, got {:?}", monitor);
+        };
+        assert_eq!(
+            value.grpc_metadata,
+            Some("{\"authorization\": \"token\"}".to_owned())
+        );
+        assert_eq!(value.cache_bust, Some(true));
+
+        for monitor_type in ["http", "keyword", "json-query"] {
+            let monitor = assert_keys_round_trip(
+                json!({
+                    "type": monitor_type,
+                    "name": "Website",
+                    "url": "https://example.com",
+                    "cacheBust": true,
+                }),
+                &["type", "cacheBust"],
+            );
+
+            assert_eq!(
+                serde_json::to_value(&monitor).unwrap().get("grpcMetadata"),
+                None,
+                "'{}' monitor sent a grpcMetadata key",
+                monitor_type
+            );
+        }
+    }
+
+    #[test]
+    fn kafka_sasl_mechanisms_round_trip() {
+        for options in [
+            json!({"mechanism": "None"}),
+            json!({"mechanism": "plain", "username": "user", "password": "secret"}),
+            json!({"mechanism": "scram-sha-256", "username": "user", "password": "secret"}),
+            json!({"mechanism": "scram-sha-512", "username": "user", "password": "secret"}),
+            json!({
+                "mechanism": "aws",
+                "authorizationIdentity": "identity",
+                "accessKeyId": "AKIDEXAMPLE",
+                "secretAccessKey": "secret",
+                "sessionToken": "token",
+            }),
+        ] {
+            assert_keys_round_trip(
+                json!({
+                    "type": "kafka-producer",
+                    "name": "Events",
+                    "kafkaProducerBrokers": ["kafka.example.com:9092"],
+                    "kafkaProducerTopic": "events",
+                    "kafkaProducerSaslOptions": options,
+                }),
+                &["kafkaProducerSaslOptions"],
+            );
+        }
+
+        let options = serde_json::from_value::<KafkaProducerSaslOptions>(json!({
+            "mechanism": "aws",
+            "session_token": "token",
+        }))
+        .unwrap();
+        assert_eq!(
+            serde_json::to_value(options).unwrap().get("sessionToken"),
+            Some(&json!("token"))
+        );
+    }
+
+    #[test]
+    fn tailscale_ping_hostname_is_validated() {
+        let tailscale_ping = |hostname: Value| {
+            serde_json::from_value::<Monitor>(json!({
+                "type": "tailscale-ping",
+                "name": "Node",
+                "hostname": hostname,
+            }))
+            .unwrap()
+            .validate("node")
+        };
+
+        assert!(tailscale_ping(json!("node")).is_ok());
+        assert!(tailscale_ping(json!("node.tail1234.ts.net")).is_ok());
+        assert!(tailscale_ping(json!("100.64.0.1")).is_ok());
+        assert!(tailscale_ping(json!("fd7a:115c:a1e0::1")).is_ok());
+        assert!(tailscale_ping(json!(null)).is_err());
+        assert!(tailscale_ping(json!(" ")).is_err());
+        assert!(tailscale_ping(json!("my node")).is_err());
+        assert!(tailscale_ping(json!("node/path")).is_err());
+
+        assert_keys_round_trip(
+            json!({"type": "tailscale-ping", "name": "Node", "hostname": "node.tail1234.ts.net"}),
+            &["type", "name", "hostname"],
+        );
+    }
+
+    #[test]
+    fn dns_resolve_server_is_validated() {
+        let dns = |dns_resolve_server: &str| {
+            serde_json::from_value::<Monitor>(json!({
+                "type": "dns",
+                "name": "Resolver",
+                "hostname": "example.com",
+                "dns_resolve_server": dns_resolve_server,
+            }))
+            .unwrap()
+            .validate("resolver")
+        };
+
+        assert!(dns("1.1.1.1").is_ok());
+        assert!(dns("dns.example.com").is_ok());
+        assert!(dns("2606:4700:4700::1111").is_ok());
+        assert!(dns("1.1.1.1, 8.8.8.8").is_ok());
+        assert!(dns("1.1.1.1,").is_err());
+        assert!(dns("not a host").is_err());
+    }
+}
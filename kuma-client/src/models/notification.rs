@@ -1,6 +1,9 @@
 //! Models related to Uptime Kuma notification services
 
-use crate::deserialize::{DeserializeNumberLenient, DeserializeValueLenient};
+use crate::{
+    deserialize::{DeserializeNumberLenient, DeserializeValueLenient},
+    error::{Error, Result},
+};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, skip_serializing_none};
 
@@ -38,5 +41,47 @@ pub struct Notification {
     pub config: Option<serde_json::Value>,
 }
 
+impl Notification {
+    /// Creates a notification from the provider specific config used by Uptime Kuma,
+    /// e.g. `{"name": "Discord", "type": "discord", "discordWebhookUrl": "..."}`.
+    pub fn from_provider_config(config: serde_json::Value) -> Self {
+        Self {
+            name: config
+                .get("name")
+                .and_then(|name| name.as_str())
+                .map(|name| name.to_owned()),
+            active: config.get("active").and_then(|active| active.as_bool()),
+            is_default: config
+                .get("isDefault")
+                .and_then(|is_default| is_default.as_bool()),
+            config: Some(config),
+            ..Default::default()
+        }
+    }
+
+    pub fn validate(&self, id: impl AsRef<str>) -> Result<()> {
+        let mut errors = vec![];
+
+        if self.name.is_none() {
+            errors.push("Missing property 'name'".to_owned());
+        }
+
+        if !self
+            .config
+            .as_ref()
+            .and_then(|config| config.get("type"))
+            .is_some_and(|notification_type| notification_type.is_string())
+        {
+            errors.push("Missing property 'config.type'".to_owned());
+        }
+
+        if !errors.is_empty() {
+            return Err(Error::ValidationError(id.as_ref().to_owned(), errors));
+        }
+
+        Ok(())
+    }
+}
+
 /// A list of notification services.
 pub type NotificationList = Vec<Notification>;
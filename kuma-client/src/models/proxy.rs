@@ -0,0 +1,77 @@
+//! Models related to Uptime Kuma proxies
+
+use crate::deserialize::{DeserializeBoolLenient, DeserializeNumberLenient};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, skip_serializing_none};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Eq)]
+pub enum ProxyProtocol {
+    #[serde(rename = "http")]
+    Http,
+    #[serde(rename = "https")]
+    Https,
+    #[serde(rename = "socks")]
+    Socks,
+    #[serde(rename = "socks5")]
+    Socks5,
+    #[serde(rename = "socks5h")]
+    Socks5h,
+    #[serde(rename = "socks4")]
+    Socks4,
+}
+
+/// Represents a proxy in Uptime Kuma.
+#[skip_serializing_none]
+#[serde_as]
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize, Eq)]
+pub struct Proxy {
+    /// The unique identifier for the proxy.
+    #[serde(rename = "id")]
+    #[serde_as(as = "Option<DeserializeNumberLenient>")]
+    pub id: Option<i32>,
+
+    /// The protocol used to connect to the proxy.
+    #[serde(rename = "protocol")]
+    pub protocol: Option<ProxyProtocol>,
+
+    /// The hostname or ip address of the proxy.
+    #[serde(rename = "host")]
+    pub host: Option<String>,
+
+    /// The port of the proxy.
+    #[serde(rename = "port")]
+    #[serde_as(as = "Option<DeserializeNumberLenient>")]
+    pub port: Option<u16>,
+
+    /// Whether the proxy requires authentication.
+    #[serde(rename = "auth")]
+    #[serde_as(as = "Option<DeserializeBoolLenient>")]
+    pub auth: Option<bool>,
+
+    /// The username used to authenticate with the proxy.
+    #[serde(rename = "username")]
+    pub username: Option<String>,
+
+    /// The password used to authenticate with the proxy.
+    #[serde(rename = "password")]
+    pub password: Option<String>,
+
+    /// Whether the proxy is used by default for new monitors.
+    #[serde(rename = "default")]
+    #[serde_as(as = "Option<DeserializeBoolLenient>")]
+    pub default: Option<bool>,
+
+    /// The user identifier associated with the proxy.
+    #[serde(rename = "userId", alias = "user_id")]
+    #[serde_as(as = "Option<DeserializeNumberLenient>")]
+    pub user_id: Option<i32>,
+}
+
+impl Proxy {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+/// A list of proxies.
+pub type ProxyList = Vec<Proxy>;
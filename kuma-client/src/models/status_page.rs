@@ -2,13 +2,18 @@
 
 use crate::{
     deserialize::{DeserializeBoolLenient, DeserializeNumberLenient, DeserializeVecLenient},
+    error::{Error, Result},
     monitor::MonitorType,
 };
+use base64::Engine as _;
 use derivative::Derivative;
 use serde::{Deserialize, Serialize};
 use serde_inline_default::serde_inline_default;
 use serde_with::{serde_as, skip_serializing_none};
-use std::collections::HashMap;
+use std::{collections::HashMap, path::Path};
+
+/// Largest status page logo accepted by [`logo_data_uri`].
+pub const MAX_LOGO_SIZE: usize = 1024 * 1024;
 
 #[serde_inline_default]
 #[skip_serializing_none]
@@ -33,6 +38,12 @@ pub struct PublicGroupMonitor {
     #[derivative(PartialEq = "ignore")]
     #[derivative(Hash = "ignore")]
     pub monitor_type: Option<MonitorType>,
+
+    #[cfg(feature = "private-api")]
+    #[serde(rename = "monitor_name")]
+    #[derivative(PartialEq = "ignore")]
+    #[derivative(Hash = "ignore")]
+    pub monitor_name: Option<String>,
 }
 crate::default_from_serde!(PublicGroupMonitor);
 
@@ -132,6 +143,195 @@ pub struct StatusPage {
 }
 crate::default_from_serde!(StatusPage);
 
+impl StatusPage {
+    /// Sort the public groups by their weight and renumber the weights like Uptime Kuma does when saving a status page,
+    /// so equal status pages compare equal regardless of whether they were loaded from the server or defined locally.
+    pub fn normalize(&mut self) {
+        if let Some(groups) = &mut self.public_group_list {
+            groups.sort_by_key(|group| group.weight.unwrap_or(i32::MAX));
+
+            for (index, group) in groups.iter_mut().enumerate() {
+                group.weight = Some(index as i32 + 1);
+            }
+        }
+    }
+
+    /// Check the status page for values Uptime Kuma doesn't accept, e.g. an unknown theme.
+    pub fn validate(&self, id: impl AsRef<str>) -> Result<()> {
+        let mut errors = vec![];
+
+        if let Some(theme) = &self.theme {
+            if serde_json::from_value::<Theme>(serde_json::Value::String(theme.clone())).is_err() {
+                errors.push(format!(
+                    "Invalid theme '{}', expected 'light', 'dark' or 'auto'",
+                    theme
+                ));
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(Error::ValidationError(id.as_ref().to_owned(), errors));
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn validation_id(&self) -> String {
+        self.slug
+            .clone()
+            .or_else(|| self.title.clone())
+            .unwrap_or_else(|| "status page".to_owned())
+    }
+}
+
+fn logo_mime_type(path: &Path, image: &[u8]) -> Option<&'static str> {
+    match image {
+        [0x89, b'P', b'N', b'G', ..] => Some("image/png"),
+        [0xFF, 0xD8, 0xFF, ..] => Some("image/jpeg"),
+        [b'G', b'I', b'F', b'8', ..] => Some("image/gif"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("image/webp"),
+        [0x00, 0x00, 0x01, 0x00, ..] => Some("image/x-icon"),
+        _ if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("svg")) =>
+        {
+            Some("image/svg+xml")
+        }
+        _ => None,
+    }
+}
+
+/// Read an image file and encode it as a base64 data URI, the format the Uptime Kuma UI uses to upload a status page logo.
+///
+/// The result can be assigned to [`StatusPage::icon`] before calling [`crate::Client::edit_status_page`].
+pub fn logo_data_uri(path: impl AsRef<Path>) -> Result<String> {
+    let path = path.as_ref();
+    let name = path.display().to_string();
+
+    let image = std::fs::read(path)
+        .map_err(|e| Error::InvalidConfig(format!("Unable to read {}: {}", name, e)))?;
+
+    let mut errors = vec![];
+
+    if image.len() > MAX_LOGO_SIZE {
+        errors.push(format!(
+            "Logo is too large ({} bytes, max {} bytes)",
+            image.len(),
+            MAX_LOGO_SIZE
+        ));
+    }
+
+    let mime_type = logo_mime_type(path, &image);
+    if mime_type.is_none() {
+        errors.push("Unsupported image type, expected png, jpeg, gif, webp, ico or svg".to_owned());
+    }
+
+    match mime_type {
+        Some(mime_type) if errors.is_empty() => Ok(format!(
+            "data:{};base64,{}",
+            mime_type,
+            base64::engine::general_purpose::STANDARD.encode(&image)
+        )),
+        _ => Err(Error::ValidationError(name, errors)),
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum IncidentStyle {
+    #[default]
+    #[serde(rename = "info")]
+    Info,
+    #[serde(rename = "warning")]
+    Warning,
+    #[serde(rename = "danger")]
+    Danger,
+    #[serde(rename = "primary")]
+    Primary,
+    #[serde(rename = "light")]
+    Light,
+    #[serde(rename = "dark")]
+    Dark,
+}
+
+#[serde_inline_default]
+#[skip_serializing_none]
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Incident {
+    #[serde(rename = "id")]
+    #[serde_as(as = "Option<DeserializeNumberLenient>")]
+    pub id: Option<i32>,
+
+    #[serde(rename = "title")]
+    pub title: Option<String>,
+
+    #[serde(rename = "content")]
+    pub content: Option<String>,
+
+    #[serde(rename = "style")]
+    #[serde_inline_default(Some(IncidentStyle::Info))]
+    pub style: Option<IncidentStyle>,
+
+    #[serde(rename = "pin")]
+    #[serde_as(as = "Option<DeserializeBoolLenient>")]
+    pub pin: Option<bool>,
+
+    #[serde(rename = "createdDate")]
+    #[serde(alias = "created_date")]
+    pub created_date: Option<String>,
+
+    #[serde(rename = "lastUpdatedDate")]
+    #[serde(alias = "last_updated_date")]
+    pub last_updated_date: Option<String>,
+}
+crate::default_from_serde!(Incident);
+
 pub type StatusPageList = HashMap<String, StatusPage>;
 pub type PublicGroupList = Vec<PublicGroup>;
 pub type PublicGroupMonitorList = Vec<PublicGroupMonitor>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn logo(dir: &TempDir, name: &str, content: &[u8]) -> Result<String> {
+        let path = dir.path().join(name);
+        fs::write(&path, content).unwrap();
+        logo_data_uri(path)
+    }
+
+    #[test]
+    fn logo_data_uri_detects_the_image_type() {
+        let dir = TempDir::new().unwrap();
+
+        assert_eq!(
+            logo(&dir, "logo.bin", b"\x89PNG\r\n").unwrap(),
+            "data:image/png;base64,iVBORw0K"
+        );
+        assert_eq!(
+            logo(&dir, "logo.svg", b"<svg/>").unwrap(),
+            "data:image/svg+xml;base64,PHN2Zy8+"
+        );
+    }
+
+    #[test]
+    fn logo_data_uri_rejects_invalid_images() {
+        let dir = TempDir::new().unwrap();
+
+        let Err(Error::ValidationError(_, errors)) = logo(&dir, "logo.txt", b"hello") else {
+            panic!("Expected a validation error for an unsupported image type");
+        };
+        assert_eq!(errors.len(), 1);
+
+        let mut image = b"\x89PNG".to_vec();
+        image.resize(MAX_LOGO_SIZE + 1, 0);
+        let Err(Error::ValidationError(_, errors)) = logo(&dir, "logo.png", &image) else {
+            panic!("Expected a validation error for a too large image");
+        };
+        assert!(errors[0].starts_with("Logo is too large"));
+
+        assert!(logo_data_uri(dir.path().join("missing.png")).is_err());
+    }
+}
@@ -0,0 +1,842 @@
+//! A minimal in-process Uptime Kuma server for testing [Client](crate::Client) end to end.
+//!
+//! The server speaks just enough of the Engine.IO/Socket.IO protocol and the Uptime Kuma API to log in,
+//! receive the initial lists and add/edit/delete monitors, notifications, tags, maintenances and status pages.
+//! Status pages are also served over plain HTTP at `/api/status-page/<slug>`, like the public status page API,
+//! gzip encoded if the request accepts it.
+//! Calls the server doesn't know are acknowledged with `{"ok": true}` unless a response was set using [MockServer::respond].
+
+use crate::Config;
+use flate2::{write::GzEncoder, Compression};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Map, Value};
+use std::{collections::HashMap, io::Write as _, sync::Arc};
+use tokio::{
+    io::{AsyncReadExt as _, AsyncWriteExt as _},
+    net::{TcpListener, TcpStream},
+    sync::{broadcast, Mutex},
+    task::JoinHandle,
+};
+use tokio_tungstenite::{accept_async, tungstenite::Message};
+use url::Url;
+
+/// The version reported by the mock server in the `info` event.
+#[cfg(not(feature = "uptime-kuma-v2"))]
+pub const MOCK_SERVER_VERSION: &str = "1.23.16";
+
+/// The version reported by the mock server in the `info` event.
+#[cfg(feature = "uptime-kuma-v2")]
+pub const MOCK_SERVER_VERSION: &str = "2.0.0";
+
+/// Payloads resembling the lists sent by Uptime Kuma.
+pub mod fixtures {
+    use serde_json::{json, Value};
+
+    /// A `monitorList` containing a group with a single HTTP monitor.
+    pub fn monitor_list() -> Value {
+        json!({
+            "1": {
+                "id": 1,
+                "name": "Group",
+                "type": "group",
+                "active": true,
+                "interval": 60,
+                "retryInterval": 60,
+                "maxretries": 0,
+                "upsideDown": false,
+                "parent": null,
+                "tags": [],
+                "notificationIDList": {},
+                "accepted_statuscodes": ["200-299"],
+            },
+            "2": {
+                "id": 2,
+                "name": "Website",
+                "type": "http",
+                "active": true,
+                "interval": 60,
+                "retryInterval": 60,
+                "maxretries": 0,
+                "upsideDown": false,
+                "parent": 1,
+                "url": "https://example.com",
+                "method": "GET",
+                "tags": [],
+                "notificationIDList": {},
+                "accepted_statuscodes": ["200-299"],
+            },
+        })
+    }
+
+    /// A `maintenanceList` with a recurring maintenance whose time range omits the `seconds`, as sent by older Uptime Kuma versions.
+    pub fn maintenance_list() -> Value {
+        json!({
+            "1": {
+                "id": 1,
+                "title": "Nightly backup",
+                "description": "",
+                "strategy": "recurring-weekday",
+                "active": true,
+                "status": "scheduled",
+                "intervalDay": 1,
+                "dateRange": ["2024-01-01T00:00:00", "2024-12-31T23:59:59"],
+                "timeRange": [{"hours": 2, "minutes": 0}, {"hours": 3, "minutes": 0}],
+                "weekdays": [1, 3, 5],
+                "daysOfMonth": [],
+                "timeslotList": [],
+                "cron": "",
+                "durationMinutes": 60,
+                "timezone": "UTC",
+                "timezoneOption": "UTC",
+                "timezoneOffset": "+00:00",
+            },
+        })
+    }
+}
+
+#[derive(Default)]
+struct State {
+    monitors: Map<String, Value>,
+    notifications: Vec<Value>,
+    maintenances: Map<String, Value>,
+    tags: Vec<Value>,
+    status_pages: Map<String, Value>,
+    public_groups: HashMap<String, Value>,
+    next_id: i64,
+    responses: HashMap<String, Value>,
+    ignored: HashMap<String, usize>,
+    calls: Vec<(String, Vec<Value>)>,
+    http_requests: Vec<String>,
+    version: Option<String>,
+}
+
+impl State {
+    fn next_id(&mut self) -> i64 {
+        self.next_id = self
+            .monitors
+            .keys()
+            .chain(self.maintenances.keys())
+            .filter_map(|id| id.parse::<i64>().ok())
+            .chain(
+                self.notifications
+                    .iter()
+                    .chain(self.tags.iter())
+                    .chain(self.status_pages.values())
+                    .filter_map(|entry| entry["id"].as_i64()),
+            )
+            .fold(self.next_id, i64::max)
+            + 1;
+
+        self.next_id
+    }
+
+    fn version(&self) -> &str {
+        self.version.as_deref().unwrap_or(MOCK_SERVER_VERSION)
+    }
+
+    fn lists(&self) -> Vec<(&'static str, Value)> {
+        // Docker hosts were added in Uptime Kuma 1.18, older versions don't send the list
+        let mut version = self
+            .version()
+            .split('.')
+            .map(|part| part.parse::<u32>().unwrap_or_default());
+        let has_docker_hosts = (
+            version.next().unwrap_or_default(),
+            version.next().unwrap_or_default(),
+        ) >= (1, 18);
+
+        vec![
+            ("monitorList", Value::Object(self.monitors.clone())),
+            ("notificationList", json!(self.notifications)),
+            ("maintenanceList", Value::Object(self.maintenances.clone())),
+            (
+                "statusPageList",
+                Value::Object(
+                    self.status_pages
+                        .values()
+                        .map(|status_page| (status_page["id"].to_string(), status_page.clone()))
+                        .collect(),
+                ),
+            ),
+            ("dockerHostList", json!([])),
+            ("proxyList", json!([])),
+        ]
+        .into_iter()
+        .filter(|(name, _)| has_docker_hosts || *name != "dockerHostList")
+        .collect()
+    }
+
+    /// Records a call which shouldn't be acknowledged, returns false if the call should be handled.
+    fn ignore(&mut self, method: &str, args: &[Value]) -> bool {
+        match self.ignored.get_mut(method) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                self.calls.push((method.to_owned(), args.to_vec()));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Handles a call, returning the acknowledgement and the events sent afterwards.
+    fn call(&mut self, method: &str, args: Vec<Value>) -> (Value, Vec<(&'static str, Value)>) {
+        self.calls.push((method.to_owned(), args.clone()));
+
+        if let Some(response) = self.responses.get(method) {
+            return (response.clone(), vec![]);
+        }
+
+        let arg = |index: usize| args.get(index).cloned().unwrap_or_default();
+        let key = |value: Value| match value {
+            Value::String(id) => id,
+            id => id.to_string(),
+        };
+
+        match method {
+            "login" | "loginByToken" => (json!({"ok": true, "token": "mock-token"}), self.lists()),
+            "getMonitorList" => (
+                json!({"ok": true}),
+                vec![("monitorList", Value::Object(self.monitors.clone()))],
+            ),
+            "getMonitor" => (
+                json!({"ok": true, "monitor": self.monitors.get(&key(arg(0)))}),
+                vec![],
+            ),
+            "add" | "editMonitor" => {
+                let mut monitor = arg(0);
+                let id = match monitor["id"].as_i64() {
+                    Some(id) if method == "editMonitor" => id,
+                    _ => self.next_id(),
+                };
+
+                monitor["id"] = json!(id);
+                monitor["active"] = json!(monitor["active"].as_bool().unwrap_or(true));
+                self.monitors.insert(id.to_string(), monitor);
+
+                (
+                    json!({"ok": true, "msg": "Saved.", "monitorID": id}),
+                    vec![("monitorList", Value::Object(self.monitors.clone()))],
+                )
+            }
+            "deleteMonitor" => {
+                self.monitors.remove(&key(arg(0)));
+
+                (
+                    json!({"ok": true, "msg": "Deleted Successfully."}),
+                    vec![("monitorList", Value::Object(self.monitors.clone()))],
+                )
+            }
+            "pauseMonitor" | "resumeMonitor" => {
+                if let Some(monitor) = self.monitors.get_mut(&key(arg(0))) {
+                    monitor["active"] = json!(method == "resumeMonitor");
+                }
+
+                (
+                    json!({"ok": true}),
+                    vec![("monitorList", Value::Object(self.monitors.clone()))],
+                )
+            }
+            "addNotification" => {
+                let config = arg(0);
+                let id = match arg(1).as_i64() {
+                    Some(id) => id,
+                    None => self.next_id(),
+                };
+
+                self.notifications.retain(|entry| entry["id"] != json!(id));
+                self.notifications.push(json!({
+                    "id": id,
+                    "name": config["name"],
+                    "active": true,
+                    "isDefault": config["isDefault"].as_bool().unwrap_or(false),
+                    "userId": 1,
+                    "config": config.to_string(),
+                }));
+
+                (
+                    json!({"ok": true, "msg": "Saved.", "id": id}),
+                    vec![("notificationList", json!(self.notifications))],
+                )
+            }
+            "deleteNotification" => {
+                let id = arg(0);
+                self.notifications.retain(|entry| entry["id"] != id);
+
+                (
+                    json!({"ok": true, "msg": "Deleted"}),
+                    vec![("notificationList", json!(self.notifications))],
+                )
+            }
+            "getTags" => (json!({"ok": true, "tags": self.tags}), vec![]),
+            "addTag" | "editTag" => {
+                let mut tag = arg(0);
+                let id = match tag["id"].as_i64() {
+                    Some(id) if method == "editTag" => id,
+                    _ => self.next_id(),
+                };
+
+                tag["id"] = json!(id);
+                self.tags.retain(|entry| entry["id"] != json!(id));
+                self.tags.push(tag.clone());
+
+                (json!({"ok": true, "tag": tag}), vec![])
+            }
+            "deleteTag" => {
+                let id = arg(0);
+                self.tags.retain(|entry| entry["id"] != id);
+
+                (json!({"ok": true, "msg": "Deleted Successfully."}), vec![])
+            }
+            "getMaintenance" => (
+                json!({"ok": true, "maintenance": self.maintenances.get(&key(arg(0)))}),
+                vec![],
+            ),
+            "addMaintenance" | "editMaintenance" => {
+                let mut maintenance = arg(0);
+                let id = match maintenance["id"].as_i64() {
+                    Some(id) if method == "editMaintenance" => id,
+                    _ => self.next_id(),
+                };
+
+                maintenance["id"] = json!(id);
+                self.maintenances.insert(id.to_string(), maintenance);
+
+                (
+                    json!({"ok": true, "msg": "Saved.", "maintenanceID": id}),
+                    vec![("maintenanceList", Value::Object(self.maintenances.clone()))],
+                )
+            }
+            "deleteMaintenance" => {
+                self.maintenances.remove(&key(arg(0)));
+
+                (
+                    json!({"ok": true, "msg": "Deleted Successfully."}),
+                    vec![("maintenanceList", Value::Object(self.maintenances.clone()))],
+                )
+            }
+            "addStatusPage" => {
+                let slug = key(arg(1));
+                if self.status_pages.contains_key(&slug) {
+                    return (json!({"ok": false, "msg": "Slug already exists"}), vec![]);
+                }
+
+                let id = self.next_id();
+                self.status_pages.insert(
+                    slug.clone(),
+                    json!({"id": id, "slug": slug, "title": arg(0), "icon": "/icon.svg"}),
+                );
+                self.public_groups.insert(slug, json!([]));
+
+                (json!({"ok": true, "msg": "OK!"}), vec![])
+            }
+            "saveStatusPage" => {
+                let slug = key(arg(0));
+                let Some(status_page) = self.status_pages.get_mut(&slug) else {
+                    return (json!({"ok": false, "msg": "No slug?"}), vec![]);
+                };
+
+                if let Value::Object(config) = arg(1) {
+                    for (key, value) in config {
+                        if !matches!(key.as_str(), "id" | "logo" | "publicGroupList") {
+                            status_page[key] = value;
+                        }
+                    }
+                }
+                if let Value::String(icon) = arg(2) {
+                    status_page["icon"] = json!(icon);
+                }
+
+                // Uptime Kuma assigns ids to new groups and numbers them in the order they were sent
+                let mut groups = arg(3);
+                for (index, group) in groups.as_array_mut().into_iter().flatten().enumerate() {
+                    if group["id"].is_null() {
+                        group["id"] = json!(index + 1);
+                    }
+                    group["weight"] = json!(index + 1);
+                }
+                self.public_groups.insert(slug, groups.clone());
+
+                (json!({"ok": true, "publicGroupList": groups}), vec![])
+            }
+            "getStatusPage" => match self.status_pages.get(&key(arg(0))) {
+                Some(status_page) => (json!({"ok": true, "config": status_page}), vec![]),
+                None => (
+                    json!({"ok": false, "msg": "Cannot read properties of null (reading 'toJSON')"}),
+                    vec![],
+                ),
+            },
+            "deleteStatusPage" => {
+                self.status_pages.remove(&key(arg(0)));
+                self.public_groups.remove(&key(arg(0)));
+
+                (json!({"ok": true}), vec![])
+            }
+            "getMonitorMaintenance" => (json!({"ok": true, "monitors": []}), vec![]),
+            "getMaintenanceStatusPage" => (json!({"ok": true, "statusPages": []}), vec![]),
+            _ => (json!({"ok": true}), vec![]),
+        }
+    }
+}
+
+/// A minimal Uptime Kuma server listening on a random local port, it's stopped when dropped.
+pub struct MockServer {
+    url: Url,
+    state: Arc<Mutex<State>>,
+    events: broadcast::Sender<String>,
+    task: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Starts a mock server without any entities.
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Unable to bind mock server");
+        let url = Url::parse(&format!(
+            "http://{}/",
+            listener.local_addr().expect("Mock server has no address")
+        ))
+        .unwrap();
+
+        let state = Arc::new(Mutex::new(State::default()));
+        let (events, _) = broadcast::channel(16);
+        let task = tokio::spawn({
+            let state = state.clone();
+            let events = events.clone();
+            async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::spawn(Self::handle_connection(
+                        stream,
+                        state.clone(),
+                        events.subscribe(),
+                    ));
+                }
+            }
+        });
+
+        Self {
+            url,
+            state,
+            events,
+            task,
+        }
+    }
+
+    /// Replaces the monitors of the server with a `monitorList` payload, e.g. [fixtures::monitor_list].
+    pub async fn with_monitors(self, monitors: Value) -> Self {
+        if let Value::Object(monitors) = monitors {
+            self.state.lock().await.monitors = monitors;
+        }
+        self
+    }
+
+    /// Replaces the notifications of the server with a `notificationList` payload.
+    pub async fn with_notifications(self, notifications: Value) -> Self {
+        if let Value::Array(notifications) = notifications {
+            self.state.lock().await.notifications = notifications;
+        }
+        self
+    }
+
+    /// Replaces the maintenances of the server with a `maintenanceList` payload, e.g. [fixtures::maintenance_list].
+    pub async fn with_maintenances(self, maintenances: Value) -> Self {
+        if let Value::Object(maintenances) = maintenances {
+            self.state.lock().await.maintenances = maintenances;
+        }
+        self
+    }
+
+    /// Reports `version` instead of [MOCK_SERVER_VERSION], versions older than 1.18 don't send a `dockerHostList`.
+    pub async fn with_version(self, version: impl Into<String>) -> Self {
+        self.state.lock().await.version = Some(version.into());
+        self
+    }
+
+    /// Sends an event to all connected clients.
+    pub fn emit(&self, event: &str, payload: Value) {
+        _ = self.events.send(format!("42{}", json!([event, payload])));
+    }
+
+    /// Acknowledges all following calls to `method` with `response` instead of the default behavior.
+    pub async fn respond(&self, method: impl Into<String>, response: Value) {
+        self.state
+            .lock()
+            .await
+            .responses
+            .insert(method.into(), response);
+    }
+
+    /// Doesn't acknowledge the next `count` calls to `method`, letting them time out on the client.
+    pub async fn ignore(&self, method: impl Into<String>, count: usize) {
+        self.state.lock().await.ignored.insert(method.into(), count);
+    }
+
+    /// Returns all calls received by the server with their arguments, in the order they were received.
+    pub async fn calls(&self) -> Vec<(String, Vec<Value>)> {
+        self.state.lock().await.calls.clone()
+    }
+
+    /// Returns the head (request line and headers) of all HTTP requests received by the server, in the order they were received.
+    pub async fn http_requests(&self) -> Vec<String> {
+        self.state.lock().await.http_requests.clone()
+    }
+
+    /// The URL the server is listening on.
+    pub fn url(&self) -> Url {
+        self.url.clone()
+    }
+
+    /// A client config connecting to this server.
+    pub fn config(&self) -> Config {
+        Config {
+            url: self.url(),
+            username: Some("admin".to_owned()),
+            password: Some("admin".to_owned()),
+            ..Default::default()
+        }
+    }
+
+    async fn handle_connection(
+        stream: TcpStream,
+        state: Arc<Mutex<State>>,
+        mut events: broadcast::Receiver<String>,
+    ) {
+        let mut request_line = [0; 64];
+        let Ok(len) = stream.peek(&mut request_line).await else {
+            return;
+        };
+
+        if !request_line[..len].starts_with(b"GET /socket.io/") {
+            return Self::handle_http(stream, state).await;
+        }
+
+        let Ok(mut socket) = accept_async(stream).await else {
+            return;
+        };
+
+        let handshake = json!({
+            "sid": "mock",
+            "upgrades": [],
+            "pingInterval": 25000,
+            "pingTimeout": 20000,
+            "maxPayload": 1000000,
+        });
+
+        if socket
+            .send(Message::text(format!("0{}", handshake)))
+            .await
+            .is_err()
+        {
+            return;
+        }
+
+        loop {
+            let responses = tokio::select! {
+                message = socket.next() => match message {
+                    Some(Ok(Message::Text(packet))) => Self::handle_packet(&state, &packet).await,
+                    Some(Ok(_)) => continue,
+                    _ => return,
+                },
+                Ok(event) = events.recv() => vec![event],
+            };
+
+            for response in responses {
+                if socket.send(Message::text(response)).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Answers a plain HTTP request, only the public status page API is supported.
+    async fn handle_http(mut stream: TcpStream, state: Arc<Mutex<State>>) {
+        let mut request = vec![];
+        let mut buffer = [0; 1024];
+        while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+            match stream.read(&mut buffer).await {
+                Ok(0) | Err(_) => return,
+                Ok(len) => request.extend_from_slice(&buffer[..len]),
+            }
+        }
+
+        let request = String::from_utf8_lossy(&request);
+        let head = request.split("\r\n\r\n").next().unwrap_or_default();
+        let path = head.split(' ').nth(1).unwrap_or_default();
+        let gzip = head.lines().any(|line| {
+            line.to_lowercase()
+                .strip_prefix("accept-encoding:")
+                .is_some_and(|encodings| encodings.contains("gzip"))
+        });
+
+        let mut state = state.lock().await;
+        state.http_requests.push(head.to_owned());
+        let status_page = path
+            .strip_prefix("/api/status-page/")
+            .and_then(|slug| state.status_pages.get(slug).map(|config| (slug, config)));
+
+        let (status, body) = match status_page {
+            Some((slug, config)) => (
+                "200 OK",
+                json!({
+                    "config": config,
+                    "incident": null,
+                    "publicGroupList": state.public_groups.get(slug).cloned().unwrap_or(json!([])),
+                    "maintenanceList": [],
+                }),
+            ),
+            None => ("404 Not Found", json!({"msg": "Not Found"})),
+        };
+        drop(state);
+
+        let (encoding, body) = match gzip {
+            true => {
+                let mut encoder = GzEncoder::new(vec![], Compression::default());
+                _ = encoder.write_all(body.to_string().as_bytes());
+                (
+                    "Content-Encoding: gzip\r\n",
+                    encoder.finish().unwrap_or_default(),
+                )
+            }
+            false => ("", body.to_string().into_bytes()),
+        };
+
+        let head = format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n",
+            status,
+            encoding,
+            body.len(),
+        );
+        _ = stream.write_all(head.as_bytes()).await;
+        _ = stream.write_all(&body).await;
+    }
+
+    async fn handle_packet(state: &Arc<Mutex<State>>, packet: &str) -> Vec<String> {
+        let event = |name: &str, payload: Value| format!("42{}", json!([name, payload]));
+
+        if packet == "2" {
+            return vec!["3".to_owned()];
+        }
+
+        if packet.starts_with("40") {
+            return vec![
+                format!("40{}", json!({"sid": "mock"})),
+                event("info", json!({"version": state.lock().await.version()})),
+            ];
+        }
+
+        let Some(packet) = packet.strip_prefix("42") else {
+            return vec![];
+        };
+
+        let data_start = packet
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(packet.len());
+        let ack = packet[..data_start].parse::<u64>().ok();
+
+        let Ok(mut data) = serde_json::from_str::<Vec<Value>>(&packet[data_start..]) else {
+            return vec![];
+        };
+
+        if data.is_empty() {
+            return vec![];
+        }
+
+        let method = data.remove(0);
+        let method = method.as_str().unwrap_or_default();
+        let mut state = state.lock().await;
+
+        if state.ignore(method, &data) {
+            return vec![];
+        }
+
+        let (response, events) = state.call(method, data);
+
+        ack.map(|ack| format!("43{}{}", ack, json!([response])))
+            .into_iter()
+            .chain(
+                events
+                    .into_iter()
+                    .map(|(name, payload)| event(name, payload)),
+            )
+            .collect()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fixtures, MockServer};
+    use crate::{
+        maintenance::Maintenance,
+        monitor::{Monitor, MonitorHttp},
+        status_page::{PublicGroup, StatusPage},
+        Client,
+    };
+    use serde_json::json;
+    use time::Time;
+
+    #[tokio::test]
+    async fn login_and_lists() {
+        let server = MockServer::start()
+            .await
+            .with_monitors(fixtures::monitor_list())
+            .await;
+
+        let client = Client::connect(server.config()).await.unwrap();
+
+        assert!(client.is_logged_in().await);
+        assert_eq!(client.get_auth_token().await, Some("mock-token".to_owned()));
+
+        let monitors = client.get_monitors().await.unwrap();
+        assert_eq!(monitors.len(), 2);
+        assert!(matches!(monitors.get("1"), Some(Monitor::Group { .. })));
+        assert!(matches!(monitors.get("2"), Some(Monitor::Http { .. })));
+
+        assert!(server
+            .calls()
+            .await
+            .iter()
+            .any(|(method, args)| method == "login" && args[0]["username"] == "admin"));
+    }
+
+    #[tokio::test]
+    async fn add_edit_delete_monitor() {
+        let server = MockServer::start().await;
+        let client = Client::connect(server.config()).await.unwrap();
+
+        let monitor = client
+            .add_monitor(MonitorHttp {
+                name: Some("Example".to_owned()),
+                url: Some("https://example.com".to_owned()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let id = monitor.common().id().unwrap();
+
+        let mut monitor = client.get_monitor(id).await.unwrap();
+        *monitor.common_mut().name_mut() = Some("Renamed".to_owned());
+        client.edit_monitor(monitor).await.unwrap();
+
+        assert_eq!(
+            client.get_monitor(id).await.unwrap().common().name(),
+            &Some("Renamed".to_owned())
+        );
+
+        client.delete_monitor(id).await.unwrap();
+        assert!(client.get_monitor(id).await.is_err());
+
+        let methods = server
+            .calls()
+            .await
+            .into_iter()
+            .map(|(method, _)| method)
+            .filter(|method| ["add", "editMonitor", "deleteMonitor"].contains(&method.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            methods,
+            vec!["add", "editMonitor", "editMonitor", "deleteMonitor"]
+        );
+    }
+
+    #[tokio::test]
+    async fn server_errors_are_returned() {
+        let server = MockServer::start().await;
+        server
+            .respond("deleteMonitor", json!({"ok": false, "msg": "Nope"}))
+            .await;
+
+        let client = Client::connect(server.config()).await.unwrap();
+
+        assert!(client.delete_monitor(1).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn maintenance_without_seconds() {
+        let server = MockServer::start()
+            .await
+            .with_maintenances(fixtures::maintenance_list())
+            .await;
+
+        let client = Client::connect(server.config()).await.unwrap();
+        let maintenances = client.get_maintenances().await.unwrap();
+
+        let Some(Maintenance::RecurringWeekday { schedule, .. }) = maintenances.get("1") else {
+            panic!(
+                "Expected a recurring-weekday maintenance, got {:?}",
+                maintenances
+            );
+        };
+
+        let time_range = schedule.time_range.clone().unwrap();
+        assert_eq!(time_range.start, Time::from_hms(2, 0, 0).unwrap());
+        assert_eq!(time_range.end, Time::from_hms(3, 0, 0).unwrap());
+    }
+
+    #[tokio::test]
+    async fn add_and_get_status_page() {
+        let server = MockServer::start().await;
+        let client = Client::connect(server.config()).await.unwrap();
+
+        client
+            .add_status_page(StatusPage {
+                slug: Some("main".to_owned()),
+                title: Some("Main".to_owned()),
+                public_group_list: Some(vec![PublicGroup {
+                    name: Some("Services".to_owned()),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let status_page = client.get_status_page("main").await.unwrap();
+        assert_eq!(status_page.title, Some("Main".to_owned()));
+
+        let groups = status_page.public_group_list.unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].id, Some(1));
+        assert_eq!(groups[0].weight, Some(1));
+
+        assert!(client.get_status_page("missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn status_pages_are_decompressed() {
+        let server = MockServer::start().await;
+        let client = Client::connect(server.config()).await.unwrap();
+
+        client
+            .add_status_page(StatusPage {
+                slug: Some("main".to_owned()),
+                title: Some("Main".to_owned()),
+                public_group_list: Some(vec![PublicGroup {
+                    name: Some("Services".to_owned()),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let status_page = client.get_status_page("main").await.unwrap();
+        assert_eq!(
+            status_page.public_group_list.unwrap()[0].name,
+            Some("Services".to_owned())
+        );
+
+        let requests = server.http_requests().await;
+        assert!(!requests.is_empty());
+        assert!(requests.iter().all(|request| request
+            .lines()
+            .any(
+                |line| line.to_lowercase().starts_with("accept-encoding:") && line.contains("gzip")
+            )));
+    }
+}